@@ -0,0 +1,112 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::config::OutputMode;
+
+/// 翻訳結果のキャッシュのキー
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheKey {
+    pub model: String,
+    pub output_mode: OutputMode,
+    /// 原文と、結果を左右する設定（翻訳先言語など）のハッシュ
+    pub text_hash: u64,
+}
+
+impl CacheKey {
+    /// variants には設定（Config::result_settings_key）など、同じ原文でも結果が変わるものを渡す
+    pub fn new(model: &str, output_mode: OutputMode, text: &str, variants: &[&str]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        variants.hash(&mut hasher);
+        Self {
+            model: model.to_string(),
+            output_mode,
+            text_hash: hasher.finish(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    key: CacheKey,
+    content: String,
+}
+
+/// 同時に翻訳が走った場合に読み書きが混ざらないようにする
+static CACHE_LOCK: Mutex<()> = Mutex::new(());
+
+fn cache_path() -> Result<PathBuf> {
+    let mut path = crate::config::config_dir()?;
+    path.push("translation_cache.json");
+    Ok(path)
+}
+
+/// 古い順（末尾が最近使ったもの）に並んだエントリを読み込む
+fn load_entries() -> Vec<CacheEntry> {
+    cache_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_entries(entries: &[CacheEntry]) -> Result<()> {
+    fs::write(cache_path()?, serde_json::to_string(entries)?)?;
+    Ok(())
+}
+
+/// キャッシュ済みの結果を返す
+/// ヒットのたびにファイル全体を書き直さないよう、削除が近い古い半分にある場合だけ末尾へ移す
+pub fn get(key: &CacheKey) -> Option<String> {
+    let _guard = crate::hotkey::lock_recovering(&CACHE_LOCK);
+    let mut entries = load_entries();
+    let index = entries.iter().position(|e| e.key == *key)?;
+    let content = entries[index].content.clone();
+
+    if index < entries.len() / 2 {
+        let entry = entries.remove(index);
+        entries.push(entry);
+        if let Err(e) = save_entries(&entries) {
+            eprintln!("Failed to update translation cache: {}", e);
+        }
+    }
+
+    Some(content)
+}
+
+/// 結果を保存し、capacity 件を超えた場合は最も長く使われていないものから削除する
+/// capacity が0の場合は保存しない（失敗しても翻訳には影響させない）
+pub fn put(key: CacheKey, content: &str, capacity: usize) {
+    if capacity == 0 {
+        return;
+    }
+
+    let _guard = crate::hotkey::lock_recovering(&CACHE_LOCK);
+    let mut entries = load_entries();
+    entries.retain(|e| e.key != key);
+    entries.push(CacheEntry {
+        key,
+        content: content.to_string(),
+    });
+    if entries.len() > capacity {
+        entries.drain(..entries.len() - capacity);
+    }
+
+    if let Err(e) = save_entries(&entries) {
+        eprintln!("Failed to save translation cache: {}", e);
+    }
+}
+
+/// キャッシュをすべて削除
+pub fn clear() -> Result<()> {
+    let path = cache_path()?;
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
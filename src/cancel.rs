@@ -0,0 +1,53 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 「すべて中止」が実行された回数（世代）
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// キャンセル確認のポーリング間隔
+const CANCEL_POLL_INTERVAL_MS: u64 = 100;
+
+/// 翻訳タスク・結果ウィンドウ用のキャンセルトークン
+/// 作成時点の世代を記録し、以降に cancel_all が呼ばれるとキャンセル扱いになる
+#[derive(Debug, Clone, Copy)]
+pub struct CancelToken {
+    generation: u64,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self {
+            generation: GENERATION.load(Ordering::SeqCst),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        GENERATION.load(Ordering::SeqCst) != self.generation
+    }
+
+    /// キャンセルされるまで待機する（tokio::select! で処理と競合させる用）
+    pub async fn cancelled(self) {
+        while !self.is_cancelled() {
+            tokio::time::sleep(Duration::from_millis(CANCEL_POLL_INTERVAL_MS)).await;
+        }
+    }
+}
+
+impl Default for CancelToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 実行中の翻訳と結果ウィンドウをすべてキャンセル
+pub fn cancel_all() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// フラグが立つまで待機する（1件の翻訳だけを中止する用）
+pub async fn flagged(flag: Arc<AtomicBool>) {
+    while !flag.load(Ordering::SeqCst) {
+        tokio::time::sleep(Duration::from_millis(CANCEL_POLL_INTERVAL_MS)).await;
+    }
+}
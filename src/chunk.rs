@@ -0,0 +1,125 @@
+/// 文の終わりとみなす文字
+const SENTENCE_ENDS: &[char] = &['。', '．', '！', '？', '.', '!', '?'];
+
+fn char_len(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// 長いテキストを max_chars 文字以下のまとまりに分割する（0の場合は分割しない）
+/// 段落（空行）単位でまとめ、1段落が長すぎる場合は行・文・文字数の順で細かく分ける
+pub fn split(text: &str, max_chars: usize) -> Vec<String> {
+    if max_chars == 0 || char_len(text) <= max_chars {
+        return vec![text.to_string()];
+    }
+
+    let text = text.replace("\r\n", "\n");
+    split_with(&text, max_chars, &["\n\n", "\n"])
+        .into_iter()
+        .filter(|chunk| !chunk.trim().is_empty())
+        .collect()
+}
+
+/// 区切り文字で分けた部分を、上限を超えない範囲でつなげ直す
+fn split_with(text: &str, max_chars: usize, separators: &[&str]) -> Vec<String> {
+    let Some((separator, finer)) = separators.split_first() else {
+        return split_sentences(text, max_chars);
+    };
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for part in text.split(separator) {
+        // 1つの部分だけで上限を超える場合は、より細かい単位で分ける
+        if char_len(part) > max_chars {
+            if !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
+            }
+            chunks.extend(split_with(part, max_chars, finer));
+            continue;
+        }
+
+        if !current.is_empty()
+            && char_len(&current) + char_len(separator) + char_len(part) > max_chars
+        {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str(separator);
+        }
+        current.push_str(part);
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// 文の終わりで分け、それでも長い文は文字数で区切る
+fn split_sentences(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+
+    for sentence in text.split_inclusive(SENTENCE_ENDS) {
+        let len = char_len(sentence);
+        if current_len + len > max_chars && !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+
+        if len > max_chars {
+            let chars: Vec<char> = sentence.chars().collect();
+            for piece in chars.chunks(max_chars) {
+                chunks.push(piece.iter().collect());
+            }
+            continue;
+        }
+
+        current.push_str(sentence);
+        current_len += len;
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_text_is_not_split() {
+        assert_eq!(split("短い文章", 10), vec!["短い文章"]);
+        assert_eq!(split("上限0は分割しない", 0), vec!["上限0は分割しない"]);
+    }
+
+    #[test]
+    fn paragraphs_are_joined_up_to_the_limit() {
+        // 区切りを含めてちょうど上限に収まる段落はまとめ、超える段落は次のまとまりにする
+        assert_eq!(
+            split("aaaa\n\nbbbb\n\ncccc", 10),
+            vec!["aaaa\n\nbbbb", "cccc"]
+        );
+        assert_eq!(split("aaaa\r\nbbbb\r\ncccc", 9), vec!["aaaa\nbbbb", "cccc"]);
+    }
+
+    #[test]
+    fn long_sentence_is_cut_by_length() {
+        assert_eq!(split("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+    }
+
+    #[test]
+    fn multibyte_text_is_split_on_char_boundaries() {
+        assert_eq!(
+            split("あいうえお。かきくけこ。", 6),
+            vec!["あいうえお。", "かきくけこ。"]
+        );
+        assert_eq!(
+            split("あいうえおかきく", 3),
+            vec!["あいう", "えおか", "きく"]
+        );
+    }
+}
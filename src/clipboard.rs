@@ -1,6 +1,108 @@
 use anyhow::{Context, Result};
-use clipboard_win::{formats, get_clipboard};
+use clipboard_win::{formats, get_clipboard, set_clipboard};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use windows::ApplicationModel::DataTransfer::{Clipboard, StandardDataFormats};
 
-pub fn get_text() -> Result<String> {
+use crate::config::ClipboardBackend;
+
+/// 設定された方式でクリップボードのテキストを取得
+pub fn get_text(backend: ClipboardBackend) -> Result<String> {
+    match backend {
+        ClipboardBackend::Win32 => get_text_win32(),
+        ClipboardBackend::WinRt => get_text_winrt().or_else(|e| {
+            // WinRT が使えない環境では従来の方式で読み直す
+            eprintln!("WinRT clipboard failed, falling back to Win32: {}", e);
+            get_text_win32()
+        }),
+    }
+}
+
+/// このアプリが最後にクリップボードへ書き込んだテキストのハッシュ
+static LAST_WRITTEN: Mutex<Option<u64>> = Mutex::new(None);
+
+fn hash_text(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// クリップボードにテキストを書き込む
+pub fn set_text(text: &str) -> Result<()> {
+    set_clipboard(formats::Unicode, text).context("Failed to write clipboard")?;
+    *crate::hotkey::lock_recovering(&LAST_WRITTEN) = Some(hash_text(text));
+    Ok(())
+}
+
+/// 翻訳結果で上書きする前のクリップボードの内容（「元に戻す」用）
+static REPLACED: Mutex<Option<String>> = Mutex::new(None);
+
+/// 元の内容を「元に戻す」で復元できるよう保存してから、クリップボードを上書きする
+/// 元の内容を読めなかった場合や上書きする内容と同じ場合は、以前の翻訳で保存した内容も破棄する
+pub fn replace_text(text: &str, backend: ClipboardBackend) -> Result<()> {
+    let previous = get_text(backend).ok().filter(|t| !t.is_empty() && t != text);
+    set_text(text)?;
+    *crate::hotkey::lock_recovering(&REPLACED) = previous;
+    Ok(())
+}
+
+/// 上書きする前の内容を復元できるか
+pub fn can_restore() -> bool {
+    crate::hotkey::lock_recovering(&REPLACED).is_some()
+}
+
+/// 上書きする前の内容に戻す（戻す内容がない場合は false）
+pub fn restore_replaced() -> Result<bool> {
+    let Some(previous) = crate::hotkey::lock_recovering(&REPLACED).take() else {
+        return Ok(false);
+    };
+    // 自分の書き込みとして記録し、自動翻訳で元の内容を翻訳し直さないようにする
+    set_text(&previous)?;
+    Ok(true)
+}
+
+/// パスワードマネージャーなどが、監視・履歴の対象外として印を付けた内容か
+/// （ExcludeClipboardContentFromMonitorProcessing 形式がある、または CanIncludeInClipboardHistory が0）
+pub fn is_excluded_from_monitoring() -> bool {
+    let available = |name: &str| {
+        clipboard_win::register_format(name)
+            .map(|format| format.get())
+            .filter(|format| clipboard_win::is_format_avail(*format))
+    };
+
+    if available("ExcludeClipboardContentFromMonitorProcessing").is_some() {
+        return true;
+    }
+
+    available("CanIncludeInClipboardHistory").is_some_and(|format| {
+        get_clipboard::<Vec<u8>, _>(formats::RawData(format))
+            .is_ok_and(|data| data.get(..4) == Some(&[0, 0, 0, 0]))
+    })
+}
+
+/// このアプリ自身が最後に書き込んだテキストか（自動翻訳で結果を翻訳し直さないため）
+pub fn is_own_write(text: &str) -> bool {
+    *crate::hotkey::lock_recovering(&LAST_WRITTEN) == Some(hash_text(text))
+}
+
+fn get_text_win32() -> Result<String> {
     get_clipboard(formats::Unicode).context("Failed to read clipboard")
 }
+
+/// WinRT の Clipboard API で取得（遅延レンダリング・非同期形式に対応）
+fn get_text_winrt() -> Result<String> {
+    let content = Clipboard::GetContent().context("Failed to open WinRT clipboard")?;
+
+    if !content.Contains(&StandardDataFormats::Text()?)? {
+        return Ok(String::new());
+    }
+
+    // 遅延レンダリングの場合は提供元アプリがデータを生成するまで待つ
+    let text = content
+        .GetTextAsync()?
+        .get()
+        .context("Failed to read clipboard text")?;
+
+    Ok(text.to_string_lossy())
+}
@@ -0,0 +1,153 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use windows::core::w;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::DataExchange::{
+    AddClipboardFormatListener, RemoveClipboardFormatListener,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetMessageW,
+    PostQuitMessage, PostThreadMessageW, RegisterClassW, HWND_MESSAGE, MSG, WINDOW_EX_STYLE,
+    WINDOW_STYLE, WM_CLIPBOARDUPDATE, WM_QUIT, WNDCLASSW,
+};
+
+use crate::hotkey::lock_recovering;
+
+/// 連続した変更をまとめる待ち時間（最後の変更からこの時間が経ってから翻訳する）
+const DEBOUNCE_MS: u64 = 300;
+
+/// 最後にクリップボードが変更された時刻（未処理の変更がない場合はNone）
+static LAST_CHANGE: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// 監視を開始済みか（設定の変更で開始・停止するため）
+static LISTENING: AtomicBool = AtomicBool::new(false);
+
+/// 監視スレッドのID（停止を知らせる先、メッセージループに入る前は0）
+static LISTENER_THREAD: AtomicU32 = AtomicU32::new(0);
+
+/// ウィンドウクラスを登録済みか（監視を再開した場合に登録し直さないため）
+static CLASS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// クリップボード監視用の非表示ウィンドウのプロシージャ
+unsafe extern "system" fn window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_CLIPBOARDUPDATE {
+        // パスワードなど監視の対象外とされた内容は、直前の未処理の変更ごと翻訳しない
+        let excluded = crate::clipboard::is_excluded_from_monitoring();
+        *lock_recovering(&LAST_CHANGE) = (!excluded).then(Instant::now);
+        return LRESULT(0);
+    }
+
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+/// 専用スレッドでクリップボードの変更監視を開始する（監視中の場合は何もしない）
+pub fn spawn_listener() {
+    if LISTENING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    thread::spawn(|| {
+        if let Err(e) = start_listener() {
+            LISTENING.store(false, Ordering::SeqCst);
+            eprintln!("Failed to start clipboard listener: {}", e);
+            crate::notification::show_error("エラー", "クリップボードの監視の開始に失敗しました");
+        }
+    });
+}
+
+/// クリップボードの変更監視を止め、未処理の変更を破棄する
+pub fn stop_listener() {
+    LISTENING.store(false, Ordering::SeqCst);
+    let thread_id = LISTENER_THREAD.swap(0, Ordering::SeqCst);
+    if thread_id != 0 {
+        unsafe {
+            let _ = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+        }
+    }
+    *lock_recovering(&LAST_CHANGE) = None;
+}
+
+/// クリップボードの変更監視を開始（メッセージループを回すため専用スレッドから呼ぶ）
+fn start_listener() -> windows::core::Result<()> {
+    unsafe {
+        let instance = GetModuleHandleW(None)?;
+        let class_name = w!("ClipboardTranslator_ClipboardListener");
+
+        if !CLASS_REGISTERED.load(Ordering::SeqCst) {
+            let window_class = WNDCLASSW {
+                lpfnWndProc: Some(window_proc),
+                hInstance: instance.into(),
+                lpszClassName: class_name,
+                ..Default::default()
+            };
+            if RegisterClassW(&window_class) == 0 {
+                return Err(windows::core::Error::from_win32());
+            }
+            CLASS_REGISTERED.store(true, Ordering::SeqCst);
+        }
+
+        // メッセージ専用ウィンドウ（画面には表示されない）
+        let hwnd = CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            class_name,
+            w!(""),
+            WINDOW_STYLE::default(),
+            0,
+            0,
+            0,
+            0,
+            HWND_MESSAGE,
+            None,
+            instance,
+            None,
+        );
+        if hwnd.0 == 0 {
+            return Err(windows::core::Error::from_win32());
+        }
+
+        if let Err(e) = AddClipboardFormatListener(hwnd) {
+            let _ = DestroyWindow(hwnd);
+            return Err(e);
+        }
+
+        // 停止の知らせを受け取れるよう、メッセージキューができてからスレッドIDを公開する
+        LISTENER_THREAD.store(GetCurrentThreadId(), Ordering::SeqCst);
+        // 開始までの間に停止された場合は、すぐにループを抜ける
+        if !LISTENING.load(Ordering::SeqCst) {
+            PostQuitMessage(0);
+        }
+
+        // メッセージループ
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+            let _ = DispatchMessageW(&msg);
+        }
+
+        // クリーンアップ
+        let _ = RemoveClipboardFormatListener(hwnd);
+        let _ = DestroyWindow(hwnd);
+    }
+
+    Ok(())
+}
+
+/// クリップボードが変更され、その後 DEBOUNCE_MS 以上変更がなければtrue（メインスレッドから呼ぶ）
+pub fn check_changed() -> bool {
+    let mut last_change = lock_recovering(&LAST_CHANGE);
+    match *last_change {
+        Some(at) if at.elapsed() >= Duration::from_millis(DEBOUNCE_MS) => {
+            *last_change = None;
+            true
+        }
+        _ => false,
+    }
+}
@@ -1,10 +1,15 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 pub const DEFAULT_MODEL: &str = "gemini-2.0-flash";
 
+/// カスタムプロンプトの初期値（{text} が原文に置き換わる）
+pub const DEFAULT_CUSTOM_PROMPT: &str = "以下のテキストを翻訳してください。\n\n{text}";
+
 // APIから取得できない場合のフォールバック用
 pub const FALLBACK_MODELS: &[&str] = &[
     "gemini-2.0-flash",
@@ -18,6 +23,11 @@ pub enum OutputMode {
     #[default]
     Detailed,
     Concise,
+    Simple,
+    /// 単語の意味・品詞・例文を辞書のように表示する
+    Dictionary,
+    /// 設定画面で入力したプロンプトを使う
+    Custom,
 }
 
 impl OutputMode {
@@ -25,11 +35,264 @@ impl OutputMode {
         match self {
             OutputMode::Detailed => "詳細（言語判定・翻訳・スラング解説・要約）",
             OutputMode::Concise => "簡潔（5行以内で要点のみ）",
+            OutputMode::Simple => "わかりやすく（翻訳＋平易な言葉での解説）",
+            OutputMode::Dictionary => "辞書（単語の意味・品詞・例文）",
+            OutputMode::Custom => "カスタム（独自のプロンプト）",
         }
     }
 
     pub fn all() -> &'static [OutputMode] {
-        &[OutputMode::Detailed, OutputMode::Concise]
+        &[
+            OutputMode::Detailed,
+            OutputMode::Concise,
+            OutputMode::Simple,
+            OutputMode::Dictionary,
+            OutputMode::Custom,
+        ]
+    }
+
+    /// ボタン等に表示する短い名前
+    pub fn short_label(&self) -> &'static str {
+        match self {
+            OutputMode::Detailed => "詳細",
+            OutputMode::Concise => "簡潔",
+            OutputMode::Simple => "わかりやすく",
+            OutputMode::Dictionary => "辞書",
+            OutputMode::Custom => "カスタム",
+        }
+    }
+}
+
+/// 「わかりやすく」モードの解説の難易度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ReadingLevel {
+    Child,
+    #[default]
+    General,
+    Expert,
+}
+
+impl ReadingLevel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReadingLevel::Child => "子ども向け",
+            ReadingLevel::General => "一般向け",
+            ReadingLevel::Expert => "専門家向け",
+        }
+    }
+
+    /// プロンプトに埋め込む読者像
+    pub fn audience(&self) -> &'static str {
+        match self {
+            ReadingLevel::Child => "小学生でも理解できるよう、身近なたとえを使い、難しい言葉を避けて",
+            ReadingLevel::General => "専門知識のない一般の読者が理解できるよう、専門用語は言い換えて",
+            ReadingLevel::Expert => "専門家向けに、正確さを保ちつつ論点を整理して簡潔に",
+        }
+    }
+
+    pub fn all() -> &'static [ReadingLevel] {
+        &[ReadingLevel::Child, ReadingLevel::General, ReadingLevel::Expert]
+    }
+}
+
+/// 翻訳エラーの通知方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ErrorDisplay {
+    /// 結果ウィンドウ内にのみ表示
+    Window,
+    /// トースト通知のみ（結果ウィンドウは閉じる）
+    Toast,
+    #[default]
+    Both,
+}
+
+impl ErrorDisplay {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ErrorDisplay::Window => "ウィンドウのみ",
+            ErrorDisplay::Toast => "トースト通知のみ",
+            ErrorDisplay::Both => "ウィンドウとトースト通知",
+        }
+    }
+
+    pub fn shows_window(&self) -> bool {
+        matches!(self, ErrorDisplay::Window | ErrorDisplay::Both)
+    }
+
+    pub fn shows_toast(&self) -> bool {
+        matches!(self, ErrorDisplay::Toast | ErrorDisplay::Both)
+    }
+
+    pub fn all() -> &'static [ErrorDisplay] {
+        &[ErrorDisplay::Window, ErrorDisplay::Toast, ErrorDisplay::Both]
+    }
+}
+
+/// 翻訳に使うAPI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum Provider {
+    #[default]
+    Gemini,
+    /// OpenAI、またはOpenAI互換の /chat/completions API
+    OpenAi,
+    /// DeepL API（翻訳のみ。解説は出せないため簡潔モード専用）
+    DeepL,
+}
+
+impl Provider {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Provider::Gemini => "Google Gemini",
+            Provider::OpenAi => "OpenAI（互換APIを含む）",
+            Provider::DeepL => "DeepL（翻訳のみ）",
+        }
+    }
+
+    pub fn all() -> &'static [Provider] {
+        &[Provider::Gemini, Provider::OpenAi, Provider::DeepL]
+    }
+
+    /// APIキーの保存先の名前に使う識別子
+    pub fn credential_name(&self) -> &'static str {
+        match self {
+            Provider::Gemini => "gemini",
+            Provider::OpenAi => "openai",
+            Provider::DeepL => "deepl",
+        }
+    }
+
+    /// 解説付きの出力モードを使えるか（DeepLは訳文しか返さない）
+    pub fn supports_explanation(&self) -> bool {
+        *self != Provider::DeepL
+    }
+}
+
+/// DeepLの翻訳先言語（言語コード, 表示名）
+pub const DEEPL_TARGET_LANGUAGES: &[(&str, &str)] = &[
+    ("JA", "日本語"),
+    ("EN-US", "英語（アメリカ）"),
+    ("EN-GB", "英語（イギリス）"),
+    ("ZH-HANS", "中国語（簡体字）"),
+    ("ZH-HANT", "中国語（繁体字）"),
+    ("KO", "韓国語"),
+    ("DE", "ドイツ語"),
+    ("FR", "フランス語"),
+    ("ES", "スペイン語"),
+    ("IT", "イタリア語"),
+    ("PT-BR", "ポルトガル語（ブラジル）"),
+    ("RU", "ロシア語"),
+];
+
+/// ウィンドウの配色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum Theme {
+    /// Windowsのアプリモード設定に合わせる
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Theme::System => "システム設定に合わせる",
+            Theme::Light => "ライト",
+            Theme::Dark => "ダーク",
+        }
+    }
+
+    pub fn all() -> &'static [Theme] {
+        &[Theme::System, Theme::Light, Theme::Dark]
+    }
+}
+
+/// URL・ファイルパス・コードをコピーした場合の扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum SpecialContentAction {
+    /// 通常の文章と同じく翻訳する
+    #[default]
+    Translate,
+    /// 翻訳せずに通知だけ出す
+    Skip,
+    /// コードは解説し、URL・ファイルパスは翻訳しない
+    ExplainCode,
+}
+
+impl SpecialContentAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SpecialContentAction::Translate => "通常どおり翻訳する",
+            SpecialContentAction::Skip => "翻訳しない",
+            SpecialContentAction::ExplainCode => "コードは解説し、URL・パスは翻訳しない",
+        }
+    }
+
+    pub fn all() -> &'static [SpecialContentAction] {
+        &[
+            SpecialContentAction::Translate,
+            SpecialContentAction::Skip,
+            SpecialContentAction::ExplainCode,
+        ]
+    }
+}
+
+/// コードを解説する場合のプロンプト（{text} にコードが入る）
+/// 解説は翻訳先の言語で書かせる（空の場合は既定の日本語）
+pub fn code_explanation_prompt(target_language: &str) -> String {
+    let target_language = match target_language.trim() {
+        "" => crate::language::DEFAULT_TARGET_LANGUAGE,
+        target => target,
+    };
+    format!(
+        "以下のコードが何をしているかを{}で簡潔に解説してください。コメントや文字列に外国語があれば、その意味も添えてください。\n\n```\n{{text}}\n```",
+        target_language
+    )
+}
+
+/// 自動起動の登録方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum StartupMethod {
+    /// レジストリの Run キー（管理者権限不要）
+    #[default]
+    Registry,
+    /// タスクスケジューラ（最上位の特権で起動。管理者のウィンドウでもキー入力を検知できる）
+    TaskScheduler,
+}
+
+impl StartupMethod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StartupMethod::Registry => "通常（レジストリ）",
+            StartupMethod::TaskScheduler => "管理者権限（タスクスケジューラ）",
+        }
+    }
+
+    pub fn all() -> &'static [StartupMethod] {
+        &[StartupMethod::Registry, StartupMethod::TaskScheduler]
+    }
+}
+
+/// クリップボードの読み取り方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ClipboardBackend {
+    /// clipboard-win（軽量・既定）
+    #[default]
+    Win32,
+    /// Windows Runtime の Clipboard API（遅延レンダリング対応）
+    WinRt,
+}
+
+impl ClipboardBackend {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ClipboardBackend::Win32 => "標準（軽量）",
+            ClipboardBackend::WinRt => "WinRT（遅延レンダリング対応）",
+        }
+    }
+
+    pub fn all() -> &'static [ClipboardBackend] {
+        &[ClipboardBackend::Win32, ClipboardBackend::WinRt]
     }
 }
 
@@ -41,6 +304,9 @@ pub struct Hotkey {
     pub key_code: i32, // Windows VK code
     #[serde(default)]
     pub is_double_press: bool, // ダブルプレス検出（例: Ctrl+C+C）
+    /// マウスのサイドボタン（1: 戻る / XBUTTON1、2: 進む / XBUTTON2）。指定時はキーの代わりに使う
+    #[serde(default)]
+    pub mouse_button: Option<u32>,
 }
 
 impl Default for Hotkey {
@@ -52,11 +318,29 @@ impl Default for Hotkey {
             shift: false,
             key_code: 0x43, // VK_C
             is_double_press: true,
+            mouse_button: None,
         }
     }
 }
 
 impl Hotkey {
+    /// キーコードと修飾キーの状態が一致するか
+    pub fn matches(&self, key_code: i32, ctrl: bool, alt: bool, shift: bool) -> bool {
+        self.mouse_button.is_none()
+            && self.key_code == key_code
+            && self.ctrl == ctrl
+            && self.alt == alt
+            && self.shift == shift
+    }
+
+    /// マウスのサイドボタンと修飾キーの状態が一致するか
+    pub fn matches_mouse(&self, button: u32, ctrl: bool, alt: bool, shift: bool) -> bool {
+        self.mouse_button == Some(button)
+            && self.ctrl == ctrl
+            && self.alt == alt
+            && self.shift == shift
+    }
+
     pub fn to_string(&self) -> String {
         let mut parts = Vec::new();
         if self.ctrl {
@@ -70,16 +354,25 @@ impl Hotkey {
         }
 
         // キーコードを文字に変換（簡易版）
-        let key_name = match self.key_code {
-            0x41..=0x5A => {
+        let key_name = match (self.mouse_button, self.key_code) {
+            (Some(1), _) => "マウス戻る".to_string(),
+            (Some(2), _) => "マウス進む".to_string(),
+            (Some(button), _) => format!("Mouse{}", button),
+            (None, 0x41..=0x5A) => {
                 // A-Z
                 char::from_u32(self.key_code as u32).unwrap_or('?').to_string()
             }
-            0x30..=0x39 => {
+            (None, 0x30..=0x39) => {
                 // 0-9
                 char::from_u32(self.key_code as u32).unwrap_or('?').to_string()
             }
-            _ => format!("Key{:X}", self.key_code),
+            (None, 0x70..=0x7B) => {
+                // F1-F12
+                format!("F{}", self.key_code - 0x6F)
+            }
+            (None, code) => oem_key_name(code)
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("Key{:X}", code)),
         };
 
         parts.push(&key_name);
@@ -93,6 +386,121 @@ impl Hotkey {
             result
         }
     }
+
+    /// よく使われるショートカット（コピー・貼り付けなど）と重なる場合はその名前を返す
+    /// ダブルプレスなら1回目の押下は通常どおり動くため重ならない扱いにする
+    pub fn conflicts_with_system(&self) -> Option<&'static str> {
+        if self.is_double_press || self.mouse_button.is_some() {
+            return None;
+        }
+
+        match (self.ctrl, self.alt, self.shift, self.key_code) {
+            (true, false, false, 0x43) => Some("コピー（Ctrl+C）"),
+            (true, false, false, 0x56) => Some("貼り付け（Ctrl+V）"),
+            (true, false, false, 0x58) => Some("切り取り（Ctrl+X）"),
+            (true, false, false, 0x5A) => Some("元に戻す（Ctrl+Z）"),
+            (true, false, false, 0x59) => Some("やり直し（Ctrl+Y）"),
+            (true, false, false, 0x41) => Some("すべて選択（Ctrl+A）"),
+            (true, false, false, 0x53) => Some("保存（Ctrl+S）"),
+            (false, true, false, 0x73) => Some("ウィンドウを閉じる（Alt+F4）"),
+            (false, true, false, 0x09) => Some("ウィンドウの切り替え（Alt+Tab）"),
+            _ => None,
+        }
+    }
+}
+
+/// 記号キー（VK_OEM_*、US配列）の表示名
+fn oem_key_name(key_code: i32) -> Option<&'static str> {
+    match key_code {
+        0xBA => Some(";"),
+        0xBB => Some("="),
+        0xBC => Some(","),
+        0xBD => Some("-"),
+        0xBE => Some("."),
+        0xBF => Some("/"),
+        0xC0 => Some("`"),
+        0xDB => Some("["),
+        0xDC => Some("\\"),
+        0xDD => Some("]"),
+        0xDE => Some("'"),
+        _ => None,
+    }
+}
+
+/// 双方向翻訳の言語ペア（aの入力はbへ、bの入力はaへ翻訳）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguagePair {
+    pub a: String,
+    pub b: String,
+}
+
+impl Default for LanguagePair {
+    fn default() -> Self {
+        Self {
+            a: "日本語".to_string(),
+            b: "英語".to_string(),
+        }
+    }
+}
+
+/// 結果の先頭・末尾から取り除く定型文のルール（大文字小文字・前後の装飾を無視した行全体の一致）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisclaimerRule {
+    pub pattern: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl DisclaimerRule {
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            enabled: true,
+        }
+    }
+}
+
+/// 既定の定型文ルール
+pub fn default_disclaimer_rules() -> Vec<DisclaimerRule> {
+    vec![
+        DisclaimerRule::new("AIによる翻訳のため、正確でない場合があります。"),
+        DisclaimerRule::new("重要な内容は専門家にご確認ください。"),
+        DisclaimerRule::new(
+            "As an AI language model, I cannot guarantee the accuracy of this translation.",
+        ),
+    ]
+}
+
+/// 翻訳結果の送信先アプリ（タイトル・プロセス名のうち指定したものがすべて一致するウィンドウ）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SendToApp {
+    /// ウィンドウタイトルの一部（大文字小文字を区別しない）
+    #[serde(default)]
+    pub window_title: String,
+    /// 実行ファイル名（例: "notepad.exe"）
+    #[serde(default)]
+    pub process_name: String,
+    /// 起動していない場合に実行するコマンド（空の場合は通知のみ）
+    #[serde(default)]
+    pub launch_command: String,
+}
+
+/// コピー元のアプリごとの出力モード・プロンプトの上書き
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppRule {
+    pub output_mode: OutputMode,
+    /// カスタムモードで使うプロンプト（Noneの場合は基本設定のプロンプト）
+    #[serde(default)]
+    pub custom_prompt: Option<String>,
+}
+
+/// 言語ごとの追加フォント設定
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FontSetting {
+    /// 対象の言語・文字体系（例: "韓国語", "タイ語"）
+    pub language: String,
+    /// フォントファイルのパス
+    pub path: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -101,68 +509,604 @@ pub struct Config {
     pub api_key: String,
     #[serde(default = "default_model")]
     pub model: String,
+    /// モデル一覧の取得時に高速・低コストなモデルを自動で選ぶ
+    /// （既存の設定ファイルでは選択済みのモデルを維持するため既定はfalse）
+    #[serde(default)]
+    pub auto_model: bool,
+    /// モデル一覧の先頭に表示するお気に入りのモデルID
+    #[serde(default)]
+    pub favorite_models: Vec<String>,
     #[serde(default)]
     pub output_mode: OutputMode,
+    /// 1語だけコピーした場合は、出力モードに関わらず辞書モードで表示する
+    #[serde(default)]
+    pub auto_dictionary: bool,
+    /// 翻訳先の言語（原文がこの言語の場合は英語へ翻訳）
+    #[serde(default = "default_target_language")]
+    pub target_language: String,
+    /// カスタムモードのプロンプト（{text} に原文が入る）
+    #[serde(default)]
+    pub custom_prompt: Option<String>,
+    /// 生成の温度（0.0〜2.0、Noneでサーバー側の既定値）
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// 最大出力トークン数（Noneでサーバー側の既定値）
+    #[serde(default)]
+    pub max_output_tokens: Option<u32>,
     #[serde(default)]
     pub hotkey: Hotkey,
+    /// 入力が翻訳先言語の場合、直前の原文の言語へ翻訳し返す
+    #[serde(default)]
+    pub smart_swap: bool,
+    /// 原文がすでに翻訳先の言語の場合（文字体系から判定）、APIを呼ばずにそのまま表示する
+    #[serde(default)]
+    pub skip_same_language: bool,
+    /// 結果をこの行数で折りたたんで表示（0で無効）
+    #[serde(default)]
+    pub collapse_after_lines: usize,
+    /// メイリオで表示できない言語用の追加フォント（記載順にフォールバック）
+    #[serde(default)]
+    pub extra_fonts: Vec<FontSetting>,
+    /// 毎回のリクエストに付ける共有コンテキスト（用語集・例文・指示など）
+    #[serde(default)]
+    pub shared_context: String,
+    /// 共有コンテキストを cachedContent として保持する秒数（0でキャッシュしない）
+    #[serde(default = "default_context_cache_ttl_secs")]
+    pub context_cache_ttl_secs: u64,
+    /// 「わかりやすく」モードの解説の難易度
+    #[serde(default)]
+    pub reading_level: ReadingLevel,
+    /// Markdown文書の入力時に構造を保ったまま翻訳する
+    #[serde(default = "default_true")]
+    pub preserve_markdown: bool,
+    /// 詳細モードで訳文の読み方（ひらがな・ローマ字）を付ける（翻訳先が日本語の場合のみ）
+    #[serde(default)]
+    pub include_reading: bool,
+    /// 実行中の翻訳をすべて中止して状態をリセットするホットキー
+    #[serde(default)]
+    pub reset_hotkey: Option<Hotkey>,
+    /// 翻訳の一時停止・再開を切り替えるホットキー
+    #[serde(default)]
+    pub pause_hotkey: Option<Hotkey>,
+    /// 双方向翻訳の言語ペア（Noneの場合は日本語⇔英語の自動判定）
+    #[serde(default)]
+    pub language_pair: Option<LanguagePair>,
+    /// 結果の末尾から取り除く定型文（免責文など）のルール
+    #[serde(default = "default_disclaimer_rules")]
+    pub disclaimer_rules: Vec<DisclaimerRule>,
+    /// 翻訳エラーの通知方法
+    #[serde(default)]
+    pub error_display: ErrorDisplay,
+    /// URL・ファイルパス・コードをコピーした場合の扱い
+    #[serde(default)]
+    pub special_content: SpecialContentAction,
+    /// 結果ウィンドウを表示するときにフォーカスを移す（falseで作業中のアプリの入力を妨げない）
+    #[serde(default = "default_true")]
+    pub focus_result_window: bool,
+    /// 結果ウィンドウを常に最前面に表示する
+    #[serde(default = "default_true")]
+    pub always_on_top: bool,
+    /// マウスが乗るまで結果ウィンドウのクリックを背後のウィンドウへ透過する
+    #[serde(default)]
+    pub click_through_until_hover: bool,
+    /// APIリクエストに付ける追加HTTPヘッダー（社内ゲートウェイの認証ヘッダーなど）
+    #[serde(default)]
+    pub extra_headers: BTreeMap<String, String>,
+    /// 翻訳中にホットキーが押されたときの「翻訳実行中です」通知の再表示間隔（秒）
+    /// 0の場合は翻訳1回につき1度だけ通知する
+    #[serde(default)]
+    pub busy_notice_interval_secs: u64,
+    /// 翻訳中にホットキーが押された場合、無視せずに順番待ちにして前の翻訳の後で翻訳する
+    #[serde(default)]
+    pub queue_requests: bool,
+    /// クリップボードの読み取り方式
+    #[serde(default)]
+    pub clipboard_backend: ClipboardBackend,
+    /// まず簡潔な翻訳を小さなツールチップで表示し、クリックで詳細を表示する
+    #[serde(default)]
+    pub preview_first: bool,
+    /// 結果ウィンドウを1つだけ開いたままにし、次の翻訳はそのウィンドウに表示する
+    #[serde(default)]
+    pub reuse_window: bool,
+    /// 翻訳結果を貼り付ける送信先アプリ（Noneの場合は送信しない）
+    #[serde(default)]
+    pub send_to_app: Option<SendToApp>,
+    /// ボタン・選択範囲・リンクのアクセントカラー（sRGB、Noneで既定の配色）
+    #[serde(default)]
+    pub accent_color: Option<[u8; 3]>,
+    /// ウィンドウの配色（ライト・ダーク）
+    #[serde(default)]
+    pub theme: Theme,
+    /// 自動起動の登録方法
+    #[serde(default)]
+    pub startup_method: StartupMethod,
+    /// 本文の文字サイズ（見出し・ボタンなども同じ比率で拡大縮小する）
+    #[serde(default = "default_font_size")]
+    pub font_size: f32,
+    /// 簡潔モードの結果から引用符・Markdownの装飾を取り除き、プレーンテキストにする
+    #[serde(default)]
+    pub plain_concise: bool,
+    /// 言語判定の信頼度（%）がこれ未満の場合に原文の言語を確認する（0で無効）
+    #[serde(default)]
+    pub min_detection_confidence: u8,
+    /// 原文の言語の指定（判定を誤った場合の再翻訳用、保存しない）
+    #[serde(skip)]
+    pub pinned_source_language: Option<String>,
+    /// 原文の言語（Noneの場合は自動検出）
+    #[serde(default)]
+    pub source_language: Option<String>,
+    /// この文字数を超える結果はMarkdownとして解析せずプレーンテキストで表示する（0で無効）
+    #[serde(default = "default_plain_text_threshold")]
+    pub plain_text_threshold: usize,
+    /// 結果をMarkdownとして表示する（falseの場合は選択・コピーしやすいプレーンテキストで表示）
+    #[serde(default = "default_true")]
+    pub render_markdown: bool,
+    /// 結果ウィンドウのタイトルに原文と翻訳先の言語を表示する
+    #[serde(default = "default_true")]
+    pub title_shows_languages: bool,
+    /// 生成途中の翻訳を結果ウィンドウへ逐次表示する（ストリーミング）
+    #[serde(default = "default_true")]
+    pub stream_responses: bool,
+    /// 逐次表示で結果ウィンドウへ送る差分をためておける件数（表示が追いつかない間は差分をまとめて送る）
+    #[serde(default = "default_stream_channel_capacity")]
+    pub stream_channel_capacity: usize,
+    /// 翻訳する前に、取得した原文を結果ウィンドウで編集できるようにする
+    #[serde(default)]
+    pub edit_before_translate: bool,
+    /// 翻訳結果を自動でクリップボードにコピーする
+    #[serde(default)]
+    pub auto_copy: bool,
+    /// 結果ウィンドウを表示せず、通知で結果の冒頭だけを知らせる
+    #[serde(default)]
+    pub silent: bool,
+    /// ダブルプレスとみなす2回の押下の間隔（ミリ秒）
+    #[serde(default = "default_double_press_window_ms")]
+    pub double_press_window_ms: u64,
+    /// ホットキーを使わず、テキストがコピーされるたびに自動で翻訳する
+    #[serde(default)]
+    pub auto_translate_on_copy: bool,
+    /// 保存する翻訳履歴の件数（0で記録しない。原文が残るため既定では記録しない）
+    #[serde(default)]
+    pub history_limit: usize,
+    /// APIリクエストのタイムアウト（秒）
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// API呼び出しの最大試行回数
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// リトライ間隔の基準（ミリ秒）。再試行のたびに倍にする（サーバーが Retry-After を返した場合はそれに従う）
+    #[serde(default = "default_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+    /// この文字数を超える原文は送信前に確認する（0で確認しない）
+    #[serde(default = "default_max_chars")]
+    pub max_chars: usize,
+    /// この文字数を超える原文は段落ごとに分けて翻訳する（0で分割しない）
+    #[serde(default = "default_chunk_chars")]
+    pub chunk_chars: usize,
+    /// 同じ原文の翻訳結果をキャッシュする件数（0でキャッシュしない）
+    #[serde(default = "default_cache_size")]
+    pub cache_size: usize,
+    /// 翻訳に使うAPI（APIキーは選択中のプロバイダーのものを保存する）
+    #[serde(default)]
+    pub provider: Provider,
+    /// OpenAI互換APIのベースURL
+    #[serde(default = "default_openai_base_url")]
+    pub openai_base_url: String,
+    /// OpenAI互換APIで使うモデル
+    #[serde(default = "default_openai_model")]
+    pub openai_model: String,
+    /// DeepLの翻訳先言語コード（例: JA, EN-US）
+    #[serde(default = "default_deepl_target_lang")]
+    pub deepl_target_lang: String,
+    /// 名前付きのプロファイル（仕事用・チャット用など）
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+    /// 使用中のプロファイル名（Noneの場合は基本設定のまま）
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    /// 固定の訳語（原語 → 訳語）。人名・製品名などを常に同じ訳にする
+    #[serde(default)]
+    pub glossary: BTreeMap<String, String>,
+    /// コピー元アプリの実行ファイル名（小文字、例: "code.exe"）ごとの上書き
+    #[serde(default)]
+    pub app_rules: BTreeMap<String, AppRule>,
+}
+
+/// 用途ごとに切り替える設定（基本設定のモデル・出力モード・翻訳先を上書きする）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    pub model: String,
+    #[serde(default)]
+    pub output_mode: OutputMode,
+    #[serde(default = "default_target_language")]
+    pub target_language: String,
+}
+
+/// 同じ原文・モデル・出力モードでも翻訳結果が変わる設定（翻訳結果のキャッシュのキーに使う）
+#[derive(Serialize)]
+struct ResultSettings<'a> {
+    provider: Provider,
+    openai_base_url: &'a str,
+    target_language: &'a str,
+    source_language: Option<&'a str>,
+    deepl_target_lang: &'a str,
+    language_pair: Option<&'a LanguagePair>,
+    custom_prompt: Option<&'a str>,
+    reading_level: ReadingLevel,
+    temperature: Option<f32>,
+    max_output_tokens: Option<u32>,
+    shared_context: &'a str,
+    plain_concise: bool,
+    preserve_markdown: bool,
+    include_reading: bool,
+    disclaimer_rules: &'a [DisclaimerRule],
+    glossary: &'a BTreeMap<String, String>,
+}
+
+impl Config {
+    /// 翻訳結果を左右する設定をまとめて直列化した文字列
+    /// 設定を変えた後にキャッシュから古い結果を返さないよう、キャッシュのキーに含める
+    pub fn result_settings_key(&self) -> String {
+        let settings = ResultSettings {
+            provider: self.provider,
+            openai_base_url: &self.openai_base_url,
+            target_language: &self.target_language,
+            source_language: self.source_language_hint(),
+            deepl_target_lang: &self.deepl_target_lang,
+            language_pair: self.language_pair.as_ref(),
+            custom_prompt: self.custom_prompt.as_deref(),
+            reading_level: self.reading_level,
+            temperature: self.temperature,
+            max_output_tokens: self.max_output_tokens,
+            shared_context: &self.shared_context,
+            plain_concise: self.plain_concise,
+            preserve_markdown: self.preserve_markdown,
+            include_reading: self.include_reading,
+            disclaimer_rules: &self.disclaimer_rules,
+            glossary: &self.glossary,
+        };
+        serde_json::to_string(&settings).unwrap_or_default()
+    }
+
+    /// 選択中のプロバイダーで使うモデル名
+    pub fn active_model(&self) -> &str {
+        match self.provider {
+            Provider::Gemini => &self.model,
+            Provider::OpenAi => &self.openai_model,
+            Provider::DeepL => "deepl",
+        }
+    }
+
+    /// 選択中のプロバイダーで使うモデルを差し替えた設定を返す（コマンドラインでの一時的な指定用）
+    pub fn with_model(&self, model: &str) -> Config {
+        let mut config = self.clone();
+        match config.provider {
+            Provider::Gemini => config.model = model.to_string(),
+            Provider::OpenAi => config.openai_model = model.to_string(),
+            // DeepLにはモデルの選択がない
+            Provider::DeepL => {}
+        }
+        config
+    }
+
+    /// 原文の言語の指定（再翻訳時の指定を設定より優先する）
+    pub fn source_language_hint(&self) -> Option<&str> {
+        self.pinned_source_language
+            .as_deref()
+            .or(self.source_language.as_deref())
+    }
+
+    /// タイムアウトとリトライの設定
+    pub fn retry_policy(&self) -> crate::gemini::RetryPolicy {
+        crate::gemini::RetryPolicy {
+            timeout_secs: self.timeout_secs,
+            max_retries: self.max_retries,
+            retry_delay_ms: self.retry_delay_ms,
+        }
+    }
+
+    /// 使用中のプロファイルを適用した設定を返す（プロファイルがない場合はそのまま）
+    pub fn with_active_profile(&self) -> Config {
+        let Some(profile) = self
+            .active_profile
+            .as_ref()
+            .and_then(|name| self.profiles.get(name))
+        else {
+            return self.clone();
+        };
+
+        Config {
+            model: profile.model.clone(),
+            output_mode: profile.output_mode,
+            target_language: profile.target_language.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// コピー元アプリに一致するルールがあれば、出力モードとプロンプトを上書きした設定を返す
+    pub fn with_app_rule(&self, process_name: Option<&str>) -> Config {
+        let Some(rule) = process_name.and_then(|name| self.app_rules.get(&name.to_lowercase()))
+        else {
+            return self.clone();
+        };
+
+        Config {
+            output_mode: rule.output_mode,
+            custom_prompt: rule
+                .custom_prompt
+                .clone()
+                .or_else(|| self.custom_prompt.clone()),
+            ..self.clone()
+        }
+    }
 }
 
 fn default_model() -> String {
     DEFAULT_MODEL.to_string()
 }
 
+fn default_openai_base_url() -> String {
+    "https://api.openai.com/v1".to_string()
+}
+
+fn default_deepl_target_lang() -> String {
+    "JA".to_string()
+}
+
+fn default_openai_model() -> String {
+    "gpt-4o-mini".to_string()
+}
+
+fn default_timeout_secs() -> u64 {
+    crate::gemini::API_TIMEOUT_SECS
+}
+
+fn default_max_retries() -> u32 {
+    crate::gemini::MAX_RETRIES
+}
+
+fn default_retry_delay_ms() -> u64 {
+    crate::gemini::RETRY_DELAY_MS
+}
+
+/// eguiの既定の本文サイズ
+pub const DEFAULT_FONT_SIZE: f32 = 12.5;
+
+fn default_font_size() -> f32 {
+    DEFAULT_FONT_SIZE
+}
+
+fn default_double_press_window_ms() -> u64 {
+    crate::hotkey::DOUBLE_PRESS_WINDOW_MS
+}
+
+fn default_max_chars() -> usize {
+    20000
+}
+
+fn default_chunk_chars() -> usize {
+    8000
+}
+
+fn default_stream_channel_capacity() -> usize {
+    64
+}
+
+fn default_cache_size() -> usize {
+    200
+}
+
+fn default_target_language() -> String {
+    crate::language::DEFAULT_TARGET_LANGUAGE.to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_plain_text_threshold() -> usize {
+    20_000
+}
+
+fn default_context_cache_ttl_secs() -> u64 {
+    3600
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             api_key: String::new(),
             model: DEFAULT_MODEL.to_string(),
+            // 新規ユーザーはモデル選択を省けるよう自動選択にする
+            auto_model: true,
+            favorite_models: Vec::new(),
             output_mode: OutputMode::default(),
+            auto_dictionary: false,
+            target_language: default_target_language(),
+            custom_prompt: None,
+            temperature: None,
+            max_output_tokens: None,
             hotkey: Hotkey::default(),
+            smart_swap: false,
+            skip_same_language: false,
+            collapse_after_lines: 0,
+            extra_fonts: Vec::new(),
+            shared_context: String::new(),
+            context_cache_ttl_secs: default_context_cache_ttl_secs(),
+            reading_level: ReadingLevel::default(),
+            preserve_markdown: true,
+            include_reading: false,
+            reset_hotkey: None,
+            pause_hotkey: None,
+            language_pair: None,
+            disclaimer_rules: default_disclaimer_rules(),
+            error_display: ErrorDisplay::default(),
+            special_content: SpecialContentAction::default(),
+            focus_result_window: true,
+            always_on_top: true,
+            click_through_until_hover: false,
+            extra_headers: BTreeMap::new(),
+            busy_notice_interval_secs: 0,
+            queue_requests: false,
+            clipboard_backend: ClipboardBackend::default(),
+            preview_first: false,
+            reuse_window: false,
+            send_to_app: None,
+            accent_color: None,
+            theme: Theme::default(),
+            startup_method: StartupMethod::default(),
+            font_size: default_font_size(),
+            plain_concise: false,
+            min_detection_confidence: 0,
+            pinned_source_language: None,
+            source_language: None,
+            plain_text_threshold: default_plain_text_threshold(),
+            render_markdown: true,
+            title_shows_languages: true,
+            stream_responses: true,
+            stream_channel_capacity: default_stream_channel_capacity(),
+            edit_before_translate: false,
+            auto_copy: false,
+            silent: false,
+            auto_translate_on_copy: false,
+            double_press_window_ms: default_double_press_window_ms(),
+            history_limit: 0,
+            timeout_secs: default_timeout_secs(),
+            max_retries: default_max_retries(),
+            retry_delay_ms: default_retry_delay_ms(),
+            max_chars: default_max_chars(),
+            chunk_chars: default_chunk_chars(),
+            cache_size: default_cache_size(),
+            provider: Provider::default(),
+            openai_base_url: default_openai_base_url(),
+            openai_model: default_openai_model(),
+            deepl_target_lang: default_deepl_target_lang(),
+            profiles: BTreeMap::new(),
+            active_profile: None,
+            app_rules: BTreeMap::new(),
+            glossary: BTreeMap::new(),
         }
     }
 }
 
-pub fn config_path() -> Result<PathBuf> {
+pub fn config_dir() -> Result<PathBuf> {
     let mut path = dirs::config_dir().context("Could not determine config directory")?;
     path.push("ClipboardTranslator");
     fs::create_dir_all(&path)?;
+    Ok(path)
+}
+
+pub fn config_path() -> Result<PathBuf> {
+    let mut path = config_dir()?;
     path.push("config.json");
     Ok(path)
 }
 
+/// 設定ディレクトリをエクスプローラーで開き、開いたディレクトリを返す
+/// 初回起動前でも開けるよう、ディレクトリがなければ作成する
+pub fn open_config_dir() -> Result<PathBuf> {
+    let dir = config_dir()?;
+    std::process::Command::new("explorer.exe")
+        .arg(&dir)
+        .spawn()
+        .with_context(|| format!("Failed to open {}", dir.display()))?;
+    Ok(dir)
+}
+
+/// 設定ディレクトリ（設定・履歴・キャッシュ）を丸ごと削除し、削除したディレクトリを返す
+pub fn remove_all_data() -> Result<PathBuf> {
+    let dir = config_dir()?;
+    fs::remove_dir_all(&dir)
+        .with_context(|| format!("Failed to remove {}", dir.display()))?;
+    Ok(dir)
+}
+
 pub fn load_or_create() -> Result<Config> {
     let path = config_path()?;
 
     let mut config = if path.exists() {
         let content = fs::read_to_string(&path)?;
+        let config: Config = serde_json::from_str(&content)?;
 
         // 旧形式（api_keyがJSONに含まれている）の場合は移行処理
         if let Ok(old_config) = serde_json::from_str::<serde_json::Value>(&content) {
             if let Some(api_key) = old_config.get("api_key").and_then(|v| v.as_str()) {
                 if !api_key.is_empty() {
                     // Credential Managerに保存
-                    crate::credential::save_api_key(api_key)?;
+                    crate::credential::save_api_key(config.provider, api_key)?;
                 }
             }
         }
 
-        serde_json::from_str(&content)?
+        config
     } else {
         let config = Config::default();
         save(&config)?;
         config
     };
 
-    // Credential ManagerからAPIキーを読み込み
-    config.api_key = crate::credential::load_api_key().unwrap_or_default();
+    // Credential Managerから使用中のプロバイダーのAPIキーを読み込み
+    config.api_key = crate::credential::load_api_key(config.provider).unwrap_or_default();
 
     Ok(config)
 }
 
+/// 保存されているプロファイル名の一覧
+pub fn list_profiles() -> Result<Vec<String>> {
+    Ok(load_or_create()?.profiles.into_keys().collect())
+}
+
+/// 使用するプロファイルを切り替えて保存する（Noneで基本設定に戻す）
+pub fn set_active(name: Option<&str>) -> Result<()> {
+    let mut config = load_or_create()?;
+
+    if let Some(name) = name {
+        if !config.profiles.contains_key(name) {
+            anyhow::bail!("プロファイル「{}」はありません", name);
+        }
+    }
+
+    config.active_profile = name.map(str::to_string);
+    save(&config)
+}
+
+/// 設定ファイルの更新時刻（保存を検知して一部の設定をその場で反映するため）
+pub fn modified_time() -> Option<SystemTime> {
+    fs::metadata(config_path().ok()?).ok()?.modified().ok()
+}
+
+/// 設定ファイルの内容を読み込む（APIキーは含まない）
+pub fn load_saved() -> Option<Config> {
+    let content = fs::read_to_string(config_path().ok()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 設定ファイルに保存されている使用中のプロファイルと、Credential ManagerのAPIキーを読み直して適用する
+/// バックグラウンド実行中に切り替えた場合や、結果ウィンドウで予備のキーに切り替えた場合も、次の翻訳から反映するため
+pub fn apply_saved_profile(config: &Config) -> Config {
+    let mut config = match load_saved() {
+        Some(saved) => Config {
+            profiles: saved.profiles,
+            active_profile: saved.active_profile,
+            ..config.clone()
+        }
+        .with_active_profile(),
+        None => config.with_active_profile(),
+    };
+
+    if let Ok(api_key) = crate::credential::load_api_key(config.provider) {
+        if !api_key.is_empty() {
+            config.api_key = api_key;
+        }
+    }
+
+    config
+}
+
 pub fn save(config: &Config) -> Result<()> {
-    // APIキーはCredential Managerに保存
+    // APIキーはCredential Managerにプロバイダーごとに保存
     if !config.api_key.is_empty() {
-        crate::credential::save_api_key(&config.api_key)?;
+        crate::credential::save_api_key(config.provider, &config.api_key)?;
     }
 
     // 設定ファイルにはAPIキー以外を保存
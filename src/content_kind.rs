@@ -0,0 +1,260 @@
+/// 行がMarkdownの構造要素（見出し・リスト・引用・コードフェンス・表）か判定
+fn is_markdown_line(line: &str) -> bool {
+    let trimmed = line.trim_start();
+
+    let heading = trimmed.starts_with('#')
+        && trimmed.trim_start_matches('#').starts_with(' ')
+        && trimmed.chars().take_while(|c| *c == '#').count() <= 6;
+
+    let bullet = ["- ", "* ", "+ "].iter().any(|p| trimmed.starts_with(p));
+
+    let numbered = {
+        let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+        digits > 0 && trimmed[digits..].starts_with(". ")
+    };
+
+    let quote = trimmed.starts_with("> ");
+    let fence = trimmed.starts_with("```");
+    let table = trimmed.starts_with('|') && trimmed.ends_with('|');
+
+    heading || bullet || numbered || quote || fence || table
+}
+
+/// テキストがMarkdown文書らしいか判定
+/// 見出しかコードフェンスが1つ以上、または構造要素・リンクが合わせて2つ以上あればMarkdownとみなす
+pub fn looks_like_markdown(text: &str) -> bool {
+    let mut signals = 0;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || (trimmed.starts_with('#') && is_markdown_line(line)) {
+            return true;
+        }
+        if is_markdown_line(line) {
+            signals += 1;
+        }
+    }
+
+    signals += text.matches("](").count();
+
+    signals >= 2
+}
+
+/// 空白を含まない文字列の最大の長さ
+pub fn longest_unbroken_run(text: &str) -> usize {
+    text.split(char::is_whitespace)
+        .map(|run| run.chars().count())
+        .max()
+        .unwrap_or(0)
+}
+
+/// 文章ではなさそうな長いデータ（minifyされたJS・Base64など）か判定
+/// 数千文字以上の1行に空白がほとんど含まれない場合をデータとみなす
+pub fn looks_like_blob(text: &str) -> bool {
+    const MIN_BLOB_CHARS: usize = 2000;
+
+    let total = text.chars().count();
+    if total < MIN_BLOB_CHARS {
+        return false;
+    }
+
+    let whitespace = text.chars().filter(|c| c.is_whitespace()).count();
+    let lines = text.lines().count();
+
+    lines <= 3 && whitespace * 50 < total
+}
+
+/// 辞書モードで引く単語の最大文字数
+const MAX_DICTIONARY_WORD_CHARS: usize = 30;
+
+/// 漢字・かなの単語の最大文字数（区切りがないため、長いものは文とみなす）
+const MAX_DICTIONARY_CJK_CHARS: usize = 8;
+
+/// 辞書モードで引けそうな1語だけか
+/// 空白・文の区切りを含まず、文字を含む短い語のみ（漢字1文字は字典として別に扱う）
+pub fn is_single_word(text: &str) -> bool {
+    let word = text.trim();
+    let count = word.chars().count();
+    let has_cjk = word
+        .chars()
+        .any(|c| matches!(c, '\u{3040}'..='\u{30FF}' | '\u{3400}'..='\u{9FFF}'));
+    let max_chars = if has_cjk {
+        MAX_DICTIONARY_CJK_CHARS
+    } else {
+        MAX_DICTIONARY_WORD_CHARS
+    };
+
+    count >= 2
+        && count <= max_chars
+        && word.chars().any(char::is_alphabetic)
+        && !word.contains(char::is_whitespace)
+        && !word.contains([
+            '。', '、', '！', '？', '.', ',', '!', '?', ';', ':', '/', '\\', '@',
+        ])
+        && classify(word) == ClipboardKind::Text
+}
+
+/// クリップボードの内容の種類（翻訳の要否の判定用）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardKind {
+    /// 通常の文章
+    Text,
+    Url,
+    FilePath,
+    Code,
+}
+
+impl ClipboardKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ClipboardKind::Text => "文章",
+            ClipboardKind::Url => "URL",
+            ClipboardKind::FilePath => "ファイルパス",
+            ClipboardKind::Code => "コード",
+        }
+    }
+}
+
+/// クリップボードの内容を分類する
+/// 文章を誤って除外しないよう、確実に判定できる場合以外は Text とする
+pub fn classify(text: &str) -> ClipboardKind {
+    let trimmed = text.trim();
+
+    if is_url(trimmed) {
+        ClipboardKind::Url
+    } else if is_file_path(trimmed) {
+        ClipboardKind::FilePath
+    } else if looks_like_code(trimmed) {
+        ClipboardKind::Code
+    } else {
+        ClipboardKind::Text
+    }
+}
+
+/// 空白を含まない1つのURLだけか
+fn is_url(text: &str) -> bool {
+    const SCHEMES: &[&str] = &[
+        "http://", "https://", "ftp://", "file://", "mailto:", "www.",
+    ];
+
+    !text.contains(char::is_whitespace)
+        && SCHEMES.iter().any(|scheme| {
+            text.len() > scheme.len() && text.to_ascii_lowercase().starts_with(scheme)
+        })
+}
+
+/// 1行だけのファイルパスか（ドライブ名・UNCパスは空白を含んでもよい）
+fn is_file_path(text: &str) -> bool {
+    if text.contains('\n') {
+        return false;
+    }
+
+    let bytes = text.as_bytes();
+    let drive = bytes.len() > 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && (bytes[2] == b'\\' || bytes[2] == b'/');
+    let unc = text.starts_with(r"\\") && text[2..].contains('\\');
+    let unix = (text.starts_with('/') || text.starts_with("~/"))
+        && !text.contains(char::is_whitespace)
+        && text.matches('/').count() >= 2;
+
+    drive || unc || unix
+}
+
+/// 行がプログラムのコードらしいか（文末の記号・宣言のキーワードで判定）
+fn is_code_line(line: &str) -> bool {
+    const KEYWORDS: &[&str] = &[
+        "fn ",
+        "pub ",
+        "let ",
+        "const ",
+        "var ",
+        "def ",
+        "class ",
+        "import ",
+        "function ",
+        "return ",
+        "#include",
+        "#define",
+        "using ",
+        "package ",
+        "public ",
+        "private ",
+        "static ",
+        "if (",
+        "for (",
+        "while (",
+        "} else",
+        "//",
+        "/*",
+    ];
+
+    let trimmed = line.trim();
+    trimmed.ends_with(';')
+        || trimmed.ends_with('{')
+        || trimmed.starts_with('}')
+        || KEYWORDS.iter().any(|k| trimmed.starts_with(k))
+}
+
+/// コードの断片らしいか
+/// 3行以上あり、空行を除いた行の6割以上がコードらしい行の場合のみコードとみなす
+fn looks_like_code(text: &str) -> bool {
+    const MIN_CODE_LINES: usize = 3;
+
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    let code_lines = lines.iter().filter(|l| is_code_line(l)).count();
+
+    code_lines >= MIN_CODE_LINES && code_lines * 10 >= lines.len() * 6
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_are_classified_as_url() {
+        assert_eq!(classify("https://example.com/path?q=1"), ClipboardKind::Url);
+        assert_eq!(classify("  www.example.com\n"), ClipboardKind::Url);
+        // 文中のURLは文章として扱う
+        assert_eq!(
+            classify("詳しくは https://example.com を見てください"),
+            ClipboardKind::Text
+        );
+        assert_eq!(classify("https://"), ClipboardKind::Text);
+    }
+
+    #[test]
+    fn file_paths_are_classified_as_file_path() {
+        assert_eq!(
+            classify(r"C:\Users\name\My Documents\report.docx"),
+            ClipboardKind::FilePath
+        );
+        assert_eq!(
+            classify(r"\\server\share\file.txt"),
+            ClipboardKind::FilePath
+        );
+        assert_eq!(classify("/usr/local/bin"), ClipboardKind::FilePath);
+        assert_eq!(classify("~/projects/app"), ClipboardKind::FilePath);
+        // 複数行や、区切りが1つだけのものはパスとみなさない
+        assert_eq!(classify("C:\\a.txt\nC:\\b.txt"), ClipboardKind::Text);
+        assert_eq!(classify("/usr"), ClipboardKind::Text);
+    }
+
+    #[test]
+    fn code_needs_enough_code_like_lines() {
+        let code = "fn main() {\n    let x = 1;\n    println!(\"{}\", x);\n}";
+        assert_eq!(classify(code), ClipboardKind::Code);
+        // コードらしい行が3行未満、または6割未満の場合は文章とする
+        assert_eq!(classify("let x = 1;\nlet y = 2;"), ClipboardKind::Text);
+        let mixed = "Here is an example.\nIt shows a loop.\nThen it stops.\nlet x = 1;\nlet y = 2;\nlet z = 3;";
+        assert_eq!(classify(mixed), ClipboardKind::Text);
+    }
+
+    #[test]
+    fn plain_sentences_are_text() {
+        assert_eq!(classify("This is a sentence."), ClipboardKind::Text);
+        assert_eq!(classify("これは普通の文章です。"), ClipboardKind::Text);
+        assert_eq!(classify(""), ClipboardKind::Text);
+    }
+}
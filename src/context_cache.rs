@@ -0,0 +1,93 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 有効期限の直前に期限切れ扱いにする余裕（秒）
+const EXPIRY_MARGIN_SECS: u64 = 60;
+
+/// Gemini の cachedContent ハンドル（共有コンテキストごとに1つ）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedContextHandle {
+    /// "cachedContents/xxxx" 形式のリソース名
+    pub name: String,
+    pub model: String,
+    pub context_hash: u64,
+    /// 有効期限（UNIX時刻・秒）
+    pub expire_at: u64,
+}
+
+impl CachedContextHandle {
+    pub fn is_valid_for(&self, model: &str, context: &str) -> bool {
+        self.model == model
+            && self.context_hash == hash_context(context)
+            && now_secs() + EXPIRY_MARGIN_SECS < self.expire_at
+    }
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn hash_context(context: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    context.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn cache_path() -> Result<PathBuf> {
+    let mut path = crate::config::config_dir()?;
+    path.push("context_cache.json");
+    Ok(path)
+}
+
+/// 保存済みのハンドルを読み込む（モデル・内容が一致し、期限内のもののみ）
+pub fn load(model: &str, context: &str) -> Option<CachedContextHandle> {
+    let content = fs::read_to_string(cache_path().ok()?).ok()?;
+    let handle: CachedContextHandle = serde_json::from_str(&content).ok()?;
+    handle.is_valid_for(model, context).then_some(handle)
+}
+
+pub fn save(handle: &CachedContextHandle) -> Result<()> {
+    let json = serde_json::to_string_pretty(handle)?;
+    fs::write(cache_path()?, json)?;
+    Ok(())
+}
+
+/// 期限切れ・削除済みのハンドルを破棄
+pub fn clear() {
+    if let Ok(path) = cache_path() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 作成に失敗したコンテキスト（モデル・内容のハッシュ・作成を再試行しない期限）
+/// 最小トークン数に満たない場合などは何度送っても失敗するため、翻訳のたびに作り直さない
+static FAILED: Mutex<Option<(String, u64, u64)>> = Mutex::new(None);
+
+/// キャッシュの作成に失敗したことを記録し、ttl_secs の間は作成を試みないようにする
+pub fn record_failure(model: &str, context: &str, ttl_secs: u64) {
+    *crate::hotkey::lock_recovering(&FAILED) = Some((
+        model.to_string(),
+        hash_context(context),
+        now_secs() + ttl_secs,
+    ));
+}
+
+/// 同じモデル・内容でのキャッシュ作成が最近失敗したか（内容が変わった場合は再び試す）
+pub fn recently_failed(model: &str, context: &str) -> bool {
+    crate::hotkey::lock_recovering(&FAILED)
+        .as_ref()
+        .is_some_and(|(failed_model, context_hash, retry_at)| {
+            failed_model == model
+                && *context_hash == hash_context(context)
+                && now_secs() < *retry_at
+        })
+}
@@ -0,0 +1,48 @@
+use anyhow::{Context, Result};
+use std::env;
+use winreg::enums::*;
+use winreg::RegKey;
+
+/// テキスト形式のファイル（.txt, .md, .log など）の右クリックメニューの登録先
+const TEXT_FILE_SHELL_KEY: &str =
+    r"Software\Classes\SystemFileAssociations\text\shell\ClipboardTranslator";
+
+/// 右クリックメニューに表示する名前
+const MENU_LABEL: &str = "Clipboard Translatorで翻訳";
+
+/// テキストファイルの右クリックメニューに「Clipboard Translatorで翻訳」を登録
+pub fn register() -> Result<()> {
+    let exe_path = env::current_exe().context("Failed to get executable path")?;
+    let exe_path_str = exe_path.to_string_lossy();
+
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let (shell_key, _) = hkcu
+        .create_subkey(TEXT_FILE_SHELL_KEY)
+        .context("Failed to create context menu key")?;
+    shell_key
+        .set_value("", &MENU_LABEL)
+        .context("Failed to set context menu label")?;
+    shell_key
+        .set_value("Icon", &exe_path_str.as_ref())
+        .context("Failed to set context menu icon")?;
+
+    let (command_key, _) = shell_key
+        .create_subkey("command")
+        .context("Failed to create context menu command key")?;
+    let command = format!("\"{}\" --file \"%1\"", exe_path_str);
+    command_key
+        .set_value("", &command)
+        .context("Failed to set context menu command")?;
+
+    Ok(())
+}
+
+/// 右クリックメニューの登録を解除
+pub fn unregister() -> Result<()> {
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+
+    // 登録されていない場合もエラーにしない
+    let _ = hkcu.delete_subkey_all(TEXT_FILE_SHELL_KEY);
+
+    Ok(())
+}
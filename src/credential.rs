@@ -1,18 +1,210 @@
 use anyhow::{Context, Result};
 use windows::core::PCWSTR;
-use windows::Win32::Foundation::FILETIME;
+use windows::Win32::Foundation::{LocalFree, FILETIME, HLOCAL};
 use windows::Win32::Security::Credentials::{
-    CredReadW, CredWriteW, CREDENTIALW, CREDENTIAL_ATTRIBUTEW, CRED_FLAGS,
+    CredDeleteW, CredReadW, CredWriteW, CREDENTIALW, CREDENTIAL_ATTRIBUTEW, CRED_FLAGS,
     CRED_PERSIST_LOCAL_MACHINE, CRED_TYPE_GENERIC,
 };
+use windows::Win32::Security::Cryptography::{
+    CryptProtectData, CryptUnprotectData, CRYPTPROTECT_UI_FORBIDDEN, CRYPT_INTEGER_BLOB,
+};
+
+use crate::config::Provider;
 
+/// APIキーの保存先の接頭辞
+/// 旧バージョンではこの名前に保存していた（Geminiのキーのみのため、Gemini用として扱う）
 const TARGET_NAME: &str = "ClipboardTranslator_APIKey";
 
+/// 予備のAPIキー（改行区切り）の保存先の接頭辞
+/// 旧バージョンではこの名前に保存していた（Gemini用として扱う）
+const BACKUP_TARGET_NAME: &str = "ClipboardTranslator_BackupAPIKeys";
+
+/// プロバイダーごとの保存先（例: ClipboardTranslator_APIKey_gemini）
+fn target_name(provider: Provider) -> String {
+    format!("{}_{}", TARGET_NAME, provider.credential_name())
+}
+
+/// プロバイダーごとの予備のキーの保存先（例: ClipboardTranslator_BackupAPIKeys_gemini）
+fn backup_target_name(provider: Provider) -> String {
+    format!("{}_{}", BACKUP_TARGET_NAME, provider.credential_name())
+}
+
 /// Windows Credential ManagerにAPIキーを保存
-pub fn save_api_key(api_key: &str) -> Result<()> {
+pub fn save_api_key(provider: Provider, api_key: &str) -> Result<()> {
+    write_secret(&target_name(provider), api_key)
+}
+
+/// Windows Credential ManagerからAPIキーを読み込み
+/// Geminiの保存先にない場合は、旧バージョンの保存先から移行する
+pub fn load_api_key(provider: Provider) -> Result<String> {
+    read_secret(&target_name(provider)).or_else(|e| match provider {
+        Provider::Gemini => migrate_legacy_api_key(),
+        _ => Err(e),
+    })
+}
+
+/// 旧バージョンの保存先にあるキーを、Geminiの保存先へ移す
+fn migrate_legacy_api_key() -> Result<String> {
+    let api_key = read_secret(TARGET_NAME)?;
+    save_api_key(Provider::Gemini, &api_key)?;
+    let _ = delete_secret(TARGET_NAME);
+    Ok(api_key)
+}
+
+/// APIキーが保存されているか（読み込み・復号に失敗した場合も未保存とみなす）
+/// 状態の表示に使うため、旧バージョンの保存先からの移行や再暗号化は行わない
+pub fn has_api_key(provider: Provider) -> bool {
+    let legacy = (provider == Provider::Gemini).then(|| TARGET_NAME.to_string());
+    std::iter::once(target_name(provider))
+        .chain(legacy)
+        .any(|target| peek_secret(&target).is_ok_and(|(key, _)| !key.is_empty()))
+}
+
+/// Windows Credential Managerから指定のプロバイダーのAPIキーを削除
+pub fn delete_api_key(provider: Provider) -> Result<()> {
+    delete_secret(&target_name(provider))
+}
+
+/// すべてのプロバイダーのAPIキーと予備のキー、旧バージョンの保存先を削除し、削除できた保存先の名前を返す
+pub fn delete_all_api_keys() -> Vec<String> {
+    // 未登録の保存先が多いため、削除に失敗したものは報告しない
+    let mut deleted: Vec<String> = Provider::all()
+        .iter()
+        .filter(|provider| delete_api_key(**provider).is_ok())
+        .map(|provider| target_name(*provider))
+        .collect();
+
+    let others = Provider::all()
+        .iter()
+        .map(|provider| backup_target_name(*provider))
+        .chain([TARGET_NAME.to_string(), BACKUP_TARGET_NAME.to_string()]);
+    deleted.extend(others.filter(|target| delete_secret(target).is_ok()));
+
+    deleted
+}
+
+/// 指定のプロバイダーの予備のAPIキーを読み込み（未登録の場合は空）
+/// Geminiの場合は、旧バージョンの共通の保存先も読む（次に保存した時点で移される）
+pub fn load_backup_api_keys(provider: Provider) -> Vec<String> {
+    read_secret(&backup_target_name(provider))
+        .or_else(|e| match provider {
+            Provider::Gemini => read_secret(BACKUP_TARGET_NAME),
+            _ => Err(e),
+        })
+        .map(|joined| {
+            joined
+                .lines()
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 指定のプロバイダーの予備のAPIキーを保存（空の場合は削除）
+pub fn save_backup_api_keys(provider: Provider, keys: &[String]) -> Result<()> {
+    let keys: Vec<&str> = keys
+        .iter()
+        .map(|key| key.trim())
+        .filter(|key| !key.is_empty())
+        .collect();
+
+    // 旧バージョンの共通の保存先はGeminiの予備として読んでいるため、保存した時点で削除する
+    if provider == Provider::Gemini {
+        let _ = delete_secret(BACKUP_TARGET_NAME);
+    }
+
+    if keys.is_empty() {
+        let _ = delete_secret(&backup_target_name(provider));
+        return Ok(());
+    }
+
+    write_secret(&backup_target_name(provider), &keys.join("\n"))
+}
+
+/// 次の予備のキーを使用中のキーにする（使用中だったキーは予備の末尾へ回す）
+/// 予備のキーがない場合はNoneを返す
+pub fn rotate_api_key(provider: Provider) -> Result<Option<String>> {
+    let mut backups = load_backup_api_keys(provider);
+    if backups.is_empty() {
+        return Ok(None);
+    }
+
+    let next = backups.remove(0);
+    if let Ok(current) = load_api_key(provider) {
+        if !current.is_empty() {
+            backups.push(current);
+        }
+    }
+
+    save_api_key(provider, &next)?;
+    save_backup_api_keys(provider, &backups)?;
+
+    Ok(Some(next))
+}
+
+/// DPAPIで暗号化する（同じWindowsユーザーでのみ復号できる）
+fn encrypt_blob(data: &[u8]) -> Result<Vec<u8>> {
+    unsafe {
+        let input = CRYPT_INTEGER_BLOB {
+            cbData: data.len() as u32,
+            pbData: data.as_ptr() as *mut u8,
+        };
+        let mut output = CRYPT_INTEGER_BLOB::default();
+
+        CryptProtectData(
+            &input,
+            PCWSTR::null(),
+            None,
+            None,
+            None,
+            CRYPTPROTECT_UI_FORBIDDEN,
+            &mut output,
+        )
+        .context("Failed to encrypt credential")?;
+
+        Ok(take_blob(output))
+    }
+}
+
+/// DPAPIで復号する（暗号化されていないデータの場合はエラー）
+fn decrypt_blob(data: &[u8]) -> Result<Vec<u8>> {
     unsafe {
-        let target_name = encode_wide(TARGET_NAME);
-        let credential_blob = api_key.as_bytes();
+        let input = CRYPT_INTEGER_BLOB {
+            cbData: data.len() as u32,
+            pbData: data.as_ptr() as *mut u8,
+        };
+        let mut output = CRYPT_INTEGER_BLOB::default();
+
+        CryptUnprotectData(
+            &input,
+            None,
+            None,
+            None,
+            None,
+            CRYPTPROTECT_UI_FORBIDDEN,
+            &mut output,
+        )
+        .context("Failed to decrypt credential")?;
+
+        Ok(take_blob(output))
+    }
+}
+
+/// DPAPIが確保したバッファをコピーして解放する
+unsafe fn take_blob(blob: CRYPT_INTEGER_BLOB) -> Vec<u8> {
+    let data = std::slice::from_raw_parts(blob.pbData, blob.cbData as usize).to_vec();
+    let _ = LocalFree(HLOCAL(blob.pbData as *mut _));
+    data
+}
+
+fn write_secret(target: &str, secret: &str) -> Result<()> {
+    let encrypted = encrypt_blob(secret.as_bytes())?;
+
+    unsafe {
+        let target_name = encode_wide(target);
+        let credential_blob = encrypted.as_slice();
 
         let mut cred = CREDENTIALW {
             Flags: CRED_FLAGS(0),
@@ -35,10 +227,22 @@ pub fn save_api_key(api_key: &str) -> Result<()> {
     Ok(())
 }
 
-/// Windows Credential ManagerからAPIキーを読み込み
-pub fn load_api_key() -> Result<String> {
+/// 保存されている値を読み込む
+/// 暗号化前のバージョンで保存された値は、暗号化して保存し直す
+fn read_secret(target: &str) -> Result<String> {
+    let (secret, encrypted) = peek_secret(target)?;
+    if !encrypted {
+        if let Err(e) = write_secret(target, &secret) {
+            eprintln!("Failed to encrypt stored credential: {}", e);
+        }
+    }
+    Ok(secret)
+}
+
+/// 保存されている値を書き換えずに読み込み、暗号化されていたかと合わせて返す
+fn peek_secret(target: &str) -> Result<(String, bool)> {
     unsafe {
-        let target_name = encode_wide(TARGET_NAME);
+        let target_name = encode_wide(target);
         let mut pcredential: *mut CREDENTIALW = std::ptr::null_mut();
 
         CredReadW(
@@ -55,14 +259,31 @@ pub fn load_api_key() -> Result<String> {
 
         let cred = &*pcredential;
         let blob =
-            std::slice::from_raw_parts(cred.CredentialBlob, cred.CredentialBlobSize as usize);
-        let api_key = String::from_utf8(blob.to_vec()).context("Invalid UTF-8 in credential")?;
+            std::slice::from_raw_parts(cred.CredentialBlob, cred.CredentialBlobSize as usize)
+                .to_vec();
 
         // メモリ解放
         windows::Win32::Security::Credentials::CredFree(pcredential as *const _);
 
-        Ok(api_key)
+        // 暗号化前のバージョンで保存されたキーは復号できないため、そのまま読む
+        let (data, encrypted) = match decrypt_blob(&blob) {
+            Ok(decrypted) => (decrypted, true),
+            Err(_) => (blob, false),
+        };
+        let secret = String::from_utf8(data).context("Invalid UTF-8 in credential")?;
+        Ok((secret, encrypted))
+    }
+}
+
+fn delete_secret(target: &str) -> Result<()> {
+    unsafe {
+        let target_name = encode_wide(target);
+
+        CredDeleteW(PCWSTR(target_name.as_ptr()), CRED_TYPE_GENERIC, 0)
+            .context("Failed to delete credential")?;
     }
+
+    Ok(())
 }
 
 /// UTF-16に変換（null終端付き）
@@ -0,0 +1,104 @@
+/// 差分の1区間
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffSpan {
+    /// 両方に共通
+    Same(String),
+    /// 新しい結果にのみある
+    Added(String),
+    /// 前の結果にのみある
+    Removed(String),
+}
+
+/// これを超える組み合わせ数（前の語数 × 新しい語数）の場合は、全体を置き換えとして扱う
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// 差分をとる単位に分ける
+/// 英数字は単語ごと、空白は連続した分をまとめ、日本語などそれ以外の文字は1文字ずつ
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut start_kind = 0;
+
+    for (i, c) in text.char_indices() {
+        let kind = if c.is_ascii_alphanumeric() {
+            1
+        } else if c.is_whitespace() {
+            2
+        } else {
+            0
+        };
+
+        if let Some(s) = start {
+            if kind == 0 || kind != start_kind {
+                tokens.push(&text[s..i]);
+                start = None;
+            }
+        }
+
+        if kind == 0 {
+            tokens.push(&text[i..i + c.len_utf8()]);
+        } else if start.is_none() {
+            start = Some(i);
+            start_kind = kind;
+        }
+    }
+
+    if let Some(s) = start {
+        tokens.push(&text[s..]);
+    }
+    tokens
+}
+
+/// 前の結果と新しい結果の差分を、単語（日本語は文字）単位で求める
+pub fn diff_words(old: &str, new: &str) -> Vec<DiffSpan> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+
+    if old_tokens.len().saturating_mul(new_tokens.len()) > MAX_DIFF_CELLS {
+        return vec![
+            DiffSpan::Removed(old.to_string()),
+            DiffSpan::Added(new.to_string()),
+        ];
+    }
+
+    // lcs[i][j] = old_tokens[i..] と new_tokens[j..] の最長共通部分列の長さ
+    let (n, m) = (old_tokens.len(), new_tokens.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_tokens[i] == new_tokens[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut spans = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old_tokens[i] == new_tokens[j] {
+            push_span(&mut spans, DiffSpan::Same(old_tokens[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            // 置き換えは削除を先に表示する
+            push_span(&mut spans, DiffSpan::Removed(old_tokens[i].to_string()));
+            i += 1;
+        } else {
+            push_span(&mut spans, DiffSpan::Added(new_tokens[j].to_string()));
+            j += 1;
+        }
+    }
+    spans
+}
+
+/// 同じ種類の区間が続く場合は1つにまとめる
+fn push_span(spans: &mut Vec<DiffSpan>, span: DiffSpan) {
+    match (spans.last_mut(), span) {
+        (Some(DiffSpan::Same(last)), DiffSpan::Same(text))
+        | (Some(DiffSpan::Added(last)), DiffSpan::Added(text))
+        | (Some(DiffSpan::Removed(last)), DiffSpan::Removed(text)) => last.push_str(&text),
+        (_, span) => spans.push(span),
+    }
+}
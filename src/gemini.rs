@@ -1,31 +1,225 @@
 use anyhow::{Context, Result};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::collections::hash_map::RandomState;
+use std::collections::BTreeMap;
+use std::hash::BuildHasher;
+use std::time::{Duration, Instant, SystemTime};
 
 const API_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta/models";
 
-/// APIリクエストのタイムアウト（秒）
-const API_TIMEOUT_SECS: u64 = 30;
+const CACHED_CONTENTS_URL: &str = "https://generativelanguage.googleapis.com/v1beta/cachedContents";
+
+/// APIリクエストのタイムアウト（秒）の既定値
+pub const API_TIMEOUT_SECS: u64 = 30;
+
+/// API呼び出しの最大試行回数の既定値
+pub const MAX_RETRIES: u32 = 3;
+
+/// リトライ間隔の基準（ミリ秒）の既定値。再試行のたびに倍にする
+pub const RETRY_DELAY_MS: u64 = 1000;
+
+/// 指数的に伸ばすリトライ間隔の上限（ミリ秒）
+const MAX_RETRY_DELAY_MS: u64 = 30_000;
+
+/// Retry-After で指定された待ち時間の上限（これ以上は待たずに上限で打ち切る）
+const MAX_RETRY_AFTER_SECS: u64 = 120;
+
+/// タイムアウトとリトライの設定
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub timeout_secs: u64,
+    /// 最大試行回数（1以上）
+    pub max_retries: u32,
+    pub retry_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            timeout_secs: API_TIMEOUT_SECS,
+            max_retries: MAX_RETRIES,
+            retry_delay_ms: RETRY_DELAY_MS,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// attempt 回目（0始まり）の試行前に待つ時間
+    /// 再試行のたびに倍にし、同時に失敗したリクエストが一斉に再送しないよう後半をランダムにする
+    pub fn delay_before(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let base = self
+            .retry_delay_ms
+            .saturating_mul(1 << exponent)
+            .min(MAX_RETRY_DELAY_MS.max(self.retry_delay_ms));
+        let half = base / 2;
+        Duration::from_millis(half + jitter(base - half))
+    }
+
+    /// 失敗した応答を受けて attempt 回目の試行前に待つ時間（Retry-After があればそれに従う）
+    pub fn delay_after(&self, response: &reqwest::Response, attempt: u32) -> Duration {
+        retry_after(response).unwrap_or_else(|| self.delay_before(attempt))
+    }
+}
+
+/// 0 から max_ms までのランダムなミリ秒数
+fn jitter(max_ms: u64) -> u64 {
+    if max_ms == 0 {
+        return 0;
+    }
+    RandomState::new().hash_one(Instant::now()) % (max_ms + 1)
+}
+
+/// Retry-After ヘッダー（秒数またはHTTP日付）から待ち時間を求める
+pub fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let value = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+
+    let delay = match value.parse::<u64>() {
+        Ok(secs) => Duration::from_secs(secs),
+        Err(_) => httpdate::parse_http_date(value)
+            .ok()?
+            .duration_since(SystemTime::now())
+            .unwrap_or_default(),
+    };
+    Some(delay.min(Duration::from_secs(MAX_RETRY_AFTER_SECS)))
+}
+
+/// リトライの設定に従ってリクエストを送信し、最後に受け取った応答を返す
+/// 送信エラーと混雑（is_busy が true のステータス）の場合は待ってから送り直し、それ以外の応答は呼び出し側で処理する
+/// 混雑時は応答の本文を describe_busy に渡し、Err が返った場合（1日の上限など）は待たずに失敗させる
+pub async fn send_with_policy(
+    policy: RetryPolicy,
+    attempts: &mut u32,
+    notifier: Option<&RetryNotifier>,
+    mut request: impl FnMut() -> reqwest::RequestBuilder,
+    is_busy: impl Fn(reqwest::StatusCode) -> bool,
+    describe_busy: impl Fn(reqwest::StatusCode, &str) -> Result<String>,
+) -> Result<reqwest::Response> {
+    let max_retries = policy.max_retries.max(1);
+    let mut last_error = String::new();
+    // サーバーが混雑を返した場合に、次の試行前に待つ時間
+    let mut next_delay = None;
+
+    for attempt in 0..max_retries {
+        *attempts += 1;
+        if attempt > 0 {
+            let delay = next_delay
+                .take()
+                .unwrap_or_else(|| policy.delay_before(attempt));
+            tokio::time::sleep(delay).await;
+        }
+
+        let response = match request().send().await {
+            Ok(r) => r,
+            Err(e) if crate::network::is_offline_error(&e) => {
+                anyhow::bail!(crate::network::OFFLINE_MESSAGE);
+            }
+            Err(e) => {
+                last_error = e.to_string();
+                continue;
+            }
+        };
+
+        let status = response.status();
+        if !is_busy(status) {
+            return Ok(response);
+        }
+
+        let delay = policy.delay_after(&response, attempt + 1);
+        let error_text = response.text().await.unwrap_or_default();
+        last_error = describe_busy(status, &error_text)?;
+        next_delay = Some(delay);
+        // 次の試行がある場合は、待機していることと再試行の予定時刻を知らせる
+        if attempt + 1 < max_retries {
+            if let Some(notify) = notifier {
+                notify(status.as_u16(), Instant::now() + delay);
+            }
+        }
+    }
+
+    anyhow::bail!(
+        "API呼び出しに失敗しました（{}回リトライ）: {}",
+        max_retries,
+        last_error
+    )
+}
+
+/// 混雑時のエラー表示（応答の本文は使わない）
+pub fn describe_server_busy(status: reqwest::StatusCode, _error_text: &str) -> Result<String> {
+    Ok(format!(
+        "API Error {}: サーバー過負荷、リトライ中...",
+        status
+    ))
+}
 
 /// タイムアウト付きのHTTPクライアントを作成
-fn create_client() -> Result<Client> {
+pub fn create_client(timeout_secs: u64) -> Result<Client> {
     Client::builder()
-        .timeout(Duration::from_secs(API_TIMEOUT_SECS))
+        .timeout(Duration::from_secs(timeout_secs))
         .build()
         .context("HTTPクライアントの作成に失敗しました")
 }
 
+/// 設定の追加HTTPヘッダーを検証して HeaderMap に変換
+/// 不正なヘッダー名・値がある場合は送信前にエラーにする
+pub fn build_header_map(headers: &BTreeMap<String, String>) -> Result<HeaderMap> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let header_name = HeaderName::from_bytes(name.trim().as_bytes())
+            .with_context(|| format!("不正なHTTPヘッダー名です: {}", name))?;
+        let header_value = HeaderValue::from_str(value.trim())
+            .with_context(|| format!("HTTPヘッダー {} の値が不正です", name))?;
+        map.insert(header_name, header_value);
+    }
+    Ok(map)
+}
+
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 struct GeminiRequest {
     contents: Vec<Content>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cached_content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generation_config: Option<GenerationConfig>,
+}
+
+/// 生成パラメータ（指定しない項目はサーバー側の既定値を使う）
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_output_tokens: Option<u32>,
 }
 
 #[derive(Debug, Serialize)]
 struct Content {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
     parts: Vec<Part>,
 }
 
+#[derive(Debug, Serialize)]
+struct CreateCachedContentRequest {
+    model: String,
+    contents: Vec<Content>,
+    ttl: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CachedContentResponse {
+    name: String,
+}
+
 #[derive(Debug, Serialize)]
 struct Part {
     text: String,
@@ -54,7 +248,6 @@ struct PromptFeedback {
 struct SafetyRating {
     category: String,
     #[serde(default)]
-    #[allow(dead_code)]
     probability: Option<String>,
     #[serde(default)]
     blocked: Option<bool>,
@@ -90,7 +283,7 @@ struct ListModelsResponse {
     next_page_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ModelInfo {
     pub name: String,
@@ -115,12 +308,49 @@ impl ModelInfo {
     }
 }
 
-pub async fn fetch_available_models(api_key: &str) -> Result<Vec<ModelInfo>> {
-    let client = create_client()?;
+/// モデルIDからバージョン番号を取り出す（例: "gemini-2.0-flash" → 2.0）
+fn model_version(model_id: &str) -> f32 {
+    model_id
+        .split('-')
+        .find_map(|part| part.parse::<f32>().ok())
+        .unwrap_or(0.0)
+}
+
+/// 一覧から高速・低コストなモデルを選ぶ
+/// 試験版・プレビュー版を除いた flash 系のうち、最新バージョンの安定版を優先する
+pub fn pick_fast_model(models: &[ModelInfo]) -> Option<&ModelInfo> {
+    const UNSTABLE_MARKERS: &[&str] = &[
+        "exp", "preview", "thinking", "tts", "image", "audio", "live", "embedding",
+    ];
+
+    models
+        .iter()
+        .filter(|m| m.supports_generate_content())
+        .filter(|m| {
+            let id = m.model_id();
+            id.contains("flash") && !UNSTABLE_MARKERS.iter().any(|marker| id.contains(marker))
+        })
+        .max_by(|a, b| {
+            let (a_id, b_id) = (a.model_id(), b.model_id());
+            model_version(a_id)
+                .total_cmp(&model_version(b_id))
+                // 同じバージョンなら lite より通常版、固定版（-001等）より短いエイリアスを優先
+                .then_with(|| b_id.contains("lite").cmp(&a_id.contains("lite")))
+                .then_with(|| b_id.len().cmp(&a_id.len()))
+        })
+}
+
+pub async fn fetch_available_models(
+    api_key: &str,
+    extra_headers: &BTreeMap<String, String>,
+) -> Result<Vec<ModelInfo>> {
+    let client = create_client(API_TIMEOUT_SECS)?;
+    let headers = build_header_map(extra_headers)?;
     let url = format!("{}?key={}&pageSize=100", API_BASE_URL, api_key);
 
     let response = client
         .get(&url)
+        .headers(headers)
         .send()
         .await
         .context("Failed to fetch models list")?;
@@ -128,7 +358,7 @@ pub async fn fetch_available_models(api_key: &str) -> Result<Vec<ModelInfo>> {
     if !response.status().is_success() {
         let status = response.status();
         let error_text = response.text().await.unwrap_or_default();
-        anyhow::bail!("API Error {}: {}", status, error_text);
+        anyhow::bail!(describe_api_error(status, &error_text));
     }
 
     let list_response: ListModelsResponse = response
@@ -146,210 +376,803 @@ pub async fn fetch_available_models(api_key: &str) -> Result<Vec<ModelInfo>> {
     Ok(models)
 }
 
-use crate::config::OutputMode;
+/// 別のAPIキーで再試行すれば成功する可能性のあるエラー（認証エラー・クォータ超過）か
+pub fn is_key_failover_error(message: &str) -> bool {
+    ["API Error 401", "API Error 403", "API Error 429", INVALID_API_KEY_REASON]
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+/// APIキーが無効な場合にエラー応答に含まれる理由
+const INVALID_API_KEY_REASON: &str = "API_KEY_INVALID";
+
+/// APIキーの入力し直しが必要なエラーか
+pub fn is_invalid_api_key_error(message: &str) -> bool {
+    message.contains(INVALID_API_KEY_REASON)
+}
+
+/// APIのエラー応答（{"error": {...}}）
+#[derive(Debug, Deserialize)]
+struct ApiErrorResponse {
+    error: ApiErrorDetail,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiErrorDetail {
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    status: String,
+}
+
+/// 1日の無料枠を使い切ったエラーか（分あたりの制限と違い、待っても回復しない）
+fn is_daily_quota_exhausted(body: &str) -> bool {
+    body.contains("RESOURCE_EXHAUSTED") && body.contains("PerDay")
+}
+
+/// エラー応答を、ユーザーが取るべき対応がわかるメッセージにする
+/// 先頭の「API Error <コード>」と理由（API_KEY_INVALID など）は判定に使うため残す
+fn describe_api_error(status: reqwest::StatusCode, body: &str) -> String {
+    let detail = serde_json::from_str::<ApiErrorResponse>(body)
+        .ok()
+        .map(|r| r.error);
+    let api_status = detail.as_ref().map_or("", |d| d.status.as_str());
+    let message = detail
+        .as_ref()
+        .map(|d| d.message.as_str())
+        .filter(|m| !m.is_empty())
+        .unwrap_or(body);
+
+    let (hint, reason) = if is_invalid_api_key_error(body) {
+        (
+            "APIキーが無効です。設定画面でAPIキーを入力し直してください",
+            INVALID_API_KEY_REASON,
+        )
+    } else if is_daily_quota_exhausted(body) {
+        (
+            "無料枠を使い切りました。翌日まで待つか、Google AI Studio で課金を設定してください",
+            api_status,
+        )
+    } else if api_status == "RESOURCE_EXHAUSTED" || status.as_u16() == 429 {
+        (
+            "一時的な負荷のため受け付けられませんでした。しばらく待ってから再試行してください",
+            api_status,
+        )
+    } else if api_status == "PERMISSION_DENIED" {
+        (
+            "このAPIキーでは利用する権限がありません。APIキーのプロジェクトの設定を確認してください",
+            api_status,
+        )
+    } else {
+        return format!("API Error {}: {}", status, body);
+    };
+
+    if reason.is_empty() {
+        format!("API Error {}: {}\n{}", status, hint, message)
+    } else {
+        format!("API Error {}: {} [{}]\n{}", status, hint, reason, message)
+    }
+}
+
+use crate::language::DetectedLanguage;
+use crate::prompt::PromptBuilder;
+
+/// 翻訳結果と計測情報
+#[derive(Debug, Clone)]
+pub struct Translation {
+    pub content: String,
+    /// リクエスト開始から結果取得までの時間（リトライの待ち時間を含む）
+    pub elapsed: Duration,
+    /// API呼び出しの試行回数
+    pub attempts: u32,
+    /// モデルが報告した原文の言語と信頼度（報告を求めた場合のみ）
+    pub detected: Option<DetectedLanguage>,
+    /// 表（TSV）として翻訳し、クリップボードへ書き込んだか（結果ウィンドウで表として表示する）
+    pub table_copied: bool,
+}
+
+impl Translation {
+    /// 計測情報のない結果（既存テキストの表示用）
+    pub fn plain(content: String) -> Self {
+        Self {
+            content,
+            elapsed: Duration::ZERO,
+            attempts: 0,
+            detected: None,
+            table_copied: false,
+        }
+    }
+
+    /// 出力がトークン上限で途中で切れているか
+    pub fn is_truncated(&self) -> bool {
+        self.content.contains(TRUNCATED_WARNING)
+    }
+}
+
+/// サーバー混雑（503/429）でリトライを待つ際の通知先（ステータスコード, 次の試行予定時刻）
+pub type RetryNotifier = Box<dyn Fn(u16, Instant) + Send + Sync>;
 
 pub struct GeminiClient {
     api_key: String,
     model: String,
-    output_mode: OutputMode,
+    prompts: PromptBuilder,
+    shared_context: String,
+    context_cache_ttl_secs: u64,
+    extra_headers: BTreeMap<String, String>,
+    generation_config: GenerationConfig,
+    retry_notifier: Option<RetryNotifier>,
+    retry_policy: RetryPolicy,
+    /// この文字数を超える原文は段落ごとに分けて翻訳する（0で分割しない）
+    chunk_chars: usize,
     client: Client,
 }
 
 impl GeminiClient {
-    pub fn new(api_key: String, model: String, output_mode: OutputMode) -> Self {
-        let client = create_client().unwrap_or_else(|_| Client::new());
+    pub fn new(api_key: String, model: String, prompts: PromptBuilder) -> Self {
+        let client = create_client(API_TIMEOUT_SECS).unwrap_or_else(|_| Client::new());
         Self {
             api_key,
             model,
-            output_mode,
+            prompts,
+            shared_context: String::new(),
+            context_cache_ttl_secs: 0,
+            extra_headers: BTreeMap::new(),
+            generation_config: GenerationConfig::default(),
+            retry_notifier: None,
+            retry_policy: RetryPolicy::default(),
+            chunk_chars: 0,
             client,
         }
     }
 
-    fn build_prompt(&self, text: &str) -> String {
-        match self.output_mode {
-            OutputMode::Detailed => format!(
-                r#"以下のテキストを分析し、以下の形式で回答してください:
+    /// 長い原文を分割して翻訳する文字数を設定する（0で分割しない）
+    pub fn with_chunk_threshold(mut self, max_chars: usize) -> Self {
+        self.chunk_chars = max_chars;
+        self
+    }
+
+    /// 原文が長く、分割して翻訳する必要があるか
+    pub fn needs_chunking(&self, text: &str) -> bool {
+        self.chunk_chars > 0 && text.chars().count() > self.chunk_chars
+    }
+
+    /// タイムアウトとリトライ回数・間隔を設定する
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        if policy.timeout_secs != self.retry_policy.timeout_secs {
+            self.client = create_client(policy.timeout_secs).unwrap_or_else(|_| Client::new());
+        }
+        self.retry_policy = policy;
+        self
+    }
 
-【言語判定】
-検出言語: [言語名]
+    /// すべてのリクエストに付ける追加HTTPヘッダー（社内ゲートウェイの認証など）を設定する
+    pub fn with_extra_headers(mut self, headers: BTreeMap<String, String>) -> Self {
+        self.extra_headers = headers;
+        self
+    }
 
-【翻訳】
-[日本語の場合は英語へ、それ以外は日本語へ翻訳]
+    /// サーバー混雑でリトライを待つたびに呼ばれる通知を設定する（待機状態の表示用）
+    pub fn with_retry_notifier(mut self, notify: impl Fn(u16, Instant) + Send + Sync + 'static) -> Self {
+        self.retry_notifier = Some(Box::new(notify));
+        self
+    }
 
-【スラング・特殊表現】
-[該当する表現があれば解説、なければ「なし」]
+    /// 温度と最大出力トークン数を設定する（Noneの項目はサーバー側の既定値）
+    pub fn with_generation_config(
+        mut self,
+        temperature: Option<f32>,
+        max_output_tokens: Option<u32>,
+    ) -> Self {
+        self.generation_config = GenerationConfig {
+            temperature,
+            max_output_tokens,
+        };
+        self
+    }
 
-【要約】
-[テキストの要点を1-2文で]
+    /// 毎回送る共有コンテキスト（用語集・例文など）を設定する
+    /// ttl_secs > 0 の場合は cachedContent としてキャッシュして再利用する
+    pub fn with_shared_context(mut self, context: String, ttl_secs: u64) -> Self {
+        self.shared_context = context;
+        self.context_cache_ttl_secs = ttl_secs;
+        self
+    }
 
----
-テキスト:
-{}"#,
-                text
-            ),
-            OutputMode::Concise => format!(
-                r#"以下のテキストを翻訳してください。
-- 日本語なら英語へ、それ以外なら日本語へ
-- 5行以内で要点のみ
-- 余計な説明不要、翻訳結果だけ出力
-
-テキスト:
-{}"#,
-                text
-            ),
+    /// 共有コンテキストの cachedContent を取得（期限切れなら作り直す）
+    /// キャッシュを使えない場合はNoneを返し、呼び出し側でプロンプトに直接含める
+    async fn resolve_cached_context(&self) -> Option<String> {
+        if self.shared_context.trim().is_empty() || self.context_cache_ttl_secs == 0 {
+            return None;
+        }
+
+        if let Some(handle) = crate::context_cache::load(&self.model, &self.shared_context) {
+            return Some(handle.name);
+        }
+        if crate::context_cache::recently_failed(&self.model, &self.shared_context) {
+            return None;
+        }
+
+        match self.create_cached_context().await {
+            Ok(handle) => {
+                let _ = crate::context_cache::save(&handle);
+                Some(handle.name)
+            }
+            Err(e) => {
+                // 最小トークン数に満たない場合なども含め、通常送信にフォールバック
+                eprintln!("Failed to create cached content: {}", e);
+                crate::context_cache::record_failure(
+                    &self.model,
+                    &self.shared_context,
+                    self.context_cache_ttl_secs,
+                );
+                None
+            }
         }
     }
 
-    pub async fn translate_and_explain(&self, text: &str) -> Result<String> {
-        let prompt = self.build_prompt(text);
+    async fn create_cached_context(&self) -> Result<crate::context_cache::CachedContextHandle> {
+        let body = CreateCachedContentRequest {
+            model: format!("models/{}", self.model),
+            contents: vec![Content {
+                role: Some("user".to_string()),
+                parts: vec![Part {
+                    text: self.shared_context.clone(),
+                }],
+            }],
+            ttl: format!("{}s", self.context_cache_ttl_secs),
+        };
+
+        let url = format!("{}?key={}", CACHED_CONTENTS_URL, self.api_key);
+        let response = self
+            .client
+            .post(&url)
+            .headers(build_header_map(&self.extra_headers)?)
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to create cached content")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("API Error {}: {}", status, error_text);
+        }
+
+        let created: CachedContentResponse = response
+            .json()
+            .await
+            .context("Failed to parse cached content response")?;
 
-        let request_body = GeminiRequest {
+        Ok(crate::context_cache::CachedContextHandle {
+            name: created.name,
+            model: self.model.clone(),
+            context_hash: crate::context_cache::hash_context(&self.shared_context),
+            expire_at: crate::context_cache::now_secs() + self.context_cache_ttl_secs,
+        })
+    }
+
+    /// リクエスト本文を組み立てる（キャッシュがない場合は共有コンテキストを先頭に付ける）
+    fn build_request(&self, prompt: &str, cached_content: Option<String>) -> GeminiRequest {
+        let prompt = if cached_content.is_none() && !self.shared_context.trim().is_empty() {
+            format!("{}\n\n{}", self.shared_context, prompt)
+        } else {
+            prompt.to_string()
+        };
+
+        GeminiRequest {
             contents: vec![Content {
+                role: Some("user".to_string()),
                 parts: vec![Part { text: prompt }],
             }],
-        };
+            cached_content,
+            generation_config: (self.generation_config.temperature.is_some()
+                || self.generation_config.max_output_tokens.is_some())
+            .then_some(self.generation_config),
+        }
+    }
 
-        let url = format!(
-            "{}/{}:generateContent?key={}",
-            API_BASE_URL, self.model, self.api_key
+    /// 翻訳を実行し、所要時間と試行回数を合わせて返す
+    pub async fn translate_and_explain(&self, text: &str) -> Result<Translation> {
+        if self.needs_chunking(text) {
+            return self.translate_in_chunks(text, false, |_| {}).await;
+        }
+
+        let start = Instant::now();
+        let mut attempts = 0;
+        let content = self.request_prompt(&self.prompts.build(text), &mut attempts).await?;
+
+        Ok(self.prompts.finish_translation(content, start.elapsed(), attempts))
+    }
+
+    /// 表（TSV）の各セルを個別に翻訳し、同じ行・列の構造のTSVで返す
+    /// 空のセルは送らず、そのまま残す
+    pub async fn translate_table(&self, rows: &[Vec<String>]) -> Result<Translation> {
+        let start = Instant::now();
+        let mut attempts = 0;
+
+        let cells: Vec<&str> = rows
+            .iter()
+            .flatten()
+            .map(|cell| cell.as_str())
+            .filter(|cell| !cell.trim().is_empty())
+            .collect();
+
+        let (_, direction_rule) = self.prompts.translation_direction();
+        let prompt = format!(
+            r#"以下のJSON配列は表の各セルです。各要素を個別に翻訳してください。
+- {}
+- 出力は同じ要素数・同じ順番のJSON文字列配列のみ（前置きやコードフェンスは不要）
+- 数値・記号・固有名詞・URLなど翻訳の必要がない要素はそのまま返す
+- セル内の改行はそのまま保持する
+
+セル:
+{}"#,
+            direction_rule,
+            serde_json::to_string(&cells)?
         );
 
-        // リトライ設定
-        const MAX_RETRIES: u32 = 3;
-        const RETRY_DELAY_MS: u64 = 1000;
+        let response = self.request_prompt(&prompt, &mut attempts).await?;
+        let json = response
+            .trim()
+            .trim_start_matches("```json")
+            .trim_start_matches("```")
+            .trim_end_matches("```")
+            .trim();
+        let translated: Vec<String> =
+            serde_json::from_str(json).context("表の翻訳結果を解析できませんでした")?;
+
+        if translated.len() != cells.len() {
+            anyhow::bail!(
+                "表の翻訳結果のセル数が一致しません（{}件中{}件）",
+                cells.len(),
+                translated.len()
+            );
+        }
+
+        let mut translated = translated.into_iter();
+        let table: Vec<Vec<String>> = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| {
+                        if cell.trim().is_empty() {
+                            cell.clone()
+                        } else {
+                            translated.next().unwrap_or_default()
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(Translation {
+            content: crate::table::to_tsv(&table),
+            elapsed: start.elapsed(),
+            attempts,
+            detected: None,
+            table_copied: false,
+        })
+    }
+
+    /// 翻訳を実行し、生成された文章を少しずつ on_chunk へ渡す（結果ウィンドウへの逐次表示用）
+    /// 完了後は translate_and_explain と同じく、全文と計測情報を返す
+    pub async fn translate_and_explain_stream(
+        &self,
+        text: &str,
+        mut on_chunk: impl FnMut(&str),
+    ) -> Result<Translation> {
+        if self.needs_chunking(text) {
+            return self.translate_in_chunks(text, true, on_chunk).await;
+        }
+
+        let start = Instant::now();
+        let mut attempts = 0;
+        let content = self
+            .stream_prompt(&self.prompts.build(text), &mut on_chunk, &mut attempts)
+            .await?;
+
+        Ok(self.prompts.finish_translation(content, start.elapsed(), attempts))
+    }
+
+    /// 長い原文を分割して順番に翻訳し、各部分の結果を空行でつなげて返す
+    /// 各部分の訳文は届いた順に on_chunk へ渡す（stream が false の場合は部分ごとにまとめて渡す）
+    pub async fn translate_in_chunks(
+        &self,
+        text: &str,
+        stream: bool,
+        mut on_chunk: impl FnMut(&str),
+    ) -> Result<Translation> {
+        const SEPARATOR: &str = "\n\n";
 
-        let mut last_error = String::new();
+        let start = Instant::now();
+        let mut total_attempts = 0;
+        let mut content = String::new();
+        let mut detected = None;
 
-        for attempt in 0..MAX_RETRIES {
-            if attempt > 0 {
-                tokio::time::sleep(tokio::time::Duration::from_millis(
-                    RETRY_DELAY_MS * (attempt as u64 + 1),
-                ))
-                .await;
+        for (index, part) in crate::chunk::split(text, self.chunk_chars).iter().enumerate() {
+            if index > 0 {
+                on_chunk(SEPARATOR);
+                content.push_str(SEPARATOR);
             }
 
-            let response = match self.client.post(&url).json(&request_body).send().await {
-                Ok(r) => r,
-                Err(e) => {
-                    last_error = e.to_string();
-                    continue;
-                }
+            let mut attempts = 0;
+            let prompt = self.prompts.build(part);
+            let response = if stream {
+                self.stream_prompt(&prompt, &mut on_chunk, &mut attempts).await?
+            } else {
+                self.request_prompt(&prompt, &mut attempts).await?
             };
+            total_attempts += attempts;
 
-            let status = response.status();
+            // 言語判定の報告は部分ごとに付くため取り除き、最初の部分の判定を使う
+            let translation = self.prompts.finish_translation(response, Duration::ZERO, attempts);
+            if !stream {
+                on_chunk(&translation.content);
+            }
+            content.push_str(&translation.content);
+            detected = detected.or(translation.detected);
+        }
 
-            if status.is_success() {
-                let gemini_response: GeminiResponse = response
-                    .json()
-                    .await
-                    .context("Failed to parse Gemini response")?;
-
-                // プロンプト自体がブロックされた場合
-                if let Some(ref feedback) = gemini_response.prompt_feedback {
-                    if let Some(ref reason) = feedback.block_reason {
-                        let blocked_categories: Vec<&str> = feedback
-                            .safety_ratings
-                            .iter()
-                            .filter(|r| r.blocked == Some(true))
-                            .map(|r| r.category.as_str())
-                            .collect();
-
-                        let detail = if blocked_categories.is_empty() {
-                            reason.clone()
-                        } else {
-                            format!("{} ({})", reason, blocked_categories.join(", "))
-                        };
-
-                        anyhow::bail!(
-                            "コンテンツがブロックされました: {}\n\
-                            入力テキストがGeminiの安全性ポリシーに抵触した可能性があります。",
-                            detail
-                        );
+        Ok(Translation {
+            content,
+            elapsed: start.elapsed(),
+            attempts: total_attempts,
+            detected,
+            table_copied: false,
+        })
+    }
+
+    /// ストリーミングでプロンプトを送信し、生成された文章を少しずつ on_chunk へ渡す
+    async fn stream_prompt(
+        &self,
+        prompt: &str,
+        on_chunk: &mut impl FnMut(&str),
+        attempts: &mut u32,
+    ) -> Result<String> {
+        let mut response = self
+            .send_with_retry("streamGenerateContent?alt=sse", prompt, attempts)
+            .await?;
+
+        let mut content = String::new();
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut data = String::new();
+
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .context("ストリーミング応答の受信に失敗しました")?
+        {
+            buffer.extend_from_slice(&chunk);
+
+            // 1行ずつ取り出す（行の途中で分割されたUTF-8は次のチャンクを待つ）
+            while let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=pos).collect();
+                let line = String::from_utf8_lossy(&line);
+                let line = line.trim_end_matches(['\r', '\n']);
+
+                if line.is_empty() {
+                    // 空行でイベントが確定する
+                    let delta = parse_stream_event(&std::mem::take(&mut data))?;
+                    if !delta.is_empty() {
+                        on_chunk(&delta);
+                        content.push_str(&delta);
                     }
+                } else if let Some(value) = line.strip_prefix("data:") {
+                    if !data.is_empty() {
+                        data.push('\n');
+                    }
+                    data.push_str(value.trim_start());
                 }
+            }
+        }
 
-                // candidatesが空の場合
-                let candidate = gemini_response.candidates.first().ok_or_else(|| {
-                    anyhow::anyhow!(
-                        "APIからの応答が空です。\n\
-                        サーバー側で処理できなかった可能性があります。"
-                    )
-                })?;
-
-                // finishReasonのチェック
-                if let Some(ref reason) = candidate.finish_reason {
-                    match reason.as_str() {
-                        "STOP" => {} // 正常終了
-                        "SAFETY" => {
-                            let blocked_categories: Vec<&str> = candidate
-                                .safety_ratings
-                                .iter()
-                                .filter(|r| r.blocked == Some(true))
-                                .map(|r| r.category.as_str())
-                                .collect();
-
-                            anyhow::bail!(
-                                "安全性フィルターにより応答がブロックされました。\n\
-                                カテゴリ: {}",
-                                if blocked_categories.is_empty() {
-                                    "不明".to_string()
-                                } else {
-                                    blocked_categories.join(", ")
-                                }
-                            );
-                        }
-                        "MAX_TOKENS" => {
-                            // 途中で切れても返す（警告付き）
-                            if let Some(ref content) = candidate.content {
-                                if let Some(part) = content.parts.first() {
-                                    return Ok(format!(
-                                        "{}\n\n---\n[警告: 出力がトークン上限に達したため途中で切れています]",
-                                        part.text
-                                    ));
-                                }
-                            }
-                            anyhow::bail!("トークン上限に達しましたが、応答内容がありません。");
-                        }
-                        "RECITATION" => {
-                            anyhow::bail!(
-                                "著作権保護により応答が制限されました。\n\
-                                入力テキストに著作権で保護されたコンテンツが含まれている可能性があります。"
-                            );
-                        }
-                        other => {
-                            anyhow::bail!("予期しない終了理由: {}", other);
-                        }
-                    }
+        // 最後のイベントが空行で終わっていない場合
+        let delta = parse_stream_event(&data)?;
+        if !delta.is_empty() {
+            on_chunk(&delta);
+            content.push_str(&delta);
+        }
+
+        if content.is_empty() {
+            anyhow::bail!(
+                "APIからの応答が空です。\n\
+                サーバー側で処理できなかった可能性があります。"
+            );
+        }
+
+        Ok(content)
+    }
+
+    async fn request_prompt(&self, prompt: &str, attempts: &mut u32) -> Result<String> {
+        let response = self
+            .send_with_retry("generateContent", prompt, attempts)
+            .await?;
+
+        let gemini_response: GeminiResponse = response
+            .json()
+            .await
+            .context("Failed to parse Gemini response")?;
+
+        check_prompt_blocked(&gemini_response)?;
+
+        // candidatesが空の場合
+        let candidate = gemini_response.candidates.first().ok_or_else(|| {
+            anyhow::anyhow!(
+                "APIからの応答が空です。\n\
+                サーバー側で処理できなかった可能性があります。"
+            )
+        })?;
+
+        // finishReasonのチェック
+        if check_finish_reason(candidate)? {
+            // 途中で切れても返す（警告付き）
+            if let Some(ref content) = candidate.content {
+                if let Some(part) = content.parts.first() {
+                    return Ok(format!("{}{}", part.text, TRUNCATED_WARNING));
                 }
+            }
+            anyhow::bail!("トークン上限に達しましたが、応答内容がありません。");
+        }
 
-                // 正常なレスポンス抽出
-                let content = candidate.content.as_ref().ok_or_else(|| {
-                    anyhow::anyhow!("応答にコンテンツが含まれていません。")
-                })?;
+        // 正常なレスポンス抽出
+        let content = candidate.content.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("応答にコンテンツが含まれていません。")
+        })?;
 
-                let text = content.parts.first().ok_or_else(|| {
-                    anyhow::anyhow!("応答コンテンツが空です。")
-                })?;
+        let text = content.parts.first().ok_or_else(|| {
+            anyhow::anyhow!("応答コンテンツが空です。")
+        })?;
 
-                return Ok(text.text.clone());
+        Ok(text.text.clone())
+    }
+
+    /// リクエストを送信し、成功した応答を返す（サーバー混雑時はリトライする）
+    /// action は "generateContent" などのモデルに対する操作（クエリ付きも可）
+    async fn send_with_retry(
+        &self,
+        action: &str,
+        prompt: &str,
+        attempts: &mut u32,
+    ) -> Result<reqwest::Response> {
+        // ヘッダーが不正な場合は送信前に失敗させる
+        let headers = build_header_map(&self.extra_headers)?;
+        let cached_content = self.resolve_cached_context().await;
+        let mut request_body = self.build_request(prompt, cached_content);
+
+        let separator = if action.contains('?') { '&' } else { '?' };
+        let url = format!(
+            "{}/{}:{}{}key={}",
+            API_BASE_URL, self.model, action, separator, self.api_key
+        );
+
+        loop {
+            let response = send_with_policy(
+                self.retry_policy,
+                attempts,
+                self.retry_notifier.as_ref(),
+                || {
+                    self.client
+                        .post(&url)
+                        .headers(headers.clone())
+                        .json(&request_body)
+                },
+                // 503 または 429 はリトライ対象
+                |status| matches!(status.as_u16(), 429 | 503),
+                |status, error_text| {
+                    // 1日の無料枠を使い切った場合は、待っても回復しないためすぐに知らせる
+                    if is_daily_quota_exhausted(error_text) {
+                        anyhow::bail!(describe_api_error(status, error_text));
+                    }
+                    if status.as_u16() == 429 {
+                        Ok(describe_api_error(status, error_text))
+                    } else {
+                        describe_server_busy(status, error_text)
+                    }
+                },
+            )
+            .await?;
+
+            let status = response.status();
+
+            if status.is_success() {
+                return Ok(response);
             }
 
-            // 503 または 429 はリトライ対象
-            if status.as_u16() == 503 || status.as_u16() == 429 {
-                last_error = format!("API Error {}: サーバー過負荷、リトライ中...", status);
+            // キャッシュが期限切れ・削除済みの場合は破棄して通常送信でやり直す
+            if request_body.cached_content.is_some()
+                && matches!(status.as_u16(), 400 | 403 | 404)
+            {
+                crate::context_cache::clear();
+                request_body = self.build_request(prompt, None);
                 continue;
             }
 
             // その他のエラーは即座に失敗
             let error_text = response.text().await.unwrap_or_default();
-            anyhow::bail!("API Error {}: {}", status, error_text);
+            anyhow::bail!(describe_api_error(status, &error_text));
         }
+    }
+}
 
-        anyhow::bail!(
-            "API呼び出しに失敗しました（{}回リトライ）: {}",
-            MAX_RETRIES,
-            last_error
-        )
+/// 出力がトークン上限で途中で切れた場合に末尾へ付ける警告
+pub const TRUNCATED_WARNING: &str = "\n\n---\n[警告: 出力がトークン上限に達したため途中で切れています]";
+
+/// 安全性の評価（カテゴリごと）
+#[derive(Debug, Clone)]
+pub struct SafetyCategory {
+    pub category: String,
+    pub probability: Option<String>,
+    pub blocked: bool,
+}
+
+impl SafetyCategory {
+    fn from_rating(rating: &SafetyRating) -> Self {
+        Self {
+            category: rating.category.clone(),
+            probability: rating.probability.clone(),
+            blocked: rating.blocked == Some(true),
+        }
+    }
+
+    /// カテゴリの表示名（不明なカテゴリはAPIの名前のまま）
+    pub fn category_label(&self) -> &str {
+        match self.category.as_str() {
+            "HARM_CATEGORY_HARASSMENT" => "嫌がらせ",
+            "HARM_CATEGORY_HATE_SPEECH" => "ヘイトスピーチ",
+            "HARM_CATEGORY_SEXUALLY_EXPLICIT" => "性的な表現",
+            "HARM_CATEGORY_DANGEROUS_CONTENT" => "危険なコンテンツ",
+            "HARM_CATEGORY_CIVIC_INTEGRITY" => "選挙・市民活動",
+            other => other,
+        }
+    }
+
+    /// 該当する可能性の表示名
+    pub fn probability_label(&self) -> &str {
+        match self.probability.as_deref() {
+            Some("NEGLIGIBLE") => "ごくわずか",
+            Some("LOW") => "低",
+            Some("MEDIUM") => "中",
+            Some("HIGH") => "高",
+            Some(other) => other,
+            None => "-",
+        }
+    }
+}
+
+/// 安全性フィルター・著作権保護によるブロック（結果ウィンドウでカテゴリの一覧を表示するため構造化して返す）
+#[derive(Debug, Clone)]
+pub struct ContentBlocked {
+    /// ブロックの理由（"SAFETY"・"RECITATION"・プロンプトの blockReason）
+    pub reason: String,
+    /// 入力（プロンプト）自体がブロックされたか（falseの場合は応答がブロックされた）
+    pub prompt_blocked: bool,
+    pub ratings: Vec<SafetyCategory>,
+}
+
+impl ContentBlocked {
+    fn blocked_categories(&self) -> Vec<&str> {
+        self.ratings
+            .iter()
+            .filter(|r| r.blocked)
+            .map(|r| r.category.as_str())
+            .collect()
+    }
+
+    /// 著作権保護による制限か
+    pub fn is_recitation(&self) -> bool {
+        !self.prompt_blocked && self.reason == "RECITATION"
+    }
+}
+
+impl std::fmt::Display for ContentBlocked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let categories = self.blocked_categories();
+
+        if self.prompt_blocked {
+            let detail = if categories.is_empty() {
+                self.reason.clone()
+            } else {
+                format!("{} ({})", self.reason, categories.join(", "))
+            };
+            write!(
+                f,
+                "コンテンツがブロックされました: {}\n\
+                入力テキストがGeminiの安全性ポリシーに抵触した可能性があります。",
+                detail
+            )
+        } else if self.is_recitation() {
+            write!(
+                f,
+                "著作権保護により応答が制限されました。\n\
+                入力テキストに著作権で保護されたコンテンツが含まれている可能性があります。"
+            )
+        } else {
+            write!(
+                f,
+                "安全性フィルターにより応答がブロックされました。\n\
+                カテゴリ: {}",
+                if categories.is_empty() {
+                    "不明".to_string()
+                } else {
+                    categories.join(", ")
+                }
+            )
+        }
+    }
+}
+
+impl std::error::Error for ContentBlocked {}
+
+/// プロンプト自体がブロックされた場合はエラーにする
+fn check_prompt_blocked(response: &GeminiResponse) -> Result<()> {
+    if let Some(ref feedback) = response.prompt_feedback {
+        if let Some(ref reason) = feedback.block_reason {
+            return Err(ContentBlocked {
+                reason: reason.clone(),
+                prompt_blocked: true,
+                ratings: feedback
+                    .safety_ratings
+                    .iter()
+                    .map(SafetyCategory::from_rating)
+                    .collect(),
+            }
+            .into());
+        }
     }
+    Ok(())
+}
+
+/// finishReason を確認する（トークン上限で途中で切れた場合は true）
+fn check_finish_reason(candidate: &Candidate) -> Result<bool> {
+    let Some(ref reason) = candidate.finish_reason else {
+        return Ok(false);
+    };
+
+    match reason.as_str() {
+        "STOP" => Ok(false), // 正常終了
+        "SAFETY" | "RECITATION" => Err(ContentBlocked {
+            reason: reason.clone(),
+            prompt_blocked: false,
+            ratings: candidate
+                .safety_ratings
+                .iter()
+                .map(SafetyCategory::from_rating)
+                .collect(),
+        }
+        .into()),
+        "MAX_TOKENS" => Ok(true),
+        other => {
+            anyhow::bail!("予期しない終了理由: {}", other);
+        }
+    }
+}
+
+/// ストリーミング応答（SSE）の1イベントを解釈し、追加された文章を返す
+/// 最後のイベントに含まれる finishReason もここで確認する
+fn parse_stream_event(data: &str) -> Result<String> {
+    if data.trim().is_empty() {
+        return Ok(String::new());
+    }
+
+    let response: GeminiResponse =
+        serde_json::from_str(data).context("Failed to parse Gemini stream response")?;
+    check_prompt_blocked(&response)?;
+
+    // 使用量のみのイベントなど、候補を含まないものは読み飛ばす
+    let Some(candidate) = response.candidates.first() else {
+        return Ok(String::new());
+    };
+
+    let mut text: String = candidate
+        .content
+        .iter()
+        .flat_map(|content| content.parts.iter())
+        .map(|part| part.text.as_str())
+        .collect();
+
+    if check_finish_reason(candidate)? {
+        text.push_str(TRUNCATED_WARNING);
+    }
+
+    Ok(text)
 }
@@ -0,0 +1,70 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// 翻訳履歴の1件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub source: String,
+    pub result: String,
+    pub timestamp: u64,
+    pub model: String,
+}
+
+fn history_path() -> Result<PathBuf> {
+    let mut path = crate::config::config_dir()?;
+    path.push("history.jsonl");
+    Ok(path)
+}
+
+/// 履歴を JSON Lines 形式で追記し、max_entries 件を超えた古い履歴を削除する
+/// max_entries が0の場合は記録しない（失敗しても翻訳には影響させない）
+pub fn append(source: &str, result: &str, model: &str, max_entries: usize) {
+    if max_entries == 0 {
+        return;
+    }
+
+    let entry = HistoryEntry {
+        source: source.to_string(),
+        result: result.to_string(),
+        timestamp: crate::context_cache::now_secs(),
+        model: model.to_string(),
+    };
+
+    let write = || -> Result<()> {
+        let path = history_path()?;
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        drop(file);
+
+        // 上限を超えた場合は新しいものだけを残して書き直す
+        let content = fs::read_to_string(&path)?;
+        let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+        if lines.len() > max_entries {
+            let kept = lines[lines.len() - max_entries..].join("\n");
+            fs::write(&path, kept + "\n")?;
+        }
+        Ok(())
+    };
+
+    if let Err(e) = write() {
+        eprintln!("Failed to record history: {}", e);
+    }
+}
+
+/// 保存されている履歴を古い順に読み込む（壊れた行は読み飛ばす）
+pub fn load() -> Vec<HistoryEntry> {
+    let Ok(path) = history_path() else {
+        return Vec::new();
+    };
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
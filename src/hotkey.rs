@@ -1,19 +1,62 @@
-use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
 use std::time::{Duration, Instant};
 use windows::Win32::UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VK_CONTROL, VK_MENU, VK_SHIFT};
 
 use crate::config::Hotkey;
 
 /// ダブルプレスの有効期間（この時間内に2回目を押す必要がある）
-const DOUBLE_PRESS_WINDOW_MS: u128 = 500;
+pub const DOUBLE_PRESS_WINDOW_MS: u64 = 500;
 
 /// キー検知後のデバウンス遅延（連続トリガー防止）
 const DEBOUNCE_DELAY_MS: u64 = 200;
 
 static LAST_KEY_STATE: AtomicBool = AtomicBool::new(false);
-static KEY_PRESS_COUNT: AtomicU8 = AtomicU8::new(0);
-static LAST_KEY_PRESS: Mutex<Option<Instant>> = Mutex::new(None);
+static DOUBLE_PRESS: Mutex<DoublePressState> = Mutex::new(DoublePressState::new());
+
+/// Mutexをロックする（poison状態なら中身を取り戻して復旧する）
+/// フック内で一度パニックしただけで、以降のホットキー検出がすべて止まらないようにする
+/// （アプリの実行中ずっと使う他の状態も同じ理由でこれを使う）
+pub fn lock_recovering<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| {
+        eprintln!("Recovered from a poisoned mutex");
+        mutex.clear_poison();
+        poisoned.into_inner()
+    })
+}
+
+/// ダブルプレス検出の状態（1回目の押下時刻のみを保持）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DoublePressState {
+    first_press: Option<Instant>,
+}
+
+impl DoublePressState {
+    pub const fn new() -> Self {
+        Self { first_press: None }
+    }
+}
+
+/// ダブルプレスの状態遷移（純粋関数）
+/// 押下時刻 now を受け取り、次の状態とダブルプレス成立の有無を返す。
+/// 1回目から window 未満で押された場合のみ成立し、ちょうど window 経過時は1回目として数え直す。
+pub fn next_double_press(
+    state: DoublePressState,
+    now: Instant,
+    window: Duration,
+) -> (DoublePressState, bool) {
+    match state.first_press {
+        Some(first) if now.saturating_duration_since(first) < window => {
+            (DoublePressState::new(), true)
+        }
+        _ => (
+            DoublePressState {
+                first_press: Some(now),
+            },
+            false,
+        ),
+    }
+}
 
 /// 指定されたホットキーが押されたかをチェック
 pub fn is_hotkey_pressed(hotkey: &Hotkey) -> bool {
@@ -71,46 +114,110 @@ fn check_double_press_hotkey(hotkey: &Hotkey) -> bool {
 
     // エッジ検出: 修飾キー + メインキーが押された瞬間
     if modifiers_match && main_key_state && !last_state {
-        let now = Instant::now();
-
-        let mut last_press = match LAST_KEY_PRESS.lock() {
-            Ok(guard) => guard,
-            Err(_) => return false, // poisoned mutex
-        };
-
-        match *last_press {
-            Some(last_time) => {
-                let elapsed = now.duration_since(last_time);
-
-                // 規定時間内に2回目が押された
-                if elapsed.as_millis() < DOUBLE_PRESS_WINDOW_MS {
-                    let count = KEY_PRESS_COUNT.fetch_add(1, Ordering::SeqCst) + 1;
-
-                    if count >= 2 {
-                        // ダブルプレス成功
-                        KEY_PRESS_COUNT.store(0, Ordering::SeqCst);
-                        *last_press = None;
-                        drop(last_press);
-                        LAST_KEY_STATE.store(main_key_state, Ordering::SeqCst);
-
-                        std::thread::sleep(Duration::from_millis(DEBOUNCE_DELAY_MS));
-                        return true;
-                    }
-                } else {
-                    // タイムアウト、カウントリセット
-                    KEY_PRESS_COUNT.store(1, Ordering::SeqCst);
-                }
-
-                *last_press = Some(now);
-            }
-            None => {
-                // 初回のキー押下
-                *last_press = Some(now);
-                KEY_PRESS_COUNT.store(1, Ordering::SeqCst);
-            }
+        let mut state = lock_recovering(&DOUBLE_PRESS);
+
+        let (next, triggered) = next_double_press(
+            *state,
+            Instant::now(),
+            Duration::from_millis(DOUBLE_PRESS_WINDOW_MS),
+        );
+        *state = next;
+
+        if triggered {
+            // ダブルプレス成功
+            drop(state);
+            LAST_KEY_STATE.store(main_key_state, Ordering::SeqCst);
+
+            std::thread::sleep(Duration::from_millis(DEBOUNCE_DELAY_MS));
+            return true;
         }
     }
 
     LAST_KEY_STATE.store(main_key_state, Ordering::SeqCst);
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_millis(DOUBLE_PRESS_WINDOW_MS);
+
+    #[test]
+    fn lock_recovering_recovers_poisoned_mutex() {
+        let mutex = Mutex::new(DoublePressState::new());
+        let pressed = Instant::now();
+
+        // ロックを保持したまま、状態を書き換えた直後にパニックしたスレッドを再現する
+        std::thread::scope(|scope| {
+            let result = scope
+                .spawn(|| {
+                    let mut state = mutex.lock().unwrap();
+                    state.first_press = Some(pressed);
+                    panic!("panic while holding the lock");
+                })
+                .join();
+            assert!(result.is_err());
+        });
+        assert!(mutex.is_poisoned());
+
+        // 書き換え済みの中身を取り戻し、以降は通常どおりロックできる
+        assert_eq!(lock_recovering(&mutex).first_press, Some(pressed));
+        assert!(!mutex.is_poisoned());
+        assert!(mutex.lock().is_ok());
+    }
+
+    /// 1回目の押下から elapsed 後に2回目を押した場合に成立するか
+    fn second_press_after(elapsed: Duration) -> bool {
+        let start = Instant::now();
+        let (state, first) = next_double_press(DoublePressState::new(), start, WINDOW);
+        assert!(!first);
+        next_double_press(state, start + elapsed, WINDOW).1
+    }
+
+    #[test]
+    fn second_press_inside_window_triggers() {
+        assert!(second_press_after(Duration::from_millis(100)));
+        assert!(second_press_after(WINDOW - Duration::from_millis(1)));
+    }
+
+    #[test]
+    fn second_press_at_or_after_window_does_not_trigger() {
+        assert!(!second_press_after(WINDOW));
+        assert!(!second_press_after(WINDOW + Duration::from_millis(1)));
+    }
+
+    #[test]
+    fn expired_press_counts_as_new_first_press() {
+        let start = Instant::now();
+        let (state, _) = next_double_press(DoublePressState::new(), start, WINDOW);
+
+        // 長時間放置した後の押下は1回目として数え直し、その直後の押下で成立する
+        let later = start + Duration::from_secs(3600);
+        let (state, triggered) = next_double_press(state, later, WINDOW);
+        assert!(!triggered);
+        assert_eq!(
+            state,
+            DoublePressState {
+                first_press: Some(later)
+            }
+        );
+
+        let (_, triggered) = next_double_press(state, later + Duration::from_millis(200), WINDOW);
+        assert!(triggered);
+    }
+
+    #[test]
+    fn state_resets_after_trigger() {
+        let start = Instant::now();
+        let (state, _) = next_double_press(DoublePressState::new(), start, WINDOW);
+        let (state, triggered) =
+            next_double_press(state, start + Duration::from_millis(100), WINDOW);
+        assert!(triggered);
+        assert_eq!(state, DoublePressState::new());
+
+        // 3回目の押下は新しい1回目になる（連打で続けて成立しない）
+        let (_, triggered) = next_double_press(state, start + Duration::from_millis(200), WINDOW);
+        assert!(!triggered);
+    }
+}
@@ -1,20 +1,22 @@
-use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
 use windows::core::PCWSTR;
 use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
 use windows::Win32::UI::Input::KeyboardAndMouse::{
-    VK_CONTROL, VK_MENU, VK_SHIFT, VIRTUAL_KEY,
+    VIRTUAL_KEY, VK_CONTROL, VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_MENU, VK_RCONTROL, VK_RMENU,
+    VK_RSHIFT, VK_SHIFT,
 };
 use windows::Win32::UI::WindowsAndMessaging::{
     CallNextHookEx, DispatchMessageW, GetMessageW, SetWindowsHookExW, UnhookWindowsHookEx,
-    HHOOK, KBDLLHOOKSTRUCT, MSG, WH_KEYBOARD_LL, WM_KEYDOWN, WM_SYSKEYDOWN,
+    HHOOK, KBDLLHOOKSTRUCT, MSG, MSLLHOOKSTRUCT, WH_KEYBOARD_LL, WH_MOUSE_LL, WM_KEYDOWN,
+    WM_SYSKEYDOWN, WM_XBUTTONDOWN,
 };
 
 use crate::config::Hotkey;
-
-/// ダブルプレスの有効期間（この時間内に2回目を押す必要がある）
-const DOUBLE_PRESS_WINDOW_MS: u128 = 500;
+use crate::hotkey::{
+    lock_recovering, next_double_press, DoublePressState, DOUBLE_PRESS_WINDOW_MS,
+};
 
 /// 現在監視中のホットキー
 static CURRENT_HOTKEY: Mutex<Option<Hotkey>> = Mutex::new(None);
@@ -22,9 +24,19 @@ static CURRENT_HOTKEY: Mutex<Option<Hotkey>> = Mutex::new(None);
 /// ホットキーが押されたフラグ
 static HOTKEY_TRIGGERED: AtomicBool = AtomicBool::new(false);
 
+/// リセット用ホットキー（すべて中止）
+static RESET_HOTKEY: Mutex<Option<Hotkey>> = Mutex::new(None);
+static RESET_TRIGGERED: AtomicBool = AtomicBool::new(false);
+
+/// 一時停止・再開の切り替え用ホットキー
+static PAUSE_HOTKEY: Mutex<Option<Hotkey>> = Mutex::new(None);
+static PAUSE_TRIGGERED: AtomicBool = AtomicBool::new(false);
+
 /// ダブルプレス検出用
-static KEY_PRESS_COUNT: AtomicU8 = AtomicU8::new(0);
-static LAST_KEY_PRESS: Mutex<Option<Instant>> = Mutex::new(None);
+static DOUBLE_PRESS: Mutex<DoublePressState> = Mutex::new(DoublePressState::new());
+
+/// ダブルプレスとみなす間隔（ミリ秒、設定の保存時に更新される）
+static DOUBLE_PRESS_WINDOW: AtomicU64 = AtomicU64::new(DOUBLE_PRESS_WINDOW_MS);
 
 /// 修飾キーの状態
 static CTRL_PRESSED: AtomicBool = AtomicBool::new(false);
@@ -39,32 +51,32 @@ unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARA
 
         // キーダウンイベントのみ処理
         if wparam.0 as u32 == WM_KEYDOWN || wparam.0 as u32 == WM_SYSKEYDOWN {
-            // 修飾キーの状態を追跡
+            // 修飾キーの状態を追跡（Low-Level フックでは左右別のキーコードが届く）
             match vk_code {
-                VK_CONTROL => {
+                VK_CONTROL | VK_LCONTROL | VK_RCONTROL => {
                     CTRL_PRESSED.store(true, Ordering::SeqCst);
                 }
-                VK_MENU => {
+                VK_MENU | VK_LMENU | VK_RMENU => {
                     ALT_PRESSED.store(true, Ordering::SeqCst);
                 }
-                VK_SHIFT => {
+                VK_SHIFT | VK_LSHIFT | VK_RSHIFT => {
                     SHIFT_PRESSED.store(true, Ordering::SeqCst);
                 }
                 _ => {
                     // メインキーが押された
-                    check_hotkey_match(kb.vkCode as i32);
+                    check_hotkey_match(kb.vkCode as i32, Instant::now());
                 }
             }
         } else {
             // キーアップイベント
             match vk_code {
-                VK_CONTROL => {
+                VK_CONTROL | VK_LCONTROL | VK_RCONTROL => {
                     CTRL_PRESSED.store(false, Ordering::SeqCst);
                 }
-                VK_MENU => {
+                VK_MENU | VK_LMENU | VK_RMENU => {
                     ALT_PRESSED.store(false, Ordering::SeqCst);
                 }
-                VK_SHIFT => {
+                VK_SHIFT | VK_LSHIFT | VK_RSHIFT => {
                     SHIFT_PRESSED.store(false, Ordering::SeqCst);
                 }
                 _ => {}
@@ -75,33 +87,71 @@ unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARA
     CallNextHookEx(HHOOK(0), code, wparam, lparam)
 }
 
+/// Low-Level マウスフックプロシージャ（サイドボタンのみ処理）
+unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 && wparam.0 as u32 == WM_XBUTTONDOWN {
+        let ms = *(lparam.0 as *const MSLLHOOKSTRUCT);
+        // 上位ワードに XBUTTON1 / XBUTTON2 が入る
+        let button = ms.mouseData >> 16;
+        check_mouse_match(button, Instant::now());
+    }
+
+    CallNextHookEx(HHOOK(0), code, wparam, lparam)
+}
+
 /// ホットキーのマッチをチェック
-fn check_hotkey_match(vk_code: i32) {
-    let hotkey = match CURRENT_HOTKEY.lock() {
-        Ok(guard) => match *guard {
-            Some(hk) => hk,
-            None => return,
-        },
-        Err(_) => return,
-    };
+/// now は押下時刻（テスト時に任意の時刻を渡せるよう引数で受け取る）
+fn check_hotkey_match(vk_code: i32, now: Instant) {
+    let (ctrl, alt, shift) = modifier_state();
+    check_trigger(|hotkey| hotkey.matches(vk_code, ctrl, alt, shift), now);
+}
 
-    // キーコードが一致するか
-    if vk_code != hotkey.key_code {
-        return;
+/// マウスのサイドボタンのマッチをチェック
+fn check_mouse_match(button: u32, now: Instant) {
+    let (ctrl, alt, shift) = modifier_state();
+    check_trigger(|hotkey| hotkey.matches_mouse(button, ctrl, alt, shift), now);
+}
+
+/// 修飾キー（Ctrl, Alt, Shift）の状態
+fn modifier_state() -> (bool, bool, bool) {
+    (
+        CTRL_PRESSED.load(Ordering::SeqCst),
+        ALT_PRESSED.load(Ordering::SeqCst),
+        SHIFT_PRESSED.load(Ordering::SeqCst),
+    )
+}
+
+/// 押された入力がリセット用・翻訳用のホットキーに一致するか判定し、トリガーを立てる
+fn check_trigger(matches: impl Fn(&Hotkey) -> bool, now: Instant) {
+    // リセット用ホットキーは常にシングルプレスで判定
+    if let Some(reset) = *lock_recovering(&RESET_HOTKEY) {
+        if matches(&reset) {
+            RESET_TRIGGERED.store(true, Ordering::SeqCst);
+            return;
+        }
+    }
+
+    // 一時停止・再開の切り替えもシングルプレスで判定
+    if let Some(pause) = *lock_recovering(&PAUSE_HOTKEY) {
+        if matches(&pause) {
+            PAUSE_TRIGGERED.store(true, Ordering::SeqCst);
+            return;
+        }
     }
 
-    // 修飾キーの状態が一致するか
-    let ctrl = CTRL_PRESSED.load(Ordering::SeqCst);
-    let alt = ALT_PRESSED.load(Ordering::SeqCst);
-    let shift = SHIFT_PRESSED.load(Ordering::SeqCst);
+    let Some(hotkey) = *lock_recovering(&CURRENT_HOTKEY) else {
+        return;
+    };
 
-    if ctrl != hotkey.ctrl || alt != hotkey.alt || shift != hotkey.shift {
+    // キーコードと修飾キーが一致しない押下は、ダブルプレスの途中状態を破棄する
+    if !matches(&hotkey) {
+        reset_double_press();
         return;
     }
 
     // ダブルプレスチェック
     if hotkey.is_double_press {
-        if check_double_press() {
+        if check_double_press(now) {
             HOTKEY_TRIGGERED.store(true, Ordering::SeqCst);
         }
     } else {
@@ -109,51 +159,34 @@ fn check_hotkey_match(vk_code: i32) {
     }
 }
 
-/// ダブルプレスをチェック
-fn check_double_press() -> bool {
-    let now = Instant::now();
-
-    let mut last_press = match LAST_KEY_PRESS.lock() {
-        Ok(guard) => guard,
-        Err(_) => return false,
-    };
-
-    match *last_press {
-        Some(last_time) => {
-            let elapsed = now.duration_since(last_time);
-
-            if elapsed.as_millis() < DOUBLE_PRESS_WINDOW_MS {
-                let count = KEY_PRESS_COUNT.fetch_add(1, Ordering::SeqCst) + 1;
+/// ダブルプレスの途中状態をリセット
+fn reset_double_press() {
+    *lock_recovering(&DOUBLE_PRESS) = DoublePressState::new();
+}
 
-                if count >= 2 {
-                    // ダブルプレス成功
-                    KEY_PRESS_COUNT.store(0, Ordering::SeqCst);
-                    *last_press = None;
-                    return true;
-                }
-            } else {
-                // タイムアウト、カウントリセット
-                KEY_PRESS_COUNT.store(1, Ordering::SeqCst);
-            }
+/// ダブルプレスをチェック
+fn check_double_press(now: Instant) -> bool {
+    let mut state = lock_recovering(&DOUBLE_PRESS);
 
-            *last_press = Some(now);
-        }
-        None => {
-            // 初回のキー押下
-            *last_press = Some(now);
-            KEY_PRESS_COUNT.store(1, Ordering::SeqCst);
-        }
-    }
+    let window = Duration::from_millis(DOUBLE_PRESS_WINDOW.load(Ordering::SeqCst));
+    let (next, triggered) = next_double_press(*state, now, window);
+    *state = next;
 
-    false
+    triggered
 }
 
 /// ホットキー監視を開始
-pub fn start_hook(hotkey: Hotkey) -> windows::core::Result<()> {
+pub fn start_hook(
+    hotkey: Hotkey,
+    reset_hotkey: Option<Hotkey>,
+    pause_hotkey: Option<Hotkey>,
+    double_press_window_ms: u64,
+) -> windows::core::Result<()> {
     // 現在のホットキーを設定
-    if let Ok(mut guard) = CURRENT_HOTKEY.lock() {
-        *guard = Some(hotkey);
-    }
+    *lock_recovering(&CURRENT_HOTKEY) = Some(hotkey);
+    set_double_press_window(double_press_window_ms);
+    *lock_recovering(&RESET_HOTKEY) = reset_hotkey;
+    *lock_recovering(&PAUSE_HOTKEY) = pause_hotkey;
 
     unsafe {
         // Low-Level キーボードフックを設定
@@ -163,6 +196,17 @@ pub fn start_hook(hotkey: Hotkey) -> windows::core::Result<()> {
             return Err(windows::core::Error::from_win32());
         }
 
+        // マウスのサイドボタンを使う場合のみマウスフックを設定（修飾キーはキーボードフックで追跡）
+        let uses_mouse = [Some(hotkey), reset_hotkey, pause_hotkey]
+            .iter()
+            .flatten()
+            .any(|hk| hk.mouse_button.is_some());
+        let mouse_hook = if uses_mouse {
+            Some(SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_proc), None, 0)?)
+        } else {
+            None
+        };
+
         // メッセージループ
         let mut msg = MSG::default();
         while GetMessageW(&mut msg, None, 0, 0).as_bool() {
@@ -171,12 +215,100 @@ pub fn start_hook(hotkey: Hotkey) -> windows::core::Result<()> {
 
         // クリーンアップ
         let _ = UnhookWindowsHookEx(hook);
+        if let Some(mouse_hook) = mouse_hook {
+            let _ = UnhookWindowsHookEx(mouse_hook);
+        }
     }
 
     Ok(())
 }
 
+/// ダブルプレスとみなす間隔を変更する（監視中でも次の押下から反映される）
+pub fn set_double_press_window(ms: u64) {
+    DOUBLE_PRESS_WINDOW.store(ms, Ordering::SeqCst);
+}
+
 /// ホットキーがトリガーされたかチェック（メインスレッドから呼ぶ）
 pub fn check_triggered() -> bool {
     HOTKEY_TRIGGERED.swap(false, Ordering::SeqCst)
 }
+
+/// リセット用ホットキーがトリガーされたかチェック（メインスレッドから呼ぶ）
+pub fn check_reset_triggered() -> bool {
+    RESET_TRIGGERED.swap(false, Ordering::SeqCst)
+}
+
+/// 一時停止・再開用ホットキーがトリガーされたかチェック（メインスレッドから呼ぶ）
+pub fn check_pause_triggered() -> bool {
+    PAUSE_TRIGGERED.swap(false, Ordering::SeqCst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::MutexGuard;
+
+    /// 仮想キーコード: C
+    const VK_C: i32 = 0x43;
+
+    /// フックの状態はグローバルなため、テストを1つずつ実行する
+    static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    /// 指定のホットキーだけを監視し、修飾キーはそのホットキーの通りに押された状態にする
+    fn setup(hotkey: Hotkey) -> MutexGuard<'static, ()> {
+        let guard = lock_recovering(&TEST_LOCK);
+        *lock_recovering(&CURRENT_HOTKEY) = Some(hotkey);
+        *lock_recovering(&RESET_HOTKEY) = None;
+        *lock_recovering(&PAUSE_HOTKEY) = None;
+        set_double_press_window(DOUBLE_PRESS_WINDOW_MS);
+        reset_double_press();
+        CTRL_PRESSED.store(hotkey.ctrl, Ordering::SeqCst);
+        ALT_PRESSED.store(hotkey.alt, Ordering::SeqCst);
+        SHIFT_PRESSED.store(hotkey.shift, Ordering::SeqCst);
+        HOTKEY_TRIGGERED.store(false, Ordering::SeqCst);
+        guard
+    }
+
+    #[test]
+    fn double_press_detection_survives_poisoned_state() {
+        let _guard = setup(Hotkey::default());
+
+        // ロックを保持したままパニックしたフックを再現する
+        let _ = std::thread::spawn(|| {
+            let _state = DOUBLE_PRESS.lock().unwrap();
+            panic!("panic while holding the double-press state");
+        })
+        .join();
+        assert!(DOUBLE_PRESS.is_poisoned());
+
+        let start = Instant::now();
+        check_hotkey_match(VK_C, start);
+        check_hotkey_match(VK_C, start + Duration::from_millis(100));
+
+        assert!(check_triggered());
+        assert!(!DOUBLE_PRESS.is_poisoned());
+    }
+
+    #[test]
+    fn mismatched_press_resets_double_press() {
+        let _guard = setup(Hotkey::default());
+        let start = Instant::now();
+
+        check_hotkey_match(VK_C, start);
+        // Ctrl+V を挟んだ場合は Ctrl+C+C とみなさない
+        check_hotkey_match(0x56, start + Duration::from_millis(100));
+        check_hotkey_match(VK_C, start + Duration::from_millis(200));
+        assert!(!check_triggered());
+    }
+
+    #[test]
+    fn press_without_modifiers_does_not_match() {
+        let _guard = setup(Hotkey::default());
+        CTRL_PRESSED.store(false, Ordering::SeqCst);
+        let start = Instant::now();
+
+        check_hotkey_match(VK_C, start);
+        check_hotkey_match(VK_C, start + Duration::from_millis(100));
+        assert!(!check_triggered());
+    }
+}
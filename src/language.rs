@@ -0,0 +1,285 @@
+use std::sync::Mutex;
+
+use crate::config::LanguagePair;
+
+/// 直前の翻訳で検出された原文の言語（スマートスワップ用）
+static LAST_SOURCE_LANGUAGE: Mutex<Option<String>> = Mutex::new(None);
+
+/// 既定の翻訳先言語
+pub const DEFAULT_TARGET_LANGUAGE: &str = "日本語";
+
+/// 文字体系
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Script {
+    Latin,
+    Japanese,
+    Han,
+    Korean,
+    Thai,
+    Arabic,
+    Cyrillic,
+}
+
+impl Script {
+    /// 1文字の文字体系を判定（空白・記号などはNone）
+    pub fn of(c: char) -> Option<Script> {
+        match c {
+            'A'..='Z' | 'a'..='z' | '\u{00C0}'..='\u{024F}' => Some(Script::Latin),
+            '\u{3040}'..='\u{30FF}' => Some(Script::Japanese),
+            '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' => Some(Script::Han),
+            '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' => Some(Script::Korean),
+            '\u{0E00}'..='\u{0E7F}' => Some(Script::Thai),
+            '\u{0600}'..='\u{06FF}' => Some(Script::Arabic),
+            '\u{0400}'..='\u{04FF}' => Some(Script::Cyrillic),
+            _ => None,
+        }
+    }
+}
+
+/// テキストに含まれる文字体系を出現数の多い順に返す（先頭が主要な文字体系）
+/// かなが含まれる場合、漢字は日本語として数える
+pub fn scripts_by_frequency(text: &str) -> Vec<Script> {
+    script_counts(text)
+        .into_iter()
+        .map(|(script, _)| script)
+        .collect()
+}
+
+/// 文字体系ごとの文字数（多い順）
+fn script_counts(text: &str) -> Vec<(Script, usize)> {
+    let has_kana = is_japanese(text);
+    let mut counts: Vec<(Script, usize)> = Vec::new();
+
+    for script in text.chars().filter_map(Script::of) {
+        let script = if has_kana && script == Script::Han {
+            Script::Japanese
+        } else {
+            script
+        };
+
+        match counts.iter_mut().find(|(s, _)| *s == script) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((script, 1)),
+        }
+    }
+
+    counts.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    counts
+}
+
+/// 原文の主な言語を文字体系から推定する（翻訳不要の判定用）
+/// 文字の8割以上が同じ文字体系の場合のみ判定し、言語を特定できないラテン文字はNone
+pub fn guess_dominant_language(text: &str) -> Option<&'static str> {
+    let counts = script_counts(text);
+    let total: usize = counts.iter().map(|(_, count)| count).sum();
+    let &(script, count) = counts.first()?;
+    if count * 10 < total * 8 {
+        return None;
+    }
+
+    match script {
+        Script::Japanese => Some("日本語"),
+        Script::Han => Some("中国語"),
+        Script::Korean => Some("韓国語"),
+        Script::Thai => Some("タイ語"),
+        Script::Arabic => Some("アラビア語"),
+        Script::Cyrillic => Some("ロシア語"),
+        Script::Latin => None,
+    }
+}
+
+/// 原文がすでに翻訳先の言語で書かれている場合、その言語名を返す
+pub fn already_in_target(text: &str, target: &str) -> Option<&'static str> {
+    let source = guess_dominant_language(text)?;
+    let same = if source == DEFAULT_TARGET_LANGUAGE {
+        is_japanese_target(target)
+    } else {
+        language_code(target.trim()) == language_code(source)
+    };
+    same.then_some(source)
+}
+
+/// 入力が漢字1文字だけの場合にその文字を返す（前後の空白は無視）
+pub fn single_cjk_character(text: &str) -> Option<char> {
+    let mut chars = text.trim().chars();
+    let c = chars.next()?;
+    if chars.next().is_some() {
+        return None;
+    }
+    (Script::of(c) == Some(Script::Han)).then_some(c)
+}
+
+/// テキストが日本語かどうか（ひらがな・カタカナを含むか）を判定
+pub fn is_japanese(text: &str) -> bool {
+    text.chars()
+        .any(|c| matches!(c, '\u{3040}'..='\u{309F}' | '\u{30A0}'..='\u{30FF}'))
+}
+
+/// 文字種から言語名を推定（判別が確実な文字体系のみ）
+pub fn guess_language_name(text: &str) -> Option<&'static str> {
+    for c in text.chars() {
+        match c {
+            '\u{3040}'..='\u{30FF}' => return Some("日本語"),
+            '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' => return Some("韓国語"),
+            '\u{0E00}'..='\u{0E7F}' => return Some("タイ語"),
+            '\u{0400}'..='\u{04FF}' => return Some("ロシア語"),
+            '\u{0600}'..='\u{06FF}' => return Some("アラビア語"),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 主な言語名の略号（ウィンドウタイトル用）
+const LANGUAGE_CODES: &[(&str, &str)] = &[
+    ("日本語", "JA"),
+    ("英語", "EN"),
+    ("中国語", "ZH"),
+    ("韓国語", "KO"),
+    ("フランス語", "FR"),
+    ("ドイツ語", "DE"),
+    ("スペイン語", "ES"),
+    ("イタリア語", "IT"),
+    ("ポルトガル語", "PT"),
+    ("ロシア語", "RU"),
+    ("タイ語", "TH"),
+    ("アラビア語", "AR"),
+    ("ベトナム語", "VI"),
+];
+
+/// 言語名を略号にする（一覧にない言語は名前のまま）
+pub fn language_code(name: &str) -> String {
+    LANGUAGE_CODES
+        .iter()
+        .find(|(language, _)| name.starts_with(language))
+        .map(|(_, code)| code.to_string())
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// 翻訳先の言語が日本語か（空の場合は既定の日本語とみなす）
+pub fn is_japanese_target(target: &str) -> bool {
+    let target = target.trim();
+    target.is_empty() || language_code(target) == "JA" || target.eq_ignore_ascii_case("japanese")
+}
+
+/// 原文が翻訳先言語そのものだった場合の翻訳先
+pub fn fallback_target(target: &str) -> &'static str {
+    if target == "英語" {
+        DEFAULT_TARGET_LANGUAGE
+    } else {
+        "英語"
+    }
+}
+
+/// 原文の言語から翻訳先の言語を推定する（プロンプトの翻訳方向の指示と同じ規則）
+pub fn expected_target(source: &str, target: &str, pair: Option<&LanguagePair>) -> String {
+    match pair {
+        Some(pair) if source == pair.a => pair.b.clone(),
+        Some(pair) => pair.a.clone(),
+        None if source == target => fallback_target(target).to_string(),
+        None => target.to_string(),
+    }
+}
+
+/// テキストが指定した言語で書かれているか（文字種から判別できる言語のみ）
+fn is_written_in(text: &str, language: &str) -> bool {
+    guess_language_name(text) == Some(language)
+}
+
+/// 翻訳結果・原文から原文の言語名を求める
+/// モデルの報告 → 詳細モードの「検出言語」 → 文字種からの推定 の順に使う
+pub fn resolve_source_language(
+    detected: Option<&DetectedLanguage>,
+    result: &str,
+    source: &str,
+) -> Option<String> {
+    detected
+        .map(|d| d.name.clone())
+        .or_else(|| parse_detected_language(result))
+        .or_else(|| guess_language_name(source).map(|s| s.to_string()))
+}
+
+/// 言語判定の信頼度を報告させる行の接頭辞
+pub const DETECTION_REPORT_PREFIX: &str = "[検出]";
+
+/// モデルが判定した原文の言語と信頼度
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedLanguage {
+    pub name: String,
+    /// 0〜100
+    pub confidence: u8,
+}
+
+/// 応答の最後の「[検出] 言語名 | 信頼度」行を取り出し、その行を除いた本文と合わせて返す
+pub fn take_detection_report(content: &str) -> (String, Option<DetectedLanguage>) {
+    let trimmed = content.trim_end();
+    let (body, last_line) = match trimmed.rsplit_once('\n') {
+        Some((body, last)) => (body, last),
+        None => ("", trimmed),
+    };
+
+    let parsed = last_line
+        .trim()
+        .strip_prefix(DETECTION_REPORT_PREFIX)
+        .and_then(|rest| {
+            let (name, confidence) = rest.split_once('|')?;
+            let confidence = confidence
+                .trim()
+                .trim_end_matches(['%', '％'])
+                .trim()
+                .parse::<u8>()
+                .ok()?;
+            Some(DetectedLanguage {
+                name: name.trim().to_string(),
+                confidence: confidence.min(100),
+            })
+        });
+
+    match parsed {
+        Some(detected) => (body.trim_end().to_string(), Some(detected)),
+        None => (content.to_string(), None),
+    }
+}
+
+/// 詳細モードの応答から「検出言語: xxx」を抽出
+pub fn parse_detected_language(result: &str) -> Option<String> {
+    result.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix("検出言語")?;
+        let name = rest.trim_start_matches([':', '：', ' ']).trim();
+        let name = name.trim_matches(|c| c == '[' || c == ']' || c == '*');
+        if name.is_empty() {
+            None
+        } else {
+            Some(name.to_string())
+        }
+    })
+}
+
+/// 翻訳完了後に原文の言語を記録
+/// 原文が翻訳先言語の場合（スワップで訳し戻した場合）は記録しない
+pub fn remember_source_language(source: &str, result: &str, target: &str) {
+    if is_written_in(source, target) {
+        return;
+    }
+
+    let detected = parse_detected_language(result)
+        .or_else(|| guess_language_name(source).map(|s| s.to_string()));
+
+    if let Some(lang) = detected {
+        *crate::hotkey::lock_recovering(&LAST_SOURCE_LANGUAGE) = Some(lang);
+    }
+}
+
+/// スマートスワップ時の翻訳先言語を決定
+/// 入力が既に翻訳先言語の場合、直前に検出した原文の言語を返す
+pub fn swap_target_for(text: &str, target: &str) -> Option<String> {
+    if !is_written_in(text, target) {
+        return None;
+    }
+
+    let guard = crate::hotkey::lock_recovering(&LAST_SOURCE_LANGUAGE);
+    guard
+        .as_ref()
+        .filter(|lang| lang.as_str() != target)
+        .cloned()
+}
@@ -2,30 +2,60 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use anyhow::Result;
+use std::collections::VecDeque;
 use std::env;
 use std::io::{self, Read};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::mpsc;
 use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::runtime::Runtime;
 use windows::Win32::Foundation::HWND;
 use windows::Win32::System::Threading::{CreateMutexW, OpenMutexW, SYNCHRONIZATION_SYNCHRONIZE};
-use windows::Win32::UI::WindowsAndMessaging::{MessageBoxW, MB_ICONWARNING, MB_OK};
-use windows::core::w;
+use windows::Win32::UI::HiDpi::{
+    SetProcessDpiAwarenessContext, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    MessageBoxW, IDNO, IDYES, MB_ICONQUESTION, MB_ICONWARNING, MB_OK, MB_YESNO, MB_YESNOCANCEL,
+};
+use windows::core::{w, HSTRING};
+
+use provider::TranslationProvider;
 
 /// ホットキー監視のポーリング間隔
 const HOTKEY_POLL_INTERVAL_MS: u64 = 100;
 
+/// 翻訳を一時停止中か（一時停止用ホットキーで切り替え）
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+mod cache;
+mod cancel;
+mod chunk;
 mod clipboard;
+mod clipboard_watch;
 mod config;
+mod content_kind;
+mod context_menu;
+mod context_cache;
 mod credential;
+mod diff;
 mod gemini;
+mod history;
 mod hotkey;
 mod hotkey_hook;
+mod language;
+mod metrics;
+mod model_cache;
+mod native_host;
+mod network;
 mod notification;
+mod postprocess;
+mod prompt;
+mod provider;
+mod send_to_app;
 mod startup;
+mod table;
 mod ui;
 
 /// シングルインスタンスチェック
@@ -60,7 +90,29 @@ fn print_help() {
     println!("  clipboard-translator            通常起動（バックグラウンド）");
     println!("  clipboard-translator --setup    設定画面を開く");
     println!("  clipboard-translator --install  スタートアップに登録");
+    println!("                 --task           管理者権限でタスクスケジューラに登録");
     println!("  clipboard-translator --uninstall スタートアップから削除");
+    println!("  clipboard-translator --reset    保存データ（設定・APIキー・履歴・キャッシュ）を全削除");
+    println!("                 --keep-startup   --reset 時にスタートアップ登録を残す");
+    println!("                 --yes            --reset 時に確認せずに削除");
+    println!("  clipboard-translator --native-host ブラウザ拡張機能のネイティブメッセージングホストとして動作");
+    println!("  clipboard-translator --translate-text <テキスト>  テキストを翻訳して表示");
+    println!("  clipboard-translator --file <パス>  ファイルの内容を翻訳して表示");
+    println!("  clipboard-translator --batch <パス>  ファイルを1行ずつ翻訳し、<パス>.translated に書き出す");
+    println!("                 --delimiter <文字列> 改行の代わりに項目を区切る文字列（\\n・\\t も可）");
+    println!("                 --jobs <件数>    同時に翻訳する件数（既定は3件）");
+    println!("                 --stdout         ファイルの代わりに標準出力へ書き出す");
+    println!("  clipboard-translator --register-context-menu    テキストファイルの右クリックメニューに登録");
+    println!("  clipboard-translator --unregister-context-menu  右クリックメニューから削除");
+    println!("  clipboard-translator --profile [名前]  使用するプロファイルを切り替える（省略で基本設定に戻す）");
+    println!("  clipboard-translator --open-config  設定フォルダをエクスプローラーで開く");
+    println!("  clipboard-translator --history  翻訳履歴を表示");
+    println!("  clipboard-translator --clear-cache  翻訳結果のキャッシュを削除");
+    println!("  clipboard-translator --translate --json  標準入力を翻訳し、結果をJSONで出力");
+    println!("                 --model <ID>     --translate 時に使うモデルを一時的に指定");
+    println!("  clipboard-translator --print    標準入力を翻訳し、訳文だけを標準出力へ出力");
+    println!("  clipboard-translator --stdin-translate  --print と同じ");
+    println!("                 --model <ID>     --print・--stdin-translate 時に使うモデルを一時的に指定");
     println!("  clipboard-translator --help     このヘルプを表示");
     println!();
     println!("設定ファイルの場所:");
@@ -69,52 +121,592 @@ fn print_help() {
     }
     println!();
     println!("スタートアップ登録状態: {}", if startup::is_installed() { "登録済み" } else { "未登録" });
+    let provider = config::load_saved().map(|c| c.provider).unwrap_or_default();
+    println!(
+        "APIキー（{}）: {}",
+        provider.label(),
+        if credential::has_api_key(provider) { "保存済み" } else { "未保存" }
+    );
+}
+
+/// 使用するプロファイルを切り替える（見つからない場合は一覧を表示）
+fn run_profile_switch(name: Option<&str>) {
+    match config::set_active(name) {
+        Ok(_) => match name {
+            Some(name) => println!("プロファイル「{}」に切り替えました", name),
+            None => println!("基本設定に戻しました"),
+        },
+        Err(e) => {
+            eprintln!("プロファイルの切り替えに失敗: {}", e);
+            if let Ok(names) = config::list_profiles() {
+                if names.is_empty() {
+                    eprintln!("プロファイルは設定画面で作成できます");
+                } else {
+                    eprintln!("使用できるプロファイル: {}", names.join(", "));
+                }
+            }
+        }
+    }
+}
+
+/// 保存データをすべて削除し、削除したものを表示する（skip_confirm がfalseなら確認ダイアログを出す）
+fn run_reset(keep_startup: bool, skip_confirm: bool) {
+    let confirmed = skip_confirm || unsafe {
+        MessageBoxW(
+            HWND(0),
+            w!("設定・APIキー・履歴・キャッシュをすべて削除します。\nよろしいですか？"),
+            w!("データの削除"),
+            MB_YESNO | MB_ICONQUESTION,
+        ) == IDYES
+    };
+
+    if !confirmed {
+        println!("削除を中止しました");
+        return;
+    }
+
+    // 資格情報が未登録の場合もエラーにしない
+    for target in credential::delete_all_api_keys() {
+        println!("資格情報を削除: {}", target);
+    }
+
+    match config::remove_all_data() {
+        Ok(dir) => println!("設定・履歴・キャッシュを削除: {}", dir.display()),
+        Err(e) => eprintln!("設定ディレクトリの削除に失敗: {}", e),
+    }
+
+    if !keep_startup {
+        if startup::is_installed() {
+            match startup::uninstall_startup() {
+                Ok(_) => println!("スタートアップ登録を削除"),
+                Err(e) => eprintln!("スタートアップ削除に失敗: {}", e),
+            }
+        }
+        if context_menu::unregister().is_ok() {
+            println!("右クリックメニューの登録を解除");
+        }
+    }
+
+    println!("保存データを削除しました");
+}
+
+/// 翻訳タスクから結果ウィンドウへ送るメッセージ
+pub enum TranslationEvent {
+    /// 翻訳の開始（立てると通信を打ち切って翻訳をキャンセルする）
+    Started { cancel: Arc<AtomicBool> },
+    /// サーバー混雑（503/429）のため、次の再試行まで待機中
+    ServerBusy {
+        status: u16,
+        retry_at: Instant,
+        /// 立てるとリトライをやめて翻訳を中止する（「今すぐ中止」用）
+        stop: Arc<AtomicBool>,
+    },
+    /// 生成途中の文章の続き（ストリーミング表示用）
+    Partial(String),
+    /// 翻訳の完了（成功・失敗）
+    Finished(Result<gemini::Translation, String>),
+    /// 安全性フィルター・著作権保護でブロックされて翻訳が終了した
+    Blocked(gemini::ContentBlocked),
+}
+
+impl TranslationEvent {
+    /// 翻訳の終了を表すイベントなら結果を返す（ブロックは理由のメッセージを持つエラー）
+    pub fn into_outcome(self) -> Option<Result<gemini::Translation, String>> {
+        match self {
+            TranslationEvent::Finished(result) => Some(result),
+            TranslationEvent::Blocked(blocked) => Some(Err(blocked.to_string())),
+            TranslationEvent::Started { .. }
+            | TranslationEvent::ServerBusy { .. }
+            | TranslationEvent::Partial(_) => None,
+        }
+    }
+}
+
+/// 生成途中の差分を上限付きのチャネルへ送る
+/// チャネルが満杯の間は手元にためておき、次に送れたときにまとめて1件として送る
+struct PartialSender {
+    tx: mpsc::SyncSender<TranslationEvent>,
+    pending: String,
+}
+
+impl PartialSender {
+    fn new(tx: mpsc::SyncSender<TranslationEvent>) -> Self {
+        Self {
+            tx,
+            pending: String::new(),
+        }
+    }
+
+    fn send(&mut self, chunk: &str) {
+        self.pending.push_str(chunk);
+        let event = TranslationEvent::Partial(std::mem::take(&mut self.pending));
+        if let Err(mpsc::TrySendError::Full(TranslationEvent::Partial(text))) =
+            self.tx.try_send(event)
+        {
+            self.pending = text;
+        }
+    }
+
+    /// 送れずに残っている差分を、受信側が読むまで待って送る
+    fn flush(&mut self) {
+        if !self.pending.is_empty() {
+            let _ = self
+                .tx
+                .send(TranslationEvent::Partial(std::mem::take(&mut self.pending)));
+        }
+    }
 }
 
 /// バックグラウンドで翻訳タスクを起動し、結果を受信するReceiverを返す
 fn spawn_translation_task(
     text: String,
-    api_key: String,
-    model: String,
-    output_mode: config::OutputMode,
-) -> mpsc::Receiver<Result<String, String>> {
-    let (tx, rx) = mpsc::channel::<Result<String, String>>();
+    mut config: config::Config,
+) -> mpsc::Receiver<TranslationEvent> {
+    // 最初の Started を受信側へ渡す前に送るため、容量は最低1件にする
+    let (tx, rx) = mpsc::sync_channel::<TranslationEvent>(config.stream_channel_capacity.max(1));
+
+    // 結果ウィンドウの「キャンセル」でこの翻訳だけを中止できるよう、フラグを最初に渡す
+    let cancel_requested = Arc::new(AtomicBool::new(false));
+    let _ = tx.send(TranslationEvent::Started {
+        cancel: cancel_requested.clone(),
+    });
+
+    // DeepLは訳文しか返さないため、常に簡潔モードとして扱う
+    if !config.provider.supports_explanation() {
+        config.output_mode = config::OutputMode::Concise;
+    }
 
     thread::spawn(move || {
         let rt = match Runtime::new() {
             Ok(rt) => rt,
             Err(e) => {
-                let _ = tx.send(Err(format!("Tokioランタイム作成失敗: {}", e)));
+                let _ = tx.send(TranslationEvent::Finished(Err(format!(
+                    "Tokioランタイム作成失敗: {}",
+                    e
+                ))));
                 return;
             }
         };
-        let client = gemini::GeminiClient::new(api_key, model, output_mode);
+        // スマートスワップ：入力が翻訳先言語なら直前の原文の言語へ戻す
+        let swap_target = if config.smart_swap {
+            language::swap_target_for(&text, &config.target_language)
+        } else {
+            None
+        };
+
+        // Excel等からコピーした表はセルごとに翻訳して表の形を保つ（Geminiのみ）
+        let table =
+            table::parse_tsv(&text).filter(|_| config.provider == config::Provider::Gemini);
+
+        let model = config.active_model().to_string();
+
+        // 同じ原文を同じ設定で翻訳済みならAPIを呼ばずに返す（表はクリップボードへの書き込みがあるため対象外）
+        let cache_size = config.cache_size;
+        let cache_key = (cache_size > 0 && table.is_none()).then(|| {
+            cache::CacheKey::new(
+                &model,
+                config.output_mode,
+                &text,
+                &[
+                    &config.result_settings_key(),
+                    swap_target.as_deref().unwrap_or(""),
+                ],
+            )
+        });
+        if let Some(content) = cache_key.as_ref().and_then(cache::get) {
+            let _ = tx.send(TranslationEvent::Finished(Ok(gemini::Translation::plain(content))));
+            return;
+        }
+        // 明らかにオフラインの場合は、リトライを待たずにすぐ知らせる
+        if network::is_offline() {
+            let _ = tx.send(TranslationEvent::Finished(Err(
+                network::OFFLINE_MESSAGE.to_string()
+            )));
+            return;
+        }
+        let stream = config.stream_responses;
+        let clipboard_backend = config.clipboard_backend;
+        let target_language = config.target_language.clone();
+        let disclaimer_rules = config.disclaimer_rules.clone();
+        // Markdown文書をそのまま翻訳した結果は構造を残す
+        let plain_text = config.plain_concise
+            && config.output_mode == config::OutputMode::Concise
+            && !(config.preserve_markdown && content_kind::looks_like_markdown(&text));
+        let backend = provider::Backend::from_config(&config, swap_target);
+
+        // サーバー混雑中は待機状態と次の再試行時刻をウィンドウへ知らせる
+        let stop_retry = Arc::new(AtomicBool::new(false));
+        let backend = {
+            let tx = tx.clone();
+            let stop = stop_retry.clone();
+            backend.with_retry_notifier(move |status, retry_at| {
+                let _ = tx.send(TranslationEvent::ServerBusy {
+                    status,
+                    retry_at,
+                    stop: stop.clone(),
+                });
+            })
+        };
+
+        // 「すべて中止」が実行された場合は通信を打ち切る
+        let mut partial = PartialSender::new(tx.clone());
+        let cancel = cancel::CancelToken::new();
+        let start = Instant::now();
+        let result = rt.block_on(async {
+            tokio::select! {
+                result = async {
+                    match (&backend, &table) {
+                        (provider::Backend::Gemini(client), Some(rows)) => {
+                            client.translate_table(rows).await
+                        }
+                        (provider::Backend::Gemini(client), None) if stream => {
+                            client
+                                .translate_and_explain_stream(&text, |chunk| partial.send(chunk))
+                                .await
+                        }
+                        // 長い原文は分割した部分ごとに結果を表示する
+                        (provider::Backend::Gemini(client), None)
+                            if client.needs_chunking(&text) =>
+                        {
+                            client
+                                .translate_in_chunks(&text, false, |chunk| partial.send(chunk))
+                                .await
+                        }
+                        _ => backend.translate(&text).await,
+                    }
+                } => result,
+                _ = cancel.cancelled() => Err(anyhow::anyhow!("翻訳を中止しました")),
+                // 実行中の通信は future ごと破棄されて打ち切られる
+                _ = cancel::flagged(cancel_requested) => Err(anyhow::anyhow!("翻訳をキャンセルしました")),
+                _ = cancel::flagged(stop_retry) => Err(anyhow::anyhow!("リトライを中止しました")),
+            }
+        });
+
+        // 表の翻訳結果はそのまま表計算ソフトへ貼り付けられるようクリップボードへ書き込む
+        let result = result.map(|mut translation| {
+            if table.is_some() {
+                match clipboard::replace_text(&translation.content, clipboard_backend) {
+                    Ok(()) => translation.table_copied = true,
+                    Err(e) => eprintln!("Failed to copy translated table: {}", e),
+                }
+            }
+            translation
+        });
+
+        // 先頭・末尾の定型文（免責文など）を取り除く（表はセルの内容を変えないよう対象外）
+        let result = result.map(|mut translation| {
+            if table.is_some() {
+                return translation;
+            }
+            translation.content =
+                postprocess::strip_disclaimers(&translation.content, &disclaimer_rules);
+            if plain_text {
+                translation.content = postprocess::strip_concise_formatting(&translation.content);
+            }
+            translation
+        });
 
-        let result = rt.block_on(async { client.translate_and_explain(&text).await });
+        match result {
+            Ok(ref translation) => {
+                // トークン上限で途中で切れた結果は、設定を変えて翻訳し直せるようキャッシュしない
+                if let Some(key) = cache_key.filter(|_| !translation.is_truncated()) {
+                    cache::put(key, &translation.content, cache_size);
+                }
+                language::remember_source_language(&text, &translation.content, &target_language);
+                metrics::record_latency(
+                    &model,
+                    translation.elapsed,
+                    translation.attempts,
+                    text.chars().count(),
+                    true,
+                );
+            }
+            Err(_) => {
+                metrics::record_latency(&model, start.elapsed(), 0, text.chars().count(), false);
+            }
+        }
 
-        let _ = tx.send(result.map_err(|e| e.to_string()));
+        let event = match result {
+            Err(e) => match e.downcast_ref::<gemini::ContentBlocked>() {
+                Some(blocked) => TranslationEvent::Blocked(blocked.clone()),
+                None => TranslationEvent::Finished(Err(e.to_string())),
+            },
+            Ok(translation) => TranslationEvent::Finished(Ok(translation)),
+        };
+        partial.flush();
+        let _ = tx.send(event);
     });
 
     rx
 }
 
+/// 翻訳を実行し、結果を待って返す（UIなし）
+fn translate_blocking(text: String, config: config::Config) -> Result<String, String> {
+    // 混雑中の待機通知は読み飛ばし、最終結果だけを返す
+    spawn_translation_task(text, config)
+        .iter()
+        .find_map(TranslationEvent::into_outcome)
+        .unwrap_or_else(|| Err("翻訳タスクが異常終了しました".to_string()))
+        .map(|translation| translation.content)
+}
+
+/// サイレントモードの通知に表示する結果の文字数
+const SILENT_PREVIEW_CHARS: usize = 100;
+
+/// 通知の「元に戻す」ボタンの引数
+const RESTORE_CLIPBOARD_ACTION: &str = "restore-clipboard";
+
+/// ウィンドウを表示せずに翻訳し、結果の冒頭を通知で知らせる
+fn translate_silently(text: String, config: &config::Config) {
+    match translate_blocking(text.clone(), config.clone()) {
+        Ok(content) => {
+            history::append(&text, &content, config.active_model(), config.history_limit);
+
+            if config.auto_copy {
+                if let Err(e) = clipboard::replace_text(&content, config.clipboard_backend) {
+                    eprintln!("Failed to copy translation: {}", e);
+                }
+            }
+
+            let flattened = content.split_whitespace().collect::<Vec<_>>().join(" ");
+            let mut preview: String = flattened.chars().take(SILENT_PREVIEW_CHARS).collect();
+            if flattened.chars().count() > SILENT_PREVIEW_CHARS {
+                preview.push('…');
+            }
+            let title = if config.auto_copy {
+                "翻訳しました（コピー済み）"
+            } else {
+                "翻訳しました"
+            };
+            // 上書きしたクリップボードの内容は通知のボタンで元に戻せる
+            let buttons: &[(&str, &str)] = if config.auto_copy && clipboard::can_restore() {
+                &[("元に戻す", RESTORE_CLIPBOARD_ACTION)]
+            } else {
+                &[]
+            };
+            // 通知をクリックすると、ウィンドウを出さなかった結果の全文を表示する
+            notification::show_success_with_actions(title, &preview, buttons, move |action| {
+                if action == RESTORE_CLIPBOARD_ACTION {
+                    if let Err(e) = clipboard::restore_replaced() {
+                        notification::show_error("コピーエラー", &e.to_string());
+                    }
+                    return;
+                }
+                let content = content.clone();
+                thread::spawn(move || {
+                    if let Err(e) = ui::result::show_result(&content) {
+                        eprintln!("Failed to show translation UI: {}", e);
+                    }
+                });
+            });
+        }
+        Err(e) => notification::show_error("API エラー", &e),
+    }
+}
+
+/// 長い1行のデータ（minifyされたコードなど）を翻訳するか確認する
+fn confirm_blob_translation() -> bool {
+    unsafe {
+        MessageBoxW(
+            HWND(0),
+            w!("クリップボードの内容は文章ではなく、改行のない長いデータのようです。\n翻訳しますか？"),
+            w!("翻訳の確認"),
+            MB_YESNO | MB_ICONQUESTION,
+        ) == IDYES
+    }
+}
+
+/// 上限を超える長さのテキストを送る前に確認する
+/// 「はい」で全文、「いいえ」で先頭 max_chars 文字だけを翻訳し、「キャンセル」で中止（None）
+fn confirm_large_text(text: String, max_chars: usize) -> Option<String> {
+    let count = text.chars().count();
+    if max_chars == 0 || count <= max_chars {
+        return Some(text);
+    }
+
+    let message = HSTRING::from(format!(
+        "クリップボードの内容が {} 文字あります（上限 {} 文字）。\n\n\
+        はい: すべて翻訳する\n\
+        いいえ: 先頭の {} 文字だけ翻訳する\n\
+        キャンセル: 翻訳しない",
+        count, max_chars, max_chars
+    ));
+    let answer = unsafe {
+        MessageBoxW(
+            HWND(0),
+            &message,
+            w!("大きなテキストの確認"),
+            MB_YESNOCANCEL | MB_ICONWARNING,
+        )
+    };
+
+    match answer {
+        IDYES => Some(text),
+        IDNO => Some(text.chars().take(max_chars).collect()),
+        _ => None,
+    }
+}
+
+/// 最初に表示する翻訳の設定
+/// ツールチップ表示（1段目）では簡潔モードで素早く取得する
+fn first_stage_config(config: &config::Config) -> config::Config {
+    if config.preview_first {
+        config::Config {
+            output_mode: config::OutputMode::Concise,
+            ..config.clone()
+        }
+    } else {
+        config.clone()
+    }
+}
+
+/// 今回の翻訳に使う設定
+/// 使用中のプロファイルは翻訳のたびに読み直し、コピー元アプリのルールがあれば上書きする
+fn config_for_request(config: &config::Config) -> config::Config {
+    let foreground = send_to_app::foreground_process_name();
+    config::apply_saved_profile(config).with_app_rule(foreground.as_deref())
+}
+
+/// 翻訳不要と判定した原文を、APIを呼ばずにそのまま表示する
+fn show_untranslated(
+    text: String,
+    language: &str,
+    config: &config::Config,
+    is_translating: &Arc<AtomicBool>,
+) {
+    let note = format!("翻訳不要: 原文はすでに{}です", language);
+    if config.silent {
+        notification::show_info(&note);
+        is_translating.store(false, Ordering::SeqCst);
+        return;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let _ = tx.send(TranslationEvent::Finished(Ok(gemini::Translation::plain(
+        format!("（{}）\n\n{}", note, text),
+    ))));
+    // 原文をクリップボード・送信先へ書き戻さないよう、自動コピー・送信はしない
+    let config = config::Config {
+        auto_copy: false,
+        send_to_app: None,
+        ..config.clone()
+    };
+    if let Err(e) =
+        ui::result::show_result_with_receiver(rx, &config, Some(is_translating.clone()), None)
+    {
+        eprintln!("Failed to show translation UI: {}", e);
+        is_translating.store(false, Ordering::SeqCst);
+    }
+}
+
+/// 順番待ちにできる翻訳の件数（超えた分は従来どおり「翻訳実行中です」と通知して無視する）
+const MAX_PENDING_REQUESTS: usize = 5;
+
 /// 別スレッドで翻訳UIを表示（非ブロッキング）
 fn show_translation_ui_async(
     clipboard_text: String,
-    config: config::Config,
+    mut config: config::Config,
     is_translating: Arc<AtomicBool>,
 ) {
     thread::spawn(move || {
-        let rx = spawn_translation_task(
-            clipboard_text,
-            config.api_key.clone(),
-            config.model.clone(),
-            config.output_mode,
-        );
+        // URL・ファイルパス・コードは設定に応じて翻訳を省くか、コードの解説に切り替える
+        if config.special_content != config::SpecialContentAction::Translate {
+            let kind = content_kind::classify(&clipboard_text);
+            let explain = kind == content_kind::ClipboardKind::Code
+                && config.special_content == config::SpecialContentAction::ExplainCode
+                && config.provider.supports_explanation();
+
+            if explain {
+                config.output_mode = config::OutputMode::Custom;
+                config.custom_prompt =
+                    Some(config::code_explanation_prompt(&config.target_language));
+                config.preview_first = false;
+            } else if kind != content_kind::ClipboardKind::Text {
+                notification::show_info(&format!("{}のため翻訳をスキップしました", kind.label()));
+                is_translating.store(false, Ordering::SeqCst);
+                return;
+            }
+        }
+
+        // 1語だけコピーした場合は、翻訳の代わりに辞書のように意味・品詞・例文を表示する
+        if config.auto_dictionary
+            && config.provider.supports_explanation()
+            && content_kind::is_single_word(&clipboard_text)
+        {
+            config.output_mode = config::OutputMode::Dictionary;
+            config.preview_first = false;
+        }
+
+        // 原文がすでに翻訳先の言語なら、APIを呼ばずにそのまま表示する
+        // （双方向翻訳・スマートスワップで別の言語へ翻訳する場合は対象外）
+        if config.skip_same_language
+            && config.language_pair.is_none()
+            && !(config.smart_swap
+                && language::swap_target_for(&clipboard_text, &config.target_language).is_some())
+        {
+            if let Some(language) =
+                language::already_in_target(&clipboard_text, &config.target_language)
+            {
+                show_untranslated(clipboard_text, language, &config, &is_translating);
+                return;
+            }
+        }
+
+        // 文章ではなさそうな長いデータは、翻訳する前に確認する
+        if content_kind::looks_like_blob(&clipboard_text) && !confirm_blob_translation() {
+            is_translating.store(false, Ordering::SeqCst);
+            return;
+        }
+
+        // 意図せず巨大なテキストを送らないよう、上限を超える場合は確認する
+        let Some(clipboard_text) = confirm_large_text(clipboard_text, config.max_chars) else {
+            is_translating.store(false, Ordering::SeqCst);
+            return;
+        };
+
+        // 原文を編集してから翻訳する場合は、「翻訳」ボタンが押されるまでAPIを呼ばない
+        if config.edit_before_translate {
+            if let Err(e) = ui::result::show_result_for_editing(
+                clipboard_text,
+                &config,
+                Some(is_translating.clone()),
+            ) {
+                eprintln!("Failed to show translation UI: {}", e);
+                notification::show_error("エラー", "翻訳ウィンドウの表示に失敗しました");
+                is_translating.store(false, Ordering::SeqCst);
+            }
+            return;
+        }
+
+        // サイレントモードではウィンドウを表示しない
+        if config.silent {
+            translate_silently(clipboard_text, &config);
+            is_translating.store(false, Ordering::SeqCst);
+            return;
+        }
+
+        // 開いたままのウィンドウに表示する場合は、ツールチップを挟まず最初から詳細を表示する
+        let result = if config.reuse_window {
+            let rx = spawn_translation_task(clipboard_text.clone(), config.clone());
+            ui::result::show_result_in_pinned_window(
+                rx,
+                &config,
+                Some(is_translating.clone()),
+                clipboard_text,
+            )
+        } else {
+            let rx = spawn_translation_task(clipboard_text.clone(), first_stage_config(&config));
+            ui::result::show_result_with_receiver(
+                rx,
+                &config,
+                Some(is_translating.clone()),
+                Some(clipboard_text),
+            )
+        };
 
         // UIを表示（このスレッド内でブロッキング）
         // 翻訳結果が表示された時点で、UI側でフラグをクリアする
-        if let Err(e) = ui::result::show_result_with_receiver(rx, Some(is_translating.clone())) {
+        if let Err(e) = result {
             eprintln!("Failed to show translation UI: {}", e);
             notification::show_error("エラー", "翻訳ウィンドウの表示に失敗しました");
             // エラー時もフラグをクリア
@@ -123,31 +715,274 @@ fn show_translation_ui_async(
     });
 }
 
-fn run_translate_mode() -> Result<()> {
+/// --model <id> で指定されたモデル（指定がない・空の場合は設定のモデルを使う）
+fn model_override(args: &[String]) -> Option<String> {
+    let index = args.iter().position(|a| a == "--model")?;
+    let model = args
+        .get(index + 1)
+        .map(|m| m.trim())
+        .filter(|m| !m.is_empty() && !m.starts_with("--"));
+    if model.is_none() {
+        eprintln!("--model にはモデルIDを指定してください（設定のモデルを使います）");
+    }
+    model.map(str::to_string)
+}
+
+/// 設定を読み込み、使用中のプロファイルとコマンドラインのモデル指定を適用する
+fn load_config_for_cli(model: Option<&str>) -> Result<config::Config> {
+    let config = config::load_or_create()?.with_active_profile();
+    Ok(match model {
+        Some(model) => config.with_model(model),
+        None => config,
+    })
+}
+
+fn run_translate_mode(model: Option<&str>) -> Result<()> {
     // 標準入力からクリップボードテキストを読み取り
     let mut clipboard_text = String::new();
     io::stdin().read_to_string(&mut clipboard_text)?;
 
-    // 設定読み込み
-    let config = config::load_or_create()?;
+    translate_with_window(clipboard_text, model)
+}
 
-    let rx = spawn_translation_task(
-        clipboard_text,
-        config.api_key.clone(),
-        config.model.clone(),
-        config.output_mode,
-    );
+/// 標準入力のテキストを翻訳し、結果をJSONで標準出力へ書き出す（スクリプトからの利用向け）
+/// 失敗した場合は error に内容を入れ、終了コード1で終了する
+fn run_json_mode(model: Option<&str>) -> ! {
+    let mut source = String::new();
+    let (model, result) = match io::stdin().read_to_string(&mut source) {
+        Err(e) => (String::new(), Err(format!("標準入力を読み込めません: {}", e))),
+        Ok(_) => match load_config_for_cli(model) {
+            Err(e) => (String::new(), Err(e.to_string())),
+            Ok(config) => {
+                let model = config.active_model().to_string();
+                (model, translate_blocking(source.clone(), config))
+            }
+        },
+    };
+
+    let (output, code) = match result {
+        Ok(content) => (
+            serde_json::json!({
+                "source": source,
+                "result": content,
+                "model": model,
+                "error": null,
+            }),
+            0,
+        ),
+        Err(e) => (
+            serde_json::json!({
+                "source": source,
+                "result": null,
+                "model": model,
+                "error": e,
+            }),
+            1,
+        ),
+    };
+
+    println!("{}", output);
+    std::process::exit(code);
+}
+
+/// 標準入力を翻訳し、訳文だけを標準出力へ書き出して終了する（パイプ用）
+/// 成功時は終了コード0、失敗時はエラーを標準エラー出力へ書き出して1
+fn run_print_mode(model: Option<&str>) -> ! {
+    let mut source = String::new();
+    let result = match io::stdin().read_to_string(&mut source) {
+        Err(e) => Err(format!("標準入力を読み込めません: {}", e)),
+        Ok(_) if source.trim().is_empty() => Err("標準入力が空です".to_string()),
+        Ok(_) => load_config_for_cli(model)
+            .map_err(|e| e.to_string())
+            .and_then(|config| translate_blocking(source, config)),
+    };
+
+    match result {
+        Ok(content) => {
+            println!("{}", content);
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// ファイルの内容を翻訳して表示（右クリックメニューから起動された場合）
+fn run_file_mode(path: &str) -> Result<()> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| anyhow::anyhow!("ファイルを読み込めません: {}: {}", path, e))?;
+    let text = String::from_utf8_lossy(&bytes).into_owned();
+
+    if text.trim().is_empty() {
+        notification::show_info("ファイルが空のため翻訳しませんでした");
+        return Ok(());
+    }
+
+    translate_with_window(text, None)
+}
+
+/// --batch で同時に翻訳する件数の既定値（レート制限に当たりにくい数）
+const BATCH_CONCURRENCY: usize = 3;
+
+/// 「--名前 値」形式のオプションの値
+fn option_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let index = args.iter().position(|a| a == name)?;
+    args.get(index + 1).map(String::as_str)
+}
+
+/// ファイルを区切り文字（既定は改行）で分けて項目ごとに翻訳し、
+/// <パス>.translated（--stdout の場合は標準出力）へ同じ区切り文字でつないで書き出す
+/// 失敗した項目は原文のまま残す
+fn run_batch_mode(args: &[String]) -> Result<()> {
+    let Some(path) = args.get(2).filter(|p| !p.starts_with("--")) else {
+        anyhow::bail!("--batch には翻訳するファイルのパスを指定してください");
+    };
+    let delimiter = option_value(args, "--delimiter")
+        .map(|d| d.replace("\\n", "\n").replace("\\t", "\t"))
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| "\n".to_string());
+    let concurrency = option_value(args, "--jobs")
+        .and_then(|n| n.parse::<usize>().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or(BATCH_CONCURRENCY);
+    // 1項目を1つの訳文にするため、解説なしのプレーンテキストで翻訳する
+    // 同時に翻訳する項目どうしで直前の原文の言語を取り合わないよう、スマートスワップは使わない
+    let config = config::Config {
+        output_mode: config::OutputMode::Concise,
+        plain_concise: true,
+        smart_swap: false,
+        ..load_config_for_cli(model_override(args).as_deref())?
+    };
+
+    let bytes = std::fs::read(path)
+        .map_err(|e| anyhow::anyhow!("ファイルを読み込めません: {}: {}", path, e))?;
+    let text = String::from_utf8_lossy(&bytes).into_owned();
+    // CRLFのファイルでも項目の末尾に \r が残らないようにする
+    let entries: Vec<String> = text
+        .split(delimiter.as_str())
+        .map(|entry| entry.trim_end_matches('\r').to_string())
+        .collect();
+    let total = entries.iter().filter(|e| !e.trim().is_empty()).count();
+    eprintln!("{}件を翻訳します（同時に{}件まで）", total, concurrency);
+
+    // 混雑時のリトライ・待機は項目ごとの翻訳タスクの中で行われる
+    let rt = Runtime::new()?;
+    let results: Vec<Result<String, String>> = rt.block_on(async {
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency));
+        let done = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = entries
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(index, entry)| {
+                let semaphore = semaphore.clone();
+                let done = done.clone();
+                let config = config.clone();
+                tokio::spawn(async move {
+                    if entry.trim().is_empty() {
+                        return Ok(entry);
+                    }
+                    let _permit = semaphore.acquire_owned().await;
+                    let result =
+                        tokio::task::spawn_blocking(move || translate_blocking(entry, config))
+                            .await
+                            .unwrap_or_else(|e| Err(e.to_string()));
+
+                    let count = done.fetch_add(1, Ordering::SeqCst) + 1;
+                    match &result {
+                        Ok(_) => eprintln!("[{}/{}] {}件目を翻訳しました", count, total, index + 1),
+                        Err(e) => {
+                            eprintln!("[{}/{}] {}件目の翻訳に失敗: {}", count, total, index + 1, e)
+                        }
+                    }
+                    result
+                })
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            results.push(task.await.unwrap_or_else(|e| Err(e.to_string())));
+        }
+        results
+    });
+
+    let failed = results.iter().filter(|r| r.is_err()).count();
+    let output = results
+        .into_iter()
+        .zip(entries)
+        .map(|(result, entry)| match result {
+            Ok(content) => flatten_batch_entry(&content, &delimiter),
+            Err(_) => entry,
+        })
+        .collect::<Vec<_>>()
+        .join(&delimiter);
+
+    if args.iter().any(|a| a == "--stdout") {
+        print!("{}", output);
+    } else {
+        let output_path = format!("{}.translated", path);
+        std::fs::write(&output_path, output)
+            .map_err(|e| anyhow::anyhow!("書き出せません: {}: {}", output_path, e))?;
+        eprintln!("{} に書き出しました", output_path);
+    }
+    if failed > 0 {
+        eprintln!("{}件の翻訳に失敗しました（原文のまま残しています）", failed);
+    }
+    Ok(())
+}
+
+/// 訳文に区切り文字が含まれると原文と項目の対応がずれるため、改行・区切り文字を空白にまとめる
+fn flatten_batch_entry(content: &str, delimiter: &str) -> String {
+    let flattened = if delimiter.contains('\n') {
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    } else {
+        content.to_string()
+    };
+    flattened.replace(delimiter, " ")
+}
+
+/// テキストを翻訳し、ローディング表示付きの結果ウィンドウで表示
+fn translate_with_window(clipboard_text: String, model: Option<&str>) -> Result<()> {
+    // 設定読み込み（使用中のプロファイル・モデルの指定を適用）
+    let config = load_config_for_cli(model)?;
+
+    let rx = spawn_translation_task(clipboard_text.clone(), first_stage_config(&config));
 
     // ローディング表示付きのウィンドウを表示
-    ui::result::show_result_with_receiver(rx, None)?;
+    ui::result::show_result_with_receiver(rx, &config, None, Some(clipboard_text))?;
 
     Ok(())
 }
 
+fn run_native_host() -> Result<()> {
+    let config = config::load_or_create()?.with_active_profile();
+    native_host::run(|text| translate_blocking(text, config.clone()))
+}
+
 fn main() -> Result<()> {
     // コマンドライン引数をチェック
     let args: Vec<String> = env::args().collect();
 
+    // カーソル位置やモニターの大きさを物理ピクセルで取得できるよう、
+    // 最初のウィンドウを作る前にモニターごとのDPI対応を宣言する
+    unsafe {
+        let _ = SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
+    }
+
+    // ブラウザから起動された場合はネイティブメッセージングホストとして動作
+    if args.len() > 1 && native_host::is_browser_launch(&args[1]) {
+        return run_native_host();
+    }
+
     if args.len() > 1 {
         match args[1].as_str() {
             "--setup" | "-s" | "--config" => {
@@ -159,7 +994,13 @@ fn main() -> Result<()> {
                 return Ok(());
             }
             "--install" => {
-                match startup::install_startup() {
+                // --task でタスクスケジューラに管理者権限で登録する
+                let method = if args.iter().any(|a| a == "--task") {
+                    config::StartupMethod::TaskScheduler
+                } else {
+                    config::StartupMethod::Registry
+                };
+                match startup::install(method) {
                     Ok(_) => println!("スタートアップに登録しました"),
                     Err(e) => eprintln!("スタートアップ登録に失敗: {}", e),
                 }
@@ -172,9 +1013,79 @@ fn main() -> Result<()> {
                 }
                 return Ok(());
             }
+            "--reset" | "--purge" => {
+                run_reset(
+                    args.iter().any(|a| a == "--keep-startup"),
+                    args.iter().any(|a| a == "--yes" || a == "-y"),
+                );
+                return Ok(());
+            }
+            "--register-context-menu" => {
+                match context_menu::register() {
+                    Ok(_) => println!("右クリックメニューに登録しました"),
+                    Err(e) => eprintln!("右クリックメニューの登録に失敗: {}", e),
+                }
+                return Ok(());
+            }
+            "--unregister-context-menu" => {
+                match context_menu::unregister() {
+                    Ok(_) => println!("右クリックメニューから削除しました"),
+                    Err(e) => eprintln!("右クリックメニューの削除に失敗: {}", e),
+                }
+                return Ok(());
+            }
+            "--open-config" => {
+                match config::open_config_dir() {
+                    Ok(dir) => println!("設定フォルダを開きました: {}", dir.display()),
+                    Err(e) => eprintln!("設定フォルダを開けませんでした: {}", e),
+                }
+                return Ok(());
+            }
+            "--history" => {
+                ui::history::show_history_window()?;
+                return Ok(());
+            }
+            "--clear-cache" => {
+                match cache::clear() {
+                    Ok(_) => println!("翻訳結果のキャッシュを削除しました"),
+                    Err(e) => eprintln!("キャッシュの削除に失敗: {}", e),
+                }
+                return Ok(());
+            }
+            "--profile" => {
+                run_profile_switch(args.get(2).map(String::as_str));
+                return Ok(());
+            }
+            "--translate-text" => {
+                // 引数のテキストを翻訳（残りの引数はスペースで連結）
+                let text = args[2..].join(" ");
+                if text.trim().is_empty() {
+                    eprintln!("--translate-text には翻訳するテキストを指定してください");
+                    return Ok(());
+                }
+                return translate_with_window(text, None);
+            }
+            "--file" => {
+                let Some(path) = args.get(2) else {
+                    eprintln!("--file には翻訳するファイルのパスを指定してください");
+                    return Ok(());
+                };
+                return run_file_mode(path);
+            }
+            "--print" | "--stdin-translate" => {
+                run_print_mode(model_override(&args).as_deref());
+            }
+            "--batch" => {
+                return run_batch_mode(&args);
+            }
             "--translate" => {
+                // --json の場合はウィンドウを表示せず、結果をJSONで標準出力へ書き出す
+                let model = model_override(&args);
+                if args.iter().any(|a| a == "--json") {
+                    run_json_mode(model.as_deref());
+                }
                 // 翻訳モード：クリップボードテキストを受け取り、API呼び出し、結果表示
-                return run_translate_mode();
+                return run_translate_mode(model.as_deref());
             }
             "--show-result" => {
                 // 旧API（後方互換）
@@ -197,7 +1108,7 @@ fn main() -> Result<()> {
     }
 
     // 設定読み込み
-    let config = config::load_or_create()?;
+    let mut config = config::load_or_create()?;
 
     // APIキー未設定の場合は設定画面を表示
     if config.api_key.is_empty() {
@@ -217,36 +1128,161 @@ fn main() -> Result<()> {
 
     // Low-Level Hook を別スレッドで起動
     let hook_hotkey = config.hotkey;
+    let reset_hotkey = config.reset_hotkey;
+    let pause_hotkey = config.pause_hotkey;
+    let double_press_window_ms = config.double_press_window_ms;
     thread::spawn(move || {
-        if let Err(e) = hotkey_hook::start_hook(hook_hotkey) {
+        if let Err(e) = hotkey_hook::start_hook(
+            hook_hotkey,
+            reset_hotkey,
+            pause_hotkey,
+            double_press_window_ms,
+        ) {
             eprintln!("Failed to start keyboard hook: {}", e);
             notification::show_error("エラー", "キーボードフックの開始に失敗しました");
         }
     });
 
+    // コピーされたら自動で翻訳するモードでは、クリップボードの変更を監視する
+    if config.auto_translate_on_copy {
+        clipboard_watch::spawn_listener();
+    }
+
+    // 「翻訳実行中です」を最後に通知した時刻
+    let mut busy_notified_at: Option<Instant> = None;
+
+    // 翻訳中に押されたホットキーの順番待ち（原文と、押した時点の設定）
+    let mut pending: VecDeque<(String, config::Config)> = VecDeque::new();
+
+    // 設定画面で保存されたことを検知するための更新時刻
+    let mut config_modified = config::modified_time();
+
     // メインループ：フックからのトリガーをチェック
     loop {
-        if hotkey_hook::check_triggered() {
-            // 既に翻訳中かチェック
+        // ダブルプレスの間隔・コピー時の自動翻訳・翻訳中の依頼の扱いは再起動せずに反映する
+        let modified = config::modified_time();
+        if modified != config_modified {
+            config_modified = modified;
+            if let Some(saved) = config::load_saved() {
+                hotkey_hook::set_double_press_window(saved.double_press_window_ms);
+                if saved.auto_translate_on_copy != config.auto_translate_on_copy {
+                    if saved.auto_translate_on_copy {
+                        clipboard_watch::spawn_listener();
+                    } else {
+                        clipboard_watch::stop_listener();
+                    }
+                }
+                config.auto_translate_on_copy = saved.auto_translate_on_copy;
+                config.queue_requests = saved.queue_requests;
+            }
+        }
+
+        // 一時停止用ホットキー：翻訳の一時停止・再開を切り替える
+        if hotkey_hook::check_pause_triggered() {
+            let paused = !PAUSED.fetch_xor(true, Ordering::SeqCst);
+            notification::show_info(if paused {
+                "翻訳を一時停止しました"
+            } else {
+                "翻訳を再開しました"
+            });
+        }
+
+        // リセット用ホットキー：実行中の翻訳・結果ウィンドウをすべて中止
+        if hotkey_hook::check_reset_triggered() {
+            cancel::cancel_all();
+            is_translating.store(false, Ordering::SeqCst);
+            pending.clear();
+            println!("Reset hotkey detected. Aborted all translations.");
+            notification::show_info("すべての翻訳を中止し、状態をリセットしました");
+        }
+
+        // 順番待ちの翻訳は、前の翻訳の結果が表示されてから1件ずつ始める
+        if !is_translating.load(Ordering::SeqCst) && !PAUSED.load(Ordering::SeqCst) {
+            if let Some((text, request_config)) = pending.pop_front() {
+                is_translating.store(true, Ordering::SeqCst);
+                show_translation_ui_async(text, request_config, Arc::clone(&is_translating));
+            }
+        }
+
+        let hotkey_pressed = hotkey_hook::check_triggered();
+        // 自動翻訳モード：コピーされたテキストを翻訳する（連続した変更はまとめて1回）
+        let copied = config.auto_translate_on_copy && clipboard_watch::check_changed();
+
+        if hotkey_pressed || copied {
+            // 一時停止中は翻訳しない
+            if PAUSED.load(Ordering::SeqCst) {
+                thread::sleep(Duration::from_millis(HOTKEY_POLL_INTERVAL_MS));
+                continue;
+            }
+
+            // 既に翻訳中かチェック（自動翻訳のコピーは通知せずに読み飛ばす）
             if is_translating.load(Ordering::SeqCst) {
+                if !hotkey_pressed {
+                    thread::sleep(Duration::from_millis(HOTKEY_POLL_INTERVAL_MS));
+                    continue;
+                }
+
+                // 順番待ちにする場合は、押した時点のクリップボードの内容を控えておく
+                if config.queue_requests && pending.len() < MAX_PENDING_REQUESTS {
+                    match clipboard::get_text(config.clipboard_backend) {
+                        Ok(text) if !text.trim().is_empty() => {
+                            pending.push_back((text, config_for_request(&config)));
+                            notification::show_info(&format!(
+                                "翻訳待ちに追加しました（{}件待ち）",
+                                pending.len()
+                            ));
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            eprintln!("Clipboard error: {}", e);
+                            notification::show_error(
+                                "エラー",
+                                "クリップボードの取得に失敗しました",
+                            );
+                        }
+                    }
+                    thread::sleep(Duration::from_millis(HOTKEY_POLL_INTERVAL_MS));
+                    continue;
+                }
+
                 println!("Translation already in progress, ignoring hotkey");
-                notification::show_info("翻訳実行中です");
+                // 連打で通知が溢れないよう、翻訳1回につき1度（または設定間隔ごと）に抑える
+                let interval = Duration::from_secs(config.busy_notice_interval_secs);
+                let should_notify = match busy_notified_at {
+                    None => true,
+                    Some(at) => !interval.is_zero() && at.elapsed() >= interval,
+                };
+                if should_notify {
+                    notification::show_info("翻訳実行中です");
+                    busy_notified_at = Some(Instant::now());
+                }
                 thread::sleep(Duration::from_millis(HOTKEY_POLL_INTERVAL_MS));
                 continue;
             }
 
             // クリップボード取得
-            match clipboard::get_text() {
-                Ok(text) if !text.trim().is_empty() => {
+            // 自動翻訳では、このアプリ自身が書き込んだ結果（自動コピーなど）を翻訳し直さない
+            match clipboard::get_text(config.clipboard_backend) {
+                Ok(text)
+                    if !text.trim().is_empty()
+                        && (hotkey_pressed || !clipboard::is_own_write(&text)) =>
+                {
                     println!("Hotkey detected. Processing clipboard content...");
 
                     // 翻訳中フラグをセット
                     is_translating.store(true, Ordering::SeqCst);
+                    busy_notified_at = None;
 
                     // 別スレッドで翻訳UIを表示（非ブロッキング）
-                    show_translation_ui_async(text, config.clone(), Arc::clone(&is_translating));
+                    show_translation_ui_async(
+                        text,
+                        config_for_request(&config),
+                        Arc::clone(&is_translating),
+                    );
                 }
                 Ok(_) => {} // 空のクリップボードは無視
+                // 画像などテキスト以外のコピーでは自動翻訳のたびにエラーを出さない
+                Err(_) if !hotkey_pressed => {}
                 Err(e) => {
                     eprintln!("Clipboard error: {}", e);
                     notification::show_error("エラー", "クリップボードの取得に失敗しました");
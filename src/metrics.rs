@@ -0,0 +1,53 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// 翻訳1回分のレイテンシ記録
+#[derive(Debug, Serialize)]
+struct LatencyRecord<'a> {
+    timestamp: u64,
+    model: &'a str,
+    elapsed_ms: u128,
+    attempts: u32,
+    chars: usize,
+    success: bool,
+}
+
+fn metrics_path() -> Result<PathBuf> {
+    let mut path = crate::config::config_dir()?;
+    path.push("latency.jsonl");
+    Ok(path)
+}
+
+/// レイテンシを JSON Lines 形式で追記する（失敗しても翻訳には影響させない）
+pub fn record_latency(model: &str, elapsed: Duration, attempts: u32, chars: usize, success: bool) {
+    let record = LatencyRecord {
+        timestamp: crate::context_cache::now_secs(),
+        model,
+        elapsed_ms: elapsed.as_millis(),
+        attempts,
+        chars,
+        success,
+    };
+
+    eprintln!(
+        "Translation finished in {} ms ({} attempts, model: {})",
+        record.elapsed_ms, attempts, model
+    );
+
+    let write = || -> Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(metrics_path()?)?;
+        writeln!(file, "{}", serde_json::to_string(&record)?)?;
+        Ok(())
+    };
+
+    if let Err(e) = write() {
+        eprintln!("Failed to record latency: {}", e);
+    }
+}
@@ -0,0 +1,44 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::context_cache::now_secs;
+use crate::gemini::ModelInfo;
+
+/// これより古い一覧は、設定画面を開いたときに取得し直す（秒）
+const MAX_AGE_SECS: u64 = 24 * 60 * 60;
+
+/// 前回取得したモデル一覧と取得時刻
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedModelList {
+    /// 取得時刻（UNIX時刻・秒）
+    fetched_at: u64,
+    models: Vec<ModelInfo>,
+}
+
+fn cache_path() -> Result<PathBuf> {
+    let mut path = crate::config::config_dir()?;
+    path.push("models_cache.json");
+    Ok(path)
+}
+
+/// 保存済みのモデル一覧を読み込む（2つ目の値は取得し直すべき古さかどうか）
+pub fn load() -> Option<(Vec<ModelInfo>, bool)> {
+    let content = fs::read_to_string(cache_path().ok()?).ok()?;
+    let cached: CachedModelList = serde_json::from_str(&content).ok()?;
+    if cached.models.is_empty() {
+        return None;
+    }
+    let stale = now_secs().saturating_sub(cached.fetched_at) > MAX_AGE_SECS;
+    Some((cached.models, stale))
+}
+
+pub fn save(models: &[ModelInfo]) -> Result<()> {
+    let cached = CachedModelList {
+        fetched_at: now_secs(),
+        models: models.to_vec(),
+    };
+    fs::write(cache_path()?, serde_json::to_string_pretty(&cached)?)?;
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+
+/// ブラウザへ送信できるメッセージの最大サイズ（Chrome/Firefox共通の上限）
+const MAX_OUTGOING_MESSAGE_BYTES: usize = 1024 * 1024;
+
+/// 拡張機能から受け取るリクエスト
+#[derive(Debug, Deserialize)]
+struct HostRequest {
+    text: String,
+    /// 応答との対応付け用ID（そのまま返す）
+    #[serde(default)]
+    id: Option<serde_json::Value>,
+}
+
+/// 拡張機能へ返すレスポンス
+#[derive(Debug, Serialize)]
+struct HostResponse {
+    id: Option<serde_json::Value>,
+    result: Option<String>,
+    error: Option<String>,
+}
+
+/// ブラウザから起動されたかを引数で判定
+/// Chrome は拡張機能のオリジン、Firefox はマニフェストのパスを第1引数に渡す
+pub fn is_browser_launch(arg: &str) -> bool {
+    arg == "--native-host" || arg.starts_with("chrome-extension://") || arg.ends_with(".json")
+}
+
+/// 長さ（4バイト・ネイティブバイトオーダー）付きのメッセージを1件読み込む
+/// 標準入力が閉じられた場合はNoneを返す
+fn read_message(input: &mut impl Read) -> Result<Option<Vec<u8>>> {
+    let mut len_bytes = [0u8; 4];
+    match input.read_exact(&mut len_bytes) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e).context("Failed to read message length"),
+    }
+
+    let len = u32::from_ne_bytes(len_bytes) as usize;
+    let mut buf = vec![0u8; len];
+    input
+        .read_exact(&mut buf)
+        .context("Failed to read message body")?;
+
+    Ok(Some(buf))
+}
+
+/// 長さ付きでメッセージを書き込む
+fn write_message(output: &mut impl Write, response: &HostResponse) -> Result<()> {
+    let mut json = serde_json::to_vec(response)?;
+
+    if json.len() > MAX_OUTGOING_MESSAGE_BYTES {
+        json = serde_json::to_vec(&HostResponse {
+            id: response.id.clone(),
+            result: None,
+            error: Some("翻訳結果が大きすぎるため送信できません".to_string()),
+        })?;
+    }
+
+    output.write_all(&(json.len() as u32).to_ne_bytes())?;
+    output.write_all(&json)?;
+    output.flush()?;
+    Ok(())
+}
+
+/// ネイティブメッセージングホストとして動作する
+/// 標準入力が閉じられる（拡張機能側が切断する）まで翻訳リクエストを処理する
+pub fn run<F>(translate: F) -> Result<()>
+where
+    F: Fn(String) -> Result<String, String>,
+{
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut input = stdin.lock();
+    let mut output = stdout.lock();
+
+    while let Some(message) = read_message(&mut input)? {
+        let response = match serde_json::from_slice::<HostRequest>(&message) {
+            Ok(request) => match translate(request.text) {
+                Ok(result) => HostResponse {
+                    id: request.id,
+                    result: Some(result),
+                    error: None,
+                },
+                Err(e) => HostResponse {
+                    id: request.id,
+                    result: None,
+                    error: Some(e),
+                },
+            },
+            Err(e) => HostResponse {
+                id: None,
+                result: None,
+                error: Some(format!("不正なリクエスト: {}", e)),
+            },
+        };
+
+        write_message(&mut output, &response)?;
+    }
+
+    Ok(())
+}
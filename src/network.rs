@@ -0,0 +1,17 @@
+use windows::Win32::Networking::WinInet::{InternetGetConnectedState, INTERNET_CONNECTION};
+
+/// オフラインで翻訳できない場合のメッセージ
+pub const OFFLINE_MESSAGE: &str = "ネットワークに接続されていません";
+
+/// ネットワークに接続されていないことが明らかか
+/// LANやWi-Fiなどの接続が1つもない場合のみ true を返し、判定できない場合は通信を試みる
+pub fn is_offline() -> bool {
+    let mut flags = INTERNET_CONNECTION(0);
+    unsafe { InternetGetConnectedState(&mut flags, 0) }.is_err()
+}
+
+/// 送信に失敗した原因が、ネットワークに接続されていないことか
+/// リトライしても成功しないため、待たずに打ち切る判定に使う
+pub fn is_offline_error(error: &reqwest::Error) -> bool {
+    error.is_connect() && is_offline()
+}
@@ -1,7 +1,17 @@
+use std::sync::Mutex;
+use windows::core::{ComInterface, IInspectable, HSTRING};
+use windows::Data::Xml::Dom::XmlDocument;
+use windows::Foundation::TypedEventHandler;
+use windows::UI::Notifications::{
+    ToastActivatedEventArgs, ToastNotification, ToastNotificationManager,
+};
 use winrt_notification::{Toast, Duration};
 
 const APP_ID: &str = "ClipboardTranslator";
 
+/// クリックを待っている通知（破棄するとクリックを受け取れなくなるため保持する）
+static ACTIVATABLE_TOAST: Mutex<Option<ToastNotification>> = Mutex::new(None);
+
 /// エラー通知を表示
 pub fn show_error(title: &str, message: &str) {
     if let Err(e) = Toast::new(APP_ID)
@@ -28,6 +38,78 @@ pub fn show_success(title: &str, message: &str) {
     }
 }
 
+/// クリック・ボタンに反応する成功通知を表示
+/// buttons は（表示名, 引数）の組で、on_activated には押されたボタンの引数（本体のクリックは空文字）が渡される
+/// 表示できない場合は、クリックに反応しない通常の成功通知にする
+pub fn show_success_with_actions<F>(
+    title: &str,
+    message: &str,
+    buttons: &[(&str, &str)],
+    on_activated: F,
+) where
+    F: Fn(&str) + Send + 'static,
+{
+    if let Err(e) = show_activatable(title, message, buttons, on_activated) {
+        eprintln!("Failed to show clickable notification: {}", e);
+        show_success(title, message);
+    }
+}
+
+fn show_activatable<F>(
+    title: &str,
+    message: &str,
+    buttons: &[(&str, &str)],
+    on_activated: F,
+) -> windows::core::Result<()>
+where
+    F: Fn(&str) + Send + 'static,
+{
+    let actions: String = buttons
+        .iter()
+        .map(|(label, argument)| {
+            format!(
+                r#"<action content="{}" arguments="{}"/>"#,
+                escape_xml(label),
+                escape_xml(argument)
+            )
+        })
+        .collect();
+    let xml = XmlDocument::new()?;
+    xml.LoadXml(&HSTRING::from(format!(
+        r#"<toast><visual><binding template="ToastGeneric"><text>{}</text><text>{}</text></binding></visual><actions>{}</actions></toast>"#,
+        escape_xml(title),
+        escape_xml(message),
+        actions
+    )))?;
+
+    let toast = ToastNotification::CreateToastNotification(&xml)?;
+    let handler = TypedEventHandler::new(move |_, args: &Option<IInspectable>| {
+        let argument = args
+            .as_ref()
+            .and_then(|args| args.cast::<ToastActivatedEventArgs>().ok())
+            .and_then(|args| args.Arguments().ok())
+            .map(|arguments| arguments.to_string_lossy())
+            .unwrap_or_default();
+        on_activated(&argument);
+        Ok(())
+    });
+    toast.Activated(&handler)?;
+    ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(APP_ID))?.Show(&toast)?;
+
+    // 新しい通知を出したら、前の通知のクリックは受け付けなくてよい
+    *crate::hotkey::lock_recovering(&ACTIVATABLE_TOAST) = Some(toast);
+    Ok(())
+}
+
+/// 通知・タスクの定義などのXMLに埋め込めるよう特殊文字をエスケープする
+pub fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
 /// 情報通知を表示
 pub fn show_info(message: &str) {
     if let Err(e) = Toast::new(APP_ID)
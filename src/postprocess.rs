@@ -0,0 +1,189 @@
+use crate::config::DisclaimerRule;
+
+/// 定型文の行を比べる前に、前後から取り除く装飾
+const DISCLAIMER_DECORATIONS: &[char] = &['*', '_', '※'];
+
+/// 行を定型文のルールと比べられる形にする（前後の空白・装飾を除き、小文字にする）
+fn normalize_disclaimer(line: &str) -> String {
+    line.trim()
+        .trim_matches(DISCLAIMER_DECORATIONS)
+        .trim()
+        .to_lowercase()
+}
+
+/// 先頭・末尾に付け足される定型文（免責文など）を取り除く
+/// 行全体が有効なルールと一致する限り、先頭と末尾から順に削除する（文中の行は残す）
+pub fn strip_disclaimers(content: &str, rules: &[DisclaimerRule]) -> String {
+    let patterns: Vec<String> = rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .map(|rule| normalize_disclaimer(&rule.pattern))
+        .filter(|pattern| !pattern.is_empty())
+        .collect();
+
+    if patterns.is_empty() {
+        return content.to_string();
+    }
+
+    let is_disclaimer = |line: &str| patterns.contains(&normalize_disclaimer(line));
+    let is_blank = |line: &str| line.trim().is_empty();
+
+    let lines: Vec<&str> = content.lines().collect();
+    let mut start = 0;
+    let mut end = lines.len();
+
+    // 空行を読み飛ばしながら、末尾の定型文を取り除く
+    let mut stripped_end = false;
+    loop {
+        while end > start && is_blank(lines[end - 1]) {
+            end -= 1;
+        }
+        if end > start && is_disclaimer(lines[end - 1]) {
+            end -= 1;
+            stripped_end = true;
+        } else {
+            break;
+        }
+    }
+
+    // 同様に先頭の定型文を取り除く
+    let mut stripped_start = false;
+    loop {
+        while start < end && is_blank(lines[start]) {
+            start += 1;
+        }
+        if start < end && is_disclaimer(lines[start]) {
+            start += 1;
+            stripped_start = true;
+        } else {
+            break;
+        }
+    }
+
+    if !stripped_start && !stripped_end {
+        return content.to_string();
+    }
+
+    // 定型文との間に残った区切り線も取り除く
+    let is_leftover = |line: &str| is_blank(line) || is_separator(line);
+    if stripped_end {
+        while end > start && is_leftover(lines[end - 1]) {
+            end -= 1;
+        }
+    }
+    if stripped_start {
+        while start < end && is_leftover(lines[start]) {
+            start += 1;
+        }
+    }
+
+    lines[start..end].join("\n")
+}
+
+/// Markdownの区切り線（---, ***, ___）かどうか
+fn is_separator(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.len() >= 3
+        && (trimmed.chars().all(|c| c == '-')
+            || trimmed.chars().all(|c| c == '*')
+            || trimmed.chars().all(|c| c == '_'))
+}
+
+/// 簡潔モードの結果をプレーンテキストにする
+/// 全体を囲むコードフェンス・引用符・強調記号・引用記号を取り除く
+pub fn strip_concise_formatting(content: &str) -> String {
+    let mut text = content.trim().to_string();
+
+    loop {
+        let stripped = strip_code_fence(&text)
+            .or_else(|| strip_enclosing(&text))
+            .or_else(|| strip_quote_marks(&text));
+
+        match stripped {
+            Some(next) => text = next.trim().to_string(),
+            None => break,
+        }
+    }
+
+    text
+}
+
+/// 全体が1つのコードフェンスで囲まれている場合に中身を返す
+fn strip_code_fence(text: &str) -> Option<String> {
+    let rest = text.strip_prefix("```")?;
+    let body = rest.strip_suffix("```")?;
+    // 1行目の言語指定（```text など）を読み飛ばす
+    let (_, inner) = body.split_once('\n')?;
+    Some(inner.to_string())
+}
+
+/// 全体を囲む引用符・強調記号を取り除く
+fn strip_enclosing(text: &str) -> Option<String> {
+    const PAIRS: &[(&str, &str)] = &[
+        ("**", "**"),
+        ("\"", "\""),
+        ("“", "”"),
+        ("「", "」"),
+        ("『", "』"),
+        ("'", "'"),
+        ("*", "*"),
+    ];
+
+    PAIRS.iter().find_map(|(open, close)| {
+        let inner = text.strip_prefix(open)?.strip_suffix(close)?;
+        // 途中で閉じている場合（「A」と「B」など）は全体を囲んでいないので残す
+        (!inner.is_empty() && !inner.contains(close)).then(|| inner.to_string())
+    })
+}
+
+/// すべての行が引用（> ）の場合に引用記号を取り除く
+fn strip_quote_marks(text: &str) -> Option<String> {
+    if !text.lines().all(|line| line.trim_start().starts_with('>')) {
+        return None;
+    }
+
+    Some(
+        text.lines()
+            .map(|line| {
+                let line = line.trim_start().trim_start_matches('>');
+                line.strip_prefix(' ').unwrap_or(line)
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> Vec<DisclaimerRule> {
+        vec![DisclaimerRule::new(
+            "AIによる翻訳のため、正確でない場合があります。",
+        )]
+    }
+
+    #[test]
+    fn strips_matching_leading_and_trailing_lines() {
+        let content = "※AIによる翻訳のため、正確でない場合があります。\n\nこんにちは。\n\n---\n*AIによる翻訳のため、正確でない場合があります。*\n";
+        assert_eq!(strip_disclaimers(content, &rules()), "こんにちは。");
+    }
+
+    #[test]
+    fn keeps_lines_that_only_contain_the_rule() {
+        // 文の一部に定型文を含むだけの行や、文中の行は訳文として残す
+        let content = "この文はAIによる翻訳のため、正確でない場合があります。と書かれていた。";
+        assert_eq!(strip_disclaimers(content, &rules()), content);
+
+        let content = "一行目\nAIによる翻訳のため、正確でない場合があります。\n三行目";
+        assert_eq!(strip_disclaimers(content, &rules()), content);
+    }
+
+    #[test]
+    fn disabled_rules_are_ignored() {
+        let mut rules = rules();
+        rules[0].enabled = false;
+        let content = "本文\nAIによる翻訳のため、正確でない場合があります。";
+        assert_eq!(strip_disclaimers(content, &rules), content);
+    }
+}
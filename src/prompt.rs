@@ -0,0 +1,344 @@
+//! 翻訳のプロンプトの組み立て（Gemini・OpenAI互換APIで共通）
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::config::{Config, LanguagePair, OutputMode, ReadingLevel};
+use crate::gemini::Translation;
+use crate::language::DETECTION_REPORT_PREFIX;
+
+/// 出力モード・翻訳方向・用語集などの設定から、モデルへ送るプロンプトを組み立てる
+#[derive(Debug, Clone)]
+pub struct PromptBuilder {
+    output_mode: OutputMode,
+    reading_level: ReadingLevel,
+    preserve_markdown: bool,
+    /// 詳細モードで訳文の読み方を付ける
+    include_reading: bool,
+    language_pair: Option<LanguagePair>,
+    /// 翻訳先の言語（原文がこの言語の場合は英語へ）
+    target_language: String,
+    /// 原文の言語（ユーザーが指定した場合は判定を省く）
+    source_language: Option<String>,
+    /// 言語判定の信頼度を応答の最後に報告させる
+    report_detection: bool,
+    target_override: Option<String>,
+    /// カスタムモードのプロンプト（{text} に原文が入る）
+    custom_prompt: Option<String>,
+    /// 固定の訳語（原語 → 訳語）
+    glossary: BTreeMap<String, String>,
+}
+
+impl PromptBuilder {
+    pub fn new(output_mode: OutputMode) -> Self {
+        Self {
+            output_mode,
+            reading_level: ReadingLevel::default(),
+            preserve_markdown: false,
+            include_reading: false,
+            language_pair: None,
+            target_language: crate::language::DEFAULT_TARGET_LANGUAGE.to_string(),
+            source_language: None,
+            report_detection: false,
+            target_override: None,
+            custom_prompt: None,
+            glossary: BTreeMap::new(),
+        }
+    }
+
+    /// 設定からプロンプトの組み立て方を決める（target は今回だけの翻訳先。スマートスワップ用）
+    pub fn from_config(config: &Config, target: Option<String>) -> Self {
+        Self::new(config.output_mode)
+            .with_default_target_language(config.target_language.clone())
+            .with_target_language(target)
+            .with_language_pair(config.language_pair.clone())
+            .with_source_language(config.source_language_hint().map(str::to_string))
+            .with_detection_report(config.min_detection_confidence > 0)
+            .with_reading_level(config.reading_level)
+            .with_preserve_markdown(config.preserve_markdown)
+            .with_reading(config.include_reading)
+            .with_custom_prompt(config.custom_prompt.clone())
+            .with_glossary(config.glossary.clone())
+    }
+
+    /// 常に指定の訳語を使わせる用語集を設定する
+    pub fn with_glossary(mut self, glossary: BTreeMap<String, String>) -> Self {
+        self.glossary = glossary;
+        self
+    }
+
+    /// カスタムモードで使うプロンプトを設定する
+    pub fn with_custom_prompt(mut self, template: Option<String>) -> Self {
+        self.custom_prompt = template;
+        self
+    }
+
+    /// 翻訳先の言語を設定する（空の場合は既定の日本語）
+    pub fn with_default_target_language(mut self, language: String) -> Self {
+        if !language.trim().is_empty() {
+            self.target_language = language.trim().to_string();
+        }
+        self
+    }
+
+    /// 原文の言語を指定する（判定を誤った場合の再翻訳用）
+    pub fn with_source_language(mut self, language: Option<String>) -> Self {
+        self.source_language = language;
+        self
+    }
+
+    /// 言語判定の信頼度を報告させるかを設定する
+    pub fn with_detection_report(mut self, enabled: bool) -> Self {
+        self.report_detection = enabled;
+        self
+    }
+
+    /// 双方向翻訳の言語ペアを設定する（検出した言語で翻訳方向を決める）
+    pub fn with_language_pair(mut self, pair: Option<LanguagePair>) -> Self {
+        self.language_pair = pair;
+        self
+    }
+
+    /// 「わかりやすく」モードの解説の難易度を設定する
+    pub fn with_reading_level(mut self, level: ReadingLevel) -> Self {
+        self.reading_level = level;
+        self
+    }
+
+    /// 詳細モードで訳文の読み方（ひらがな・ローマ字）を付けるかを設定する
+    pub fn with_reading(mut self, enabled: bool) -> Self {
+        self.include_reading = enabled;
+        self
+    }
+
+    /// Markdown文書の入力時に構造を保ったまま翻訳するかを設定する
+    pub fn with_preserve_markdown(mut self, enabled: bool) -> Self {
+        self.preserve_markdown = enabled;
+        self
+    }
+
+    /// 翻訳先言語を明示的に指定する（スマートスワップ用）
+    pub fn with_target_language(mut self, target: Option<String>) -> Self {
+        self.target_override = target;
+        self
+    }
+
+    /// 翻訳方向の指示文（セクション内の記述用、箇条書き用）を返す
+    pub fn translation_direction(&self) -> (String, String) {
+        if let Some(target) = &self.target_override {
+            return (format!("{}へ翻訳", target), format!("{}へ翻訳", target));
+        }
+
+        if let Some(pair) = &self.language_pair {
+            return (
+                format!(
+                    "{a}の場合は{b}へ、{b}の場合は{a}へ翻訳（どちらでもない場合は{a}へ）",
+                    a = pair.a,
+                    b = pair.b
+                ),
+                format!(
+                    "{a}なら{b}へ、{b}なら{a}へ（どちらでもなければ{a}へ）",
+                    a = pair.a,
+                    b = pair.b
+                ),
+            );
+        }
+
+        let target = &self.target_language;
+        let fallback = crate::language::fallback_target(target);
+        (
+            format!("{target}の場合は{fallback}へ、それ以外は{target}へ翻訳"),
+            format!("{target}なら{fallback}へ、それ以外なら{target}へ"),
+        )
+    }
+
+    /// 漢字1文字の字典カード用プロンプト
+    fn build_character_prompt(character: char) -> String {
+        format!(
+            r#"次の漢字1文字について、字典のカード形式で解説してください。翻訳は不要です。
+以下のMarkdown形式のみで出力してください（前置きは不要）:
+
+# {c}
+
+| 項目 | 内容 |
+|---|---|
+| 音読み | [カタカナで。なければ「なし」] |
+| 訓読み | [ひらがなで。送り仮名は括弧書き。なければ「なし」] |
+| ピンイン | [声調記号付きで。簡体字・繁体字が異なる場合は併記] |
+| 意味 | [主な意味を「；」区切りで] |
+| 総画数 | [数字]画 |
+| 部首 | [部首（部首名）] |
+| 構成要素 | [字を構成する部品と、その意味・音の役割] |
+
+## 用例
+- [その字を使った代表的な熟語3つを、読みと意味付きで]
+
+漢字: {c}"#,
+            c = character
+        )
+    }
+
+    /// 原文を送るプロンプトを組み立てる
+    pub fn build(&self, text: &str) -> String {
+        // 漢字1文字だけの入力は翻訳ではなく字典として解説する
+        if let Some(character) = crate::language::single_cjk_character(text) {
+            return Self::build_character_prompt(character);
+        }
+
+        let mut prompt = String::new();
+
+        if let Some(language) = &self.source_language {
+            prompt.push_str(&format!(
+                "原文の言語は{}です（言語の判定は不要です）。\n\n",
+                language
+            ));
+        }
+
+        // 用語集が空の場合は何も書かない（モデルを混乱させないため）
+        if !self.glossary.is_empty() {
+            prompt.push_str("以下の用語は、必ず指定の訳語で翻訳してください:\n");
+            for (term, translation) in &self.glossary {
+                prompt.push_str(&format!("- {} → {}\n", term, translation));
+            }
+            prompt.push('\n');
+        }
+
+        prompt.push_str(&self.build_translation_prompt(text));
+
+        if self.report_detection && self.source_language.is_none() {
+            prompt.push_str(&format!(
+                "\n\n回答の最後の行に、原文の言語の判定結果を「{} 言語名 | 信頼度(0-100の整数)」の形式で1行だけ追加してください。",
+                DETECTION_REPORT_PREFIX
+            ));
+        }
+
+        prompt
+    }
+
+    fn build_translation_prompt(&self, text: &str) -> String {
+        let (direction, direction_rule) = self.translation_direction();
+
+        // Markdown文書は出力モードに関わらず構造を保持して翻訳する
+        if self.preserve_markdown && crate::content_kind::looks_like_markdown(text) {
+            return format!(
+                r#"以下のMarkdown文書を翻訳してください。
+- {}
+- 見出し・リスト・引用・表・リンクなどのMarkdown構造はそのまま保持し、文章部分のみ翻訳
+- コードブロック、インラインコード、URLは一切変更しない
+- 出力は翻訳後のMarkdown文書のみ（前置きや説明は不要）
+
+文書:
+{}"#,
+                direction_rule, text
+            );
+        }
+
+        // 読み方は翻訳先が日本語の場合のみ意味がある
+        let reading_section = if self.include_reading
+            && crate::language::is_japanese_target(&self.target_language)
+        {
+            "【読み方】\n[訳文が日本語の場合は、ひらがなの読みとローマ字。日本語でなければ「なし」]\n\n"
+        } else {
+            ""
+        };
+
+        match self.output_mode {
+            OutputMode::Detailed => format!(
+                r#"以下のテキストを分析し、以下の形式で回答してください:
+
+【言語判定】
+検出言語: [言語名]
+
+【翻訳】
+[{}]
+
+{}【スラング・特殊表現】
+[該当する表現があれば解説、なければ「なし」]
+
+【要約】
+[テキストの要点を1-2文で]
+
+---
+テキスト:
+{}"#,
+                direction, reading_section, text
+            ),
+            OutputMode::Concise => format!(
+                r#"以下のテキストを翻訳してください。
+- {}
+- 5行以内で要点のみ
+- 余計な説明不要、翻訳結果だけ出力
+
+テキスト:
+{}"#,
+                direction_rule, text
+            ),
+            OutputMode::Simple => format!(
+                r#"以下のテキストを翻訳し、その内容を平易な言葉で解説してください。以下の形式で回答してください:
+
+【翻訳】
+[{}]
+
+【わかりやすく】
+[{}、翻訳先の言語で内容を説明]
+
+---
+テキスト:
+{}"#,
+                direction,
+                self.reading_level.audience(),
+                text
+            ),
+            OutputMode::Dictionary => format!(
+                r#"以下の単語を辞書の項目のように解説してください。翻訳先の言語は「{}」の規則で決め、解説はその言語で書いてください。
+以下のMarkdown形式のみで出力してください（前置きは不要）:
+
+## [見出し語]（[発音記号または読み]）
+
+### 品詞
+[品詞。複数の品詞がある場合はすべて]
+
+### 意味
+1. [よく使われる意味から順に、品詞ごとに]
+
+### 例文
+- [原文の言語での例文] — [その訳]
+（2〜3例）
+
+### 類義語・関連語
+[あれば列挙、なければ「なし」]
+
+単語:
+{}"#,
+                direction_rule, text
+            ),
+            OutputMode::Custom => self
+                .custom_prompt
+                .as_deref()
+                .unwrap_or(crate::config::DEFAULT_CUSTOM_PROMPT)
+                .replace("{text}", text),
+        }
+    }
+
+    /// 応答の本文から翻訳結果を組み立てる（言語判定の報告行があれば取り出す）
+    pub fn finish_translation(
+        &self,
+        content: String,
+        elapsed: Duration,
+        attempts: u32,
+    ) -> Translation {
+        let (content, detected) = if self.report_detection {
+            crate::language::take_detection_report(&content)
+        } else {
+            (content, None)
+        };
+
+        Translation {
+            content,
+            elapsed,
+            attempts,
+            detected,
+            table_copied: false,
+        }
+    }
+}
@@ -0,0 +1,411 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+use crate::config::{Config, Provider};
+use crate::gemini::{
+    build_header_map, create_client, describe_server_busy, send_with_policy, GeminiClient,
+    RetryNotifier, RetryPolicy, Translation,
+};
+use crate::prompt::PromptBuilder;
+
+/// 翻訳を実行するバックエンド（Gemini / OpenAI互換API / DeepL）
+pub trait TranslationProvider {
+    async fn translate(&self, text: &str) -> Result<Translation>;
+}
+
+impl TranslationProvider for GeminiClient {
+    async fn translate(&self, text: &str) -> Result<Translation> {
+        self.translate_and_explain(text).await
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    #[serde(default)]
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatResponseMessage,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponseMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+/// OpenAI互換の /chat/completions エンドポイントを使うクライアント
+/// プロンプトはGeminiと共通（出力モード・翻訳方向などの設定をそのまま使う）
+pub struct OpenAiClient {
+    api_key: String,
+    /// APIのベースURL（例: https://api.openai.com/v1）
+    base_url: String,
+    model: String,
+    prompts: PromptBuilder,
+    shared_context: String,
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+    extra_headers: BTreeMap<String, String>,
+    retry_policy: RetryPolicy,
+    retry_notifier: Option<RetryNotifier>,
+    client: Client,
+}
+
+impl OpenAiClient {
+    pub fn new(api_key: String, base_url: String, model: String, prompts: PromptBuilder) -> Self {
+        Self {
+            api_key,
+            base_url,
+            model,
+            prompts,
+            shared_context: String::new(),
+            temperature: None,
+            max_tokens: None,
+            extra_headers: BTreeMap::new(),
+            retry_policy: RetryPolicy::default(),
+            retry_notifier: None,
+            client: create_client(crate::gemini::API_TIMEOUT_SECS)
+                .unwrap_or_else(|_| Client::new()),
+        }
+    }
+
+    /// タイムアウトとリトライ回数・間隔を設定する
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.client = create_client(policy.timeout_secs).unwrap_or_else(|_| Client::new());
+        self.retry_policy = policy;
+        self
+    }
+
+    /// サーバー混雑でリトライを待つたびに呼ばれる通知を設定する（待機状態の表示用）
+    pub fn with_retry_notifier(
+        mut self,
+        notify: impl Fn(u16, Instant) + Send + Sync + 'static,
+    ) -> Self {
+        self.retry_notifier = Some(Box::new(notify));
+        self
+    }
+
+    /// 毎回の翻訳に付ける共有コンテキストを設定する（systemメッセージとして送る）
+    pub fn with_shared_context(mut self, context: String) -> Self {
+        self.shared_context = context;
+        self
+    }
+
+    /// 温度と最大出力トークン数を設定する（Noneの項目はサーバー側の既定値）
+    pub fn with_generation_config(
+        mut self,
+        temperature: Option<f32>,
+        max_tokens: Option<u32>,
+    ) -> Self {
+        self.temperature = temperature;
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// すべてのリクエストに付ける追加HTTPヘッダーを設定する
+    pub fn with_extra_headers(mut self, headers: BTreeMap<String, String>) -> Self {
+        self.extra_headers = headers;
+        self
+    }
+
+    async fn request(&self, prompt: &str, attempts: &mut u32) -> Result<String> {
+        let headers = build_header_map(&self.extra_headers)?;
+        let url = format!("{}/chat/completions", self.base_url.trim_end_matches('/'));
+
+        let mut messages = Vec::new();
+        if !self.shared_context.trim().is_empty() {
+            messages.push(ChatMessage {
+                role: "system",
+                content: &self.shared_context,
+            });
+        }
+        messages.push(ChatMessage {
+            role: "user",
+            content: prompt,
+        });
+
+        let request_body = ChatRequest {
+            model: &self.model,
+            messages,
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+        };
+
+        let response = send_with_policy(
+            self.retry_policy,
+            attempts,
+            self.retry_notifier.as_ref(),
+            || {
+                self.client
+                    .post(&url)
+                    .bearer_auth(&self.api_key)
+                    .headers(headers.clone())
+                    .json(&request_body)
+            },
+            // 429 と 5xx はリトライ対象
+            |status| status.as_u16() == 429 || status.is_server_error(),
+            describe_server_busy,
+        )
+        .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            // その他のエラーは即座に失敗
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("API Error {}: {}", status, error_text);
+        }
+
+        let chat_response: ChatResponse = response
+            .json()
+            .await
+            .context("Failed to parse chat completion response")?;
+
+        let choice = chat_response
+            .choices
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("APIからの応答が空です。"))?;
+        let content = choice
+            .message
+            .content
+            .filter(|c| !c.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("応答コンテンツが空です。"))?;
+
+        // 途中で切れても返す（警告付き）
+        Ok(match choice.finish_reason.as_deref() {
+            Some("length") => format!("{}{}", content, crate::gemini::TRUNCATED_WARNING),
+            _ => content,
+        })
+    }
+}
+
+impl TranslationProvider for OpenAiClient {
+    async fn translate(&self, text: &str) -> Result<Translation> {
+        let start = Instant::now();
+        let mut attempts = 0;
+        let content = self
+            .request(&self.prompts.build(text), &mut attempts)
+            .await?;
+
+        Ok(self
+            .prompts
+            .finish_translation(content, start.elapsed(), attempts))
+    }
+}
+
+/// DeepL API Free のエンドポイント
+const DEEPL_ENDPOINT: &str = "https://api-free.deepl.com/v2/translate";
+
+#[derive(Debug, Deserialize)]
+struct DeepLResponse {
+    #[serde(default)]
+    translations: Vec<DeepLTranslation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepLTranslation {
+    text: String,
+}
+
+/// DeepL API を使うクライアント（訳文のみ。出力モードは簡潔モード相当）
+pub struct DeepLClient {
+    auth_key: String,
+    /// 翻訳先の言語コード（例: JA, EN-US）
+    target_lang: String,
+    extra_headers: BTreeMap<String, String>,
+    retry_policy: RetryPolicy,
+    retry_notifier: Option<RetryNotifier>,
+    client: Client,
+}
+
+impl DeepLClient {
+    pub fn new(auth_key: String, target_lang: String) -> Self {
+        Self {
+            auth_key,
+            target_lang,
+            extra_headers: BTreeMap::new(),
+            retry_policy: RetryPolicy::default(),
+            retry_notifier: None,
+            client: create_client(crate::gemini::API_TIMEOUT_SECS)
+                .unwrap_or_else(|_| Client::new()),
+        }
+    }
+
+    /// タイムアウトとリトライ回数・間隔を設定する
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.client = create_client(policy.timeout_secs).unwrap_or_else(|_| Client::new());
+        self.retry_policy = policy;
+        self
+    }
+
+    /// サーバー混雑でリトライを待つたびに呼ばれる通知を設定する（待機状態の表示用）
+    pub fn with_retry_notifier(
+        mut self,
+        notify: impl Fn(u16, Instant) + Send + Sync + 'static,
+    ) -> Self {
+        self.retry_notifier = Some(Box::new(notify));
+        self
+    }
+
+    /// すべてのリクエストに付ける追加HTTPヘッダーを設定する
+    pub fn with_extra_headers(mut self, headers: BTreeMap<String, String>) -> Self {
+        self.extra_headers = headers;
+        self
+    }
+
+    async fn request(&self, text: &str, attempts: &mut u32) -> Result<String> {
+        let headers = build_header_map(&self.extra_headers)?;
+        let params = [("text", text), ("target_lang", self.target_lang.as_str())];
+
+        let response = send_with_policy(
+            self.retry_policy,
+            attempts,
+            self.retry_notifier.as_ref(),
+            || {
+                self.client
+                    .post(DEEPL_ENDPOINT)
+                    .header(
+                        reqwest::header::AUTHORIZATION,
+                        format!("DeepL-Auth-Key {}", self.auth_key),
+                    )
+                    .headers(headers.clone())
+                    .form(&params)
+            },
+            // 429 と 5xx はリトライ対象
+            |status| status.as_u16() == 429 || status.is_server_error(),
+            describe_server_busy,
+        )
+        .await?;
+
+        let status = response.status();
+
+        // 456 は月間の文字数上限（リトライしても回復しない）
+        if status.as_u16() == 456 {
+            anyhow::bail!("DeepL APIの利用上限に達しました。");
+        }
+
+        if !status.is_success() {
+            // その他のエラーは即座に失敗
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("API Error {}: {}", status, error_text);
+        }
+
+        let deepl_response: DeepLResponse = response
+            .json()
+            .await
+            .context("Failed to parse DeepL response")?;
+
+        deepl_response
+            .translations
+            .into_iter()
+            .next()
+            .map(|t| t.text)
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("APIからの応答が空です。"))
+    }
+}
+
+impl TranslationProvider for DeepLClient {
+    async fn translate(&self, text: &str) -> Result<Translation> {
+        let start = Instant::now();
+        let mut attempts = 0;
+        let content = self.request(text, &mut attempts).await?;
+
+        Ok(Translation {
+            content,
+            elapsed: start.elapsed(),
+            attempts,
+            detected: None,
+            table_copied: false,
+        })
+    }
+}
+
+/// 設定で選んだ翻訳のバックエンド
+pub enum Backend {
+    Gemini(GeminiClient),
+    OpenAi(OpenAiClient),
+    DeepL(DeepLClient),
+}
+
+impl Backend {
+    /// 設定から選択中のプロバイダーのクライアントを作る（target は今回だけの翻訳先。スマートスワップ用）
+    pub fn from_config(config: &Config, target: Option<String>) -> Self {
+        let prompts = PromptBuilder::from_config(config, target);
+        match config.provider {
+            Provider::Gemini => Backend::Gemini(
+                GeminiClient::new(config.api_key.clone(), config.model.clone(), prompts)
+                    .with_extra_headers(config.extra_headers.clone())
+                    .with_generation_config(config.temperature, config.max_output_tokens)
+                    .with_retry_policy(config.retry_policy())
+                    .with_chunk_threshold(config.chunk_chars)
+                    .with_shared_context(
+                        config.shared_context.clone(),
+                        config.context_cache_ttl_secs,
+                    ),
+            ),
+            Provider::OpenAi => Backend::OpenAi(
+                OpenAiClient::new(
+                    config.api_key.clone(),
+                    config.openai_base_url.clone(),
+                    config.openai_model.clone(),
+                    prompts,
+                )
+                .with_shared_context(config.shared_context.clone())
+                .with_generation_config(config.temperature, config.max_output_tokens)
+                .with_extra_headers(config.extra_headers.clone())
+                .with_retry_policy(config.retry_policy()),
+            ),
+            Provider::DeepL => Backend::DeepL(
+                DeepLClient::new(config.api_key.clone(), config.deepl_target_lang.clone())
+                    .with_extra_headers(config.extra_headers.clone())
+                    .with_retry_policy(config.retry_policy()),
+            ),
+        }
+    }
+
+    /// サーバー混雑でリトライを待つたびに呼ばれる通知を設定する（待機状態の表示用）
+    pub fn with_retry_notifier(
+        self,
+        notify: impl Fn(u16, Instant) + Send + Sync + 'static,
+    ) -> Self {
+        match self {
+            Backend::Gemini(client) => Backend::Gemini(client.with_retry_notifier(notify)),
+            Backend::OpenAi(client) => Backend::OpenAi(client.with_retry_notifier(notify)),
+            Backend::DeepL(client) => Backend::DeepL(client.with_retry_notifier(notify)),
+        }
+    }
+}
+
+impl TranslationProvider for Backend {
+    async fn translate(&self, text: &str) -> Result<Translation> {
+        match self {
+            Backend::Gemini(client) => client.translate(text).await,
+            Backend::OpenAi(client) => client.translate(text).await,
+            Backend::DeepL(client) => client.translate(text).await,
+        }
+    }
+}
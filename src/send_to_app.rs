@@ -0,0 +1,190 @@
+use anyhow::{Context, Result};
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+use windows::core::PWSTR;
+use windows::Win32::Foundation::{CloseHandle, BOOL, HWND, LPARAM};
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+    PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+    VIRTUAL_KEY, VK_CONTROL,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    EnumWindows, GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId, IsIconic,
+    IsWindowVisible, SetForegroundWindow, ShowWindow, SW_RESTORE,
+};
+
+use crate::config::SendToApp;
+
+/// ウィンドウをアクティブにしてから貼り付けるまでの待ち時間
+const ACTIVATE_DELAY_MS: u64 = 150;
+
+/// 翻訳結果を指定アプリへ送る（クリップボードに書き込み、対象ウィンドウで貼り付け）
+/// 対象が起動していない場合は、起動コマンドがあれば起動して通知する
+pub fn send(target: &SendToApp, text: &str) -> Result<()> {
+    crate::clipboard::set_text(text)?;
+
+    let Some(hwnd) = find_window(target) else {
+        if target.launch_command.trim().is_empty() {
+            anyhow::bail!("送信先のアプリが見つかりません");
+        }
+
+        Command::new(target.launch_command.trim())
+            .spawn()
+            .context("送信先のアプリを起動できませんでした")?;
+        crate::notification::show_info(
+            "送信先のアプリを起動しました。翻訳結果はクリップボードにあります",
+        );
+        return Ok(());
+    };
+
+    unsafe {
+        if IsIconic(hwnd).as_bool() {
+            ShowWindow(hwnd, SW_RESTORE);
+        }
+        if !SetForegroundWindow(hwnd).as_bool() {
+            anyhow::bail!("送信先のウィンドウをアクティブにできませんでした");
+        }
+    }
+
+    thread::sleep(Duration::from_millis(ACTIVATE_DELAY_MS));
+    paste();
+
+    Ok(())
+}
+
+/// 探索中の条件と見つかったウィンドウ（EnumWindows のコールバックに渡す）
+struct Search<'a> {
+    target: &'a SendToApp,
+    found: Option<HWND>,
+}
+
+fn find_window(target: &SendToApp) -> Option<HWND> {
+    if target.window_title.trim().is_empty() && target.process_name.trim().is_empty() {
+        return None;
+    }
+
+    let mut search = Search {
+        target,
+        found: None,
+    };
+
+    unsafe {
+        // コールバックが FALSE を返して列挙を打ち切った場合も Err になるため無視する
+        let _ = EnumWindows(
+            Some(enum_window_proc),
+            LPARAM(&mut search as *mut Search as isize),
+        );
+    }
+
+    search.found
+}
+
+unsafe extern "system" fn enum_window_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let search = &mut *(lparam.0 as *mut Search);
+
+    if !IsWindowVisible(hwnd).as_bool() {
+        return BOOL(1);
+    }
+
+    if matches_window(hwnd, search.target) {
+        search.found = Some(hwnd);
+        return BOOL(0);
+    }
+
+    BOOL(1)
+}
+
+/// ウィンドウタイトル（部分一致）とプロセス名（実行ファイル名）の両方を満たすか
+fn matches_window(hwnd: HWND, target: &SendToApp) -> bool {
+    let title_pattern = target.window_title.trim().to_lowercase();
+    if !title_pattern.is_empty() && !window_title(hwnd).to_lowercase().contains(&title_pattern) {
+        return false;
+    }
+
+    let process_pattern = target.process_name.trim().to_lowercase();
+    if !process_pattern.is_empty() {
+        let Some(name) = process_name(hwnd) else {
+            return false;
+        };
+        if name.to_lowercase() != process_pattern {
+            return false;
+        }
+    }
+
+    true
+}
+
+fn window_title(hwnd: HWND) -> String {
+    let mut buffer = [0u16; 512];
+    let len = unsafe { GetWindowTextW(hwnd, &mut buffer) };
+    String::from_utf16_lossy(&buffer[..len.max(0) as usize])
+}
+
+/// 最前面のウィンドウのプロセスの実行ファイル名（コピー元のアプリの判定用）
+pub fn foreground_process_name() -> Option<String> {
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd.0 == 0 {
+        return None;
+    }
+    process_name(hwnd)
+}
+
+/// ウィンドウを所有するプロセスの実行ファイル名（例: "notepad.exe"）
+fn process_name(hwnd: HWND) -> Option<String> {
+    unsafe {
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if pid == 0 {
+            return None;
+        }
+
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buffer = [0u16; 1024];
+        let mut size = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut size,
+        );
+        let _ = CloseHandle(process);
+        result.ok()?;
+
+        let path = String::from_utf16_lossy(&buffer[..size as usize]);
+        path.rsplit('\\').next().map(|name| name.to_string())
+    }
+}
+
+fn key_input(vk: VIRTUAL_KEY, flags: KEYBD_EVENT_FLAGS) -> INPUT {
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: vk,
+                wScan: 0,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
+/// Ctrl+V を送信して貼り付ける
+fn paste() {
+    let vk_v = VIRTUAL_KEY(b'V' as u16);
+    let inputs = [
+        key_input(VK_CONTROL, KEYBD_EVENT_FLAGS(0)),
+        key_input(vk_v, KEYBD_EVENT_FLAGS(0)),
+        key_input(vk_v, KEYEVENTF_KEYUP),
+        key_input(VK_CONTROL, KEYEVENTF_KEYUP),
+    ];
+
+    unsafe {
+        SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+    }
+}
@@ -1,10 +1,36 @@
 use anyhow::{Context, Result};
 use std::env;
+use std::fs;
+use std::os::windows::process::CommandExt;
+use std::process::Command;
 use winreg::enums::*;
 use winreg::RegKey;
 
+use crate::config::StartupMethod;
+
 const APP_NAME: &str = "ClipboardTranslator";
 
+/// schtasks 実行時にコンソールウィンドウを表示しない
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// 指定の方法で自動起動を登録し、もう一方の登録は削除する
+pub fn install(method: StartupMethod) -> Result<()> {
+    match method {
+        // タスクの削除には管理者権限が必要なため、削除できない場合は二重に登録しないよう先に確認する
+        StartupMethod::Registry => {
+            uninstall_startup_task().context(
+                "タスクスケジューラの登録を削除できないため、レジストリ方式に切り替えられません。\n\
+                管理者として実行し直すか、タスクスケジューラから「ClipboardTranslator」を削除してください",
+            )?;
+            install_startup()
+        }
+        StartupMethod::TaskScheduler => {
+            install_startup_task()?;
+            uninstall_startup_registry()
+        }
+    }
+}
+
 pub fn install_startup() -> Result<()> {
     let exe_path = env::current_exe().context("Failed to get executable path")?;
     let exe_path_str = exe_path.to_string_lossy();
@@ -20,7 +46,66 @@ pub fn install_startup() -> Result<()> {
     Ok(())
 }
 
+/// ログオン時に最上位の特権で起動するタスクの定義
+/// 常駐アプリのため、バッテリー駆動時も起動・継続し、実行時間の上限（既定は72時間）も設けない
+const TASK_XML: &str = r#"<?xml version="1.0" encoding="UTF-16"?>
+<Task version="1.2" xmlns="http://schemas.microsoft.com/windows/2004/02/mit/task">
+  <Triggers>
+    <LogonTrigger>
+      <Enabled>true</Enabled>
+    </LogonTrigger>
+  </Triggers>
+  <Principals>
+    <Principal id="Author">
+      <LogonType>InteractiveToken</LogonType>
+      <RunLevel>HighestAvailable</RunLevel>
+    </Principal>
+  </Principals>
+  <Settings>
+    <MultipleInstancesPolicy>IgnoreNew</MultipleInstancesPolicy>
+    <DisallowStartIfOnBatteries>false</DisallowStartIfOnBatteries>
+    <StopIfGoingOnBatteries>false</StopIfGoingOnBatteries>
+    <ExecutionTimeLimit>PT0S</ExecutionTimeLimit>
+    <Enabled>true</Enabled>
+  </Settings>
+  <Actions Context="Author">
+    <Exec>
+      <Command>{command}</Command>
+    </Exec>
+  </Actions>
+</Task>
+"#;
+
+/// ログオン時に最上位の特権で起動するタスクを登録する（登録には管理者権限が必要）
+pub fn install_startup_task() -> Result<()> {
+    let exe_path = env::current_exe().context("Failed to get executable path")?;
+    let xml = TASK_XML.replace(
+        "{command}",
+        &crate::notification::escape_xml(&exe_path.to_string_lossy()),
+    );
+
+    // schtasks は UTF-16（BOM付き）のXMLを読み込む
+    let xml_path = env::temp_dir().join(format!("{}_task.xml", APP_NAME));
+    let bytes: Vec<u8> = std::iter::once(0xFEFF)
+        .chain(xml.encode_utf16())
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    fs::write(&xml_path, bytes).context("Failed to write task definition")?;
+
+    let xml_path_str = xml_path.to_string_lossy();
+    let result = run_schtasks(&["/Create", "/TN", APP_NAME, "/XML", &xml_path_str, "/F"]);
+    let _ = fs::remove_file(&xml_path);
+
+    result.context("タスクの登録に失敗しました（管理者として実行してください）")
+}
+
+/// 登録されている自動起動をすべて削除する
 pub fn uninstall_startup() -> Result<()> {
+    uninstall_startup_registry()?;
+    uninstall_startup_task()
+}
+
+fn uninstall_startup_registry() -> Result<()> {
     let hkcu = RegKey::predef(HKEY_CURRENT_USER);
     let key = hkcu
         .open_subkey_with_flags(r"Software\Microsoft\Windows\CurrentVersion\Run", KEY_WRITE)
@@ -32,7 +117,33 @@ pub fn uninstall_startup() -> Result<()> {
     Ok(())
 }
 
+fn uninstall_startup_task() -> Result<()> {
+    // タスクが存在しない場合もエラーにしない
+    if !is_task_installed() {
+        return Ok(());
+    }
+
+    run_schtasks(&["/Delete", "/TN", APP_NAME, "/F"])
+        .context("タスクの削除に失敗しました（管理者として実行してください）")
+}
+
+/// いずれかの方法で自動起動が登録されているか
 pub fn is_installed() -> bool {
+    installed_method().is_some()
+}
+
+/// 登録されている自動起動の方法（未登録の場合はNone）
+pub fn installed_method() -> Option<StartupMethod> {
+    if is_registry_installed() {
+        Some(StartupMethod::Registry)
+    } else if is_task_installed() {
+        Some(StartupMethod::TaskScheduler)
+    } else {
+        None
+    }
+}
+
+fn is_registry_installed() -> bool {
     let hkcu = RegKey::predef(HKEY_CURRENT_USER);
     if let Ok(key) = hkcu.open_subkey(r"Software\Microsoft\Windows\CurrentVersion\Run") {
         key.get_value::<String, _>(APP_NAME).is_ok()
@@ -40,3 +151,23 @@ pub fn is_installed() -> bool {
         false
     }
 }
+
+fn is_task_installed() -> bool {
+    run_schtasks(&["/Query", "/TN", APP_NAME]).is_ok()
+}
+
+/// schtasks を実行し、失敗した場合はその出力をエラーにする
+fn run_schtasks(args: &[&str]) -> Result<()> {
+    let output = Command::new("schtasks")
+        .args(args)
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .context("Failed to run schtasks")?;
+
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{}", message.trim());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+//! タブ区切り（TSV）の表データの解析と組み立て
+//! Excel・スプレッドシートからコピーした表は、改行・タブ・引用符を含むセルが "..." で囲まれる
+
+/// 表として扱う場合の1行あたりの最小列数
+const MIN_COLUMNS: usize = 2;
+
+/// 表として扱う場合の最小行数（タブを含む1行だけの文章は表にしない）
+const MIN_ROWS: usize = 2;
+
+/// TSVをセルに分解する（引用符で囲まれたセル内の改行・タブに対応）
+fn split_cells(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    cell.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => cell.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if cell.is_empty() => in_quotes = true,
+            '\t' => row.push(std::mem::take(&mut cell)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                row.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => cell.push(c),
+        }
+    }
+
+    // 末尾の改行で終わる場合は空の行を作らない
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        rows.push(row);
+    }
+
+    rows
+}
+
+/// 表の形をしたTSVなら行・列に分解して返す
+/// 2行以上あり、すべての行の列数が揃っていて、1列目がすべて空ではない場合のみ表とみなす
+/// （タブでインデントされたコードや、タブを含むだけの文章などを除外するため）
+pub fn parse_tsv(text: &str) -> Option<Vec<Vec<String>>> {
+    if !text.contains('\t') {
+        return None;
+    }
+
+    let rows = split_cells(text.trim_end_matches(['\r', '\n']));
+    if rows.len() < MIN_ROWS {
+        return None;
+    }
+    let columns = rows[0].len();
+
+    if columns < MIN_COLUMNS || rows.iter().any(|row| row.len() != columns) {
+        return None;
+    }
+
+    if rows.iter().all(|row| row[0].trim().is_empty()) {
+        return None;
+    }
+
+    Some(rows)
+}
+
+/// セルをTSVに組み立てる（改行・タブ・引用符を含むセルは引用符で囲む）
+pub fn to_tsv(rows: &[Vec<String>]) -> String {
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| {
+                    if cell.contains(['\t', '\n', '"']) {
+                        format!("\"{}\"", cell.replace('"', "\"\""))
+                    } else {
+                        cell.clone()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\t")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -1,26 +1,198 @@
 use eframe::egui::{self, FontData, FontDefinitions, FontFamily};
+use eframe::epaint::text::{FontInsert, FontPriority, InsertFontFamily};
 
-/// 日本語フォントのパス (Windows)
-const JAPANESE_FONT_PATH: &str = "C:\\Windows\\Fonts\\meiryo.ttc";
+use crate::config::{FontSetting, Theme, DEFAULT_FONT_SIZE};
+use crate::language::Script;
 
-/// 日本語フォントを設定する
-pub fn setup_japanese_fonts(ctx: &egui::Context) {
-    let mut fonts = FontDefinitions::default();
+/// 日本語フォントの候補 (Windows標準、優先順)
+/// 日本語以外のWindowsや軽量化されたインストールではメイリオがない場合がある
+const JAPANESE_FONT_PATHS: &[(&str, &str)] = &[
+    ("meiryo", "C:\\Windows\\Fonts\\meiryo.ttc"),
+    ("yugothic", "C:\\Windows\\Fonts\\YuGothM.ttc"),
+    ("msgothic", "C:\\Windows\\Fonts\\msgothic.ttc"),
+];
+
+/// システムに日本語フォントがない場合に使う埋め込みフォント（SIL Open Font License）
+const BUNDLED_JAPANESE_FONT: &[u8] = include_bytes!("../../assets/fonts/NotoSansJP-Regular.ttf");
+
+/// 使用する日本語フォント（システムのフォントを優先し、なければ埋め込みフォント）
+fn load_japanese_font() -> (&'static str, Vec<u8>) {
+    JAPANESE_FONT_PATHS
+        .iter()
+        .find_map(|(name, path)| std::fs::read(path).ok().map(|data| (*name, data)))
+        .unwrap_or_else(|| ("noto-sans-jp", BUNDLED_JAPANESE_FONT.to_vec()))
+}
+
+/// 文字体系ごとのシステムフォールバックフォント (Windows標準)
+const SYSTEM_FALLBACK_FONTS: &[(Script, &str, &str)] = &[
+    (Script::Han, "msyh", "C:\\Windows\\Fonts\\msyh.ttc"),
+    (Script::Korean, "malgun", "C:\\Windows\\Fonts\\malgun.ttf"),
+    (
+        Script::Thai,
+        "leelawadee",
+        "C:\\Windows\\Fonts\\LeelawUI.ttf",
+    ),
+    (Script::Arabic, "segoeui", "C:\\Windows\\Fonts\\segoeui.ttf"),
+];
+
+/// Windowsのアプリモードがダークかどうか（レジストリの AppsUseLightTheme が0ならダーク）
+fn system_uses_dark_theme() -> bool {
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize")
+        .and_then(|key| key.get_value::<u32, _>("AppsUseLightTheme"))
+        .map(|value| value == 0)
+        .unwrap_or(false)
+}
+
+/// 設定の配色をウィンドウに反映する
+pub fn apply_theme(ctx: &egui::Context, theme: Theme) {
+    let dark = match theme {
+        Theme::System => system_uses_dark_theme(),
+        Theme::Light => false,
+        Theme::Dark => true,
+    };
+    ctx.set_theme(if dark {
+        egui::Theme::Dark
+    } else {
+        egui::Theme::Light
+    });
+}
+
+/// 文字サイズを反映する（本文を font_size にし、他のテキストスタイルも同じ比率で拡大縮小する）
+pub fn apply_font_size(ctx: &egui::Context, font_size: f32) {
+    let scale = font_size / DEFAULT_FONT_SIZE;
+    let defaults = egui::Style::default().text_styles;
+
+    ctx.all_styles_mut(|style| {
+        for (text_style, font_id) in style.text_styles.iter_mut() {
+            if let Some(default) = defaults.get(text_style) {
+                font_id.size = default.size * scale;
+            }
+        }
+    });
+}
+
+/// アクセントカラーの明るさ（0.0〜1.0、sRGBの重み付けによる近似）
+fn luminance(color: egui::Color32) -> f32 {
+    (0.2126 * color.r() as f32 + 0.7152 * color.g() as f32 + 0.0722 * color.b() as f32) / 255.0
+}
+
+/// 背景とのコントラストが保てるよう、ダークテーマでは明るく・ライトテーマでは暗く補正する
+fn readable_accent(accent: egui::Color32, dark_mode: bool) -> egui::Color32 {
+    const MIN_DARK_LUMINANCE: f32 = 0.45;
+    const MAX_LIGHT_LUMINANCE: f32 = 0.4;
+
+    let lum = luminance(accent);
+    if dark_mode && lum < MIN_DARK_LUMINANCE {
+        let t = (MIN_DARK_LUMINANCE - lum) / (1.0 - lum).max(f32::EPSILON);
+        accent.lerp_to_gamma(egui::Color32::WHITE, t)
+    } else if !dark_mode && lum > MAX_LIGHT_LUMINANCE {
+        let t = (lum - MAX_LIGHT_LUMINANCE) / lum.max(f32::EPSILON);
+        accent.lerp_to_gamma(egui::Color32::BLACK, t)
+    } else {
+        accent
+    }
+}
+
+/// アクセントカラーをボタン・選択範囲・リンクの色に反映する（ライト・ダーク両方のテーマ）
+pub fn apply_accent_color(ctx: &egui::Context, accent: Option<[u8; 3]>) {
+    let Some([r, g, b]) = accent else {
+        return;
+    };
+    let base = egui::Color32::from_rgb(r, g, b);
 
-    if let Ok(font_data) = std::fs::read(JAPANESE_FONT_PATH) {
-        fonts.font_data.insert(
-            "meiryo".to_owned(),
-            FontData::from_owned(font_data).into(),
-        );
+    ctx.all_styles_mut(|style| {
+        let visuals = &mut style.visuals;
+        let accent = readable_accent(base, visuals.dark_mode);
+        let background = if visuals.dark_mode {
+            egui::Color32::BLACK
+        } else {
+            egui::Color32::WHITE
+        };
+
+        visuals.hyperlink_color = accent;
+        visuals.selection.bg_fill = accent.lerp_to_gamma(background, 0.5);
+        visuals.selection.stroke.color = accent;
+        visuals.widgets.hovered.bg_stroke.color = accent;
+        visuals.widgets.active.bg_fill = accent.lerp_to_gamma(background, 0.3);
+        visuals.widgets.active.bg_stroke.color = accent;
+    });
+}
 
-        if let Some(family) = fonts.families.get_mut(&FontFamily::Proportional) {
-            family.insert(0, "meiryo".to_owned());
+/// フォントをファミリーに登録する（既存フォントより前、指定位置に挿入）
+fn register_font(fonts: &mut FontDefinitions, name: &str, data: Vec<u8>, position: usize) {
+    fonts
+        .font_data
+        .insert(name.to_owned(), FontData::from_owned(data).into());
+
+    for family in [FontFamily::Proportional, FontFamily::Monospace] {
+        if let Some(list) = fonts.families.get_mut(&family) {
+            let index = position.min(list.len());
+            list.insert(index, name.to_owned());
         }
+    }
+}
+
+/// 日本語フォントと追加フォントを設定する
+/// 優先順位: 日本語フォント（メイリオ等） → 追加フォント（記載順） → eguiの既定フォント
+pub fn setup_fonts(ctx: &egui::Context, extra_fonts: &[FontSetting]) {
+    let mut fonts = FontDefinitions::default();
+
+    let (name, font_data) = load_japanese_font();
+    register_font(&mut fonts, name, font_data, 0);
+    let mut position = 1;
 
-        if let Some(family) = fonts.families.get_mut(&FontFamily::Monospace) {
-            family.insert(0, "meiryo".to_owned());
+    for setting in extra_fonts {
+        match std::fs::read(&setting.path) {
+            Ok(font_data) => {
+                let name = format!("extra:{}", setting.language);
+                register_font(&mut fonts, &name, font_data, position);
+                position += 1;
+            }
+            Err(e) => {
+                eprintln!(
+                    "Failed to load font for {} ({}): {}",
+                    setting.language, setting.path, e
+                );
+            }
         }
     }
 
     ctx.set_fonts(fonts);
 }
+
+/// テキストに含まれる文字体系をカバーするフォールバックフォントを追加する
+/// 主要な文字体系から順に、最低優先度のフォールバックとして登録する
+pub fn ensure_fonts_for(ctx: &egui::Context, text: &str) {
+    for script in crate::language::scripts_by_frequency(text) {
+        let Some((_, name, path)) = SYSTEM_FALLBACK_FONTS.iter().find(|(s, _, _)| *s == script)
+        else {
+            continue;
+        };
+
+        let loaded = ctx.fonts(|f| f.lock().fonts.definitions().font_data.contains_key(*name));
+        if loaded {
+            continue;
+        }
+
+        if let Ok(font_data) = std::fs::read(path) {
+            ctx.add_font(FontInsert::new(
+                name,
+                FontData::from_owned(font_data),
+                vec![
+                    InsertFontFamily {
+                        family: FontFamily::Proportional,
+                        priority: FontPriority::Lowest,
+                    },
+                    InsertFontFamily {
+                        family: FontFamily::Monospace,
+                        priority: FontPriority::Lowest,
+                    },
+                ],
+            ));
+        }
+    }
+}
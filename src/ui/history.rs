@@ -0,0 +1,155 @@
+use anyhow::Result;
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+use super::common::{
+    apply_accent_color, apply_font_size, apply_theme, ensure_fonts_for, setup_fonts,
+};
+use crate::history::HistoryEntry;
+
+/// 一覧に表示する原文の最大文字数
+const SOURCE_PREVIEW_CHARS: usize = 200;
+
+/// 「コピーしました」を表示する時間
+const COPIED_NOTICE_DURATION: Duration = Duration::from_secs(2);
+
+struct HistoryApp {
+    /// 新しい順の履歴
+    entries: Vec<HistoryEntry>,
+    search: String,
+    /// 最後にコピーした履歴の位置と時刻
+    copied: Option<(usize, Instant)>,
+}
+
+/// 記録時刻からの経過時間（例: "3分前"）
+fn elapsed_label(timestamp: u64) -> String {
+    let secs = crate::context_cache::now_secs().saturating_sub(timestamp);
+    match secs {
+        0..=59 => "たった今".to_string(),
+        60..=3599 => format!("{}分前", secs / 60),
+        3600..=86399 => format!("{}時間前", secs / 3600),
+        _ => format!("{}日前", secs / 86400),
+    }
+}
+
+/// 先頭の指定文字数だけを切り出す
+fn preview(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut preview: String = text.chars().take(max_chars).collect();
+    preview.push('…');
+    preview
+}
+
+impl HistoryApp {
+    fn matches(entry: &HistoryEntry, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || entry.source.to_lowercase().contains(&query)
+            || entry.result.to_lowercase().contains(&query)
+    }
+}
+
+impl eframe::App for HistoryApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("検索:");
+                ui.add(egui::TextEdit::singleline(&mut self.search).desired_width(300.0));
+            });
+            ui.add_space(10.0);
+
+            if self.entries.is_empty() {
+                ui.weak("翻訳履歴はまだありません");
+                return;
+            }
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (i, entry) in self.entries.iter().enumerate() {
+                    if !Self::matches(entry, &self.search) {
+                        continue;
+                    }
+
+                    ui.group(|ui| {
+                        ui.set_width(ui.available_width());
+                        ui.weak(format!("{} · {}", elapsed_label(entry.timestamp), entry.model));
+                        ui.add(
+                            egui::Label::new(
+                                egui::RichText::new(preview(&entry.source, SOURCE_PREVIEW_CHARS))
+                                    .weak(),
+                            )
+                            .wrap(),
+                        );
+                        ui.separator();
+                        ui.add(egui::Label::new(&entry.result).wrap().selectable(true));
+
+                        ui.horizontal(|ui| {
+                            if ui.button("コピー").clicked() {
+                                match crate::clipboard::set_text(&entry.result) {
+                                    Ok(_) => self.copied = Some((i, Instant::now())),
+                                    Err(e) => crate::notification::show_error(
+                                        "コピーエラー",
+                                        &e.to_string(),
+                                    ),
+                                }
+                            }
+
+                            if let Some((index, at)) = self.copied {
+                                let elapsed = at.elapsed();
+                                if index == i && elapsed < COPIED_NOTICE_DURATION {
+                                    ui.label("コピーしました");
+                                    ctx.request_repaint_after(COPIED_NOTICE_DURATION - elapsed);
+                                }
+                            }
+                        });
+                    });
+                    ui.add_space(5.0);
+                }
+            });
+        });
+
+        // Escキーで閉じる
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+    }
+}
+
+/// 翻訳履歴の一覧ウィンドウを表示する
+pub fn show_history_window() -> Result<()> {
+    let config = crate::config::load_or_create().unwrap_or_default();
+
+    let mut entries = crate::history::load();
+    entries.reverse();
+
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size([550.0, 600.0])
+            .with_resizable(true),
+        ..Default::default()
+    };
+
+    eframe::run_native(
+        "Clipboard Translator - 翻訳履歴",
+        options,
+        Box::new(|cc| {
+            setup_fonts(&cc.egui_ctx, &config.extra_fonts);
+            apply_theme(&cc.egui_ctx, config.theme);
+            apply_font_size(&cc.egui_ctx, config.font_size);
+            apply_accent_color(&cc.egui_ctx, config.accent_color);
+            for entry in &entries {
+                ensure_fonts_for(&cc.egui_ctx, &entry.source);
+                ensure_fonts_for(&cc.egui_ctx, &entry.result);
+            }
+            Ok(Box::new(HistoryApp {
+                entries,
+                search: String::new(),
+                copied: None,
+            }))
+        }),
+    )
+    .map_err(|e| anyhow::anyhow!("Failed to run history window: {}", e))?;
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
 pub mod common;
+pub mod history;
 pub mod result;
 pub mod setup;
@@ -2,86 +2,1077 @@ use anyhow::Result;
 use eframe::egui;
 use egui_commonmark::{CommonMarkCache, CommonMarkViewer};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{self, Receiver, TryRecvError};
-use std::sync::Arc;
-use windows::Win32::Foundation::POINT;
-use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use windows::core::HSTRING;
+use windows::Win32::Foundation::{HWND, POINT, RECT};
+use windows::Win32::Graphics::Gdi::{
+    GetMonitorInfoW, MonitorFromPoint, HMONITOR, MONITORINFO, MONITOR_DEFAULTTONEAREST,
+    MONITOR_DEFAULTTOPRIMARY,
+};
+use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetCursorPos, MessageBoxW, MB_ICONERROR, MB_ICONINFORMATION, MB_OK, MB_TOPMOST,
+};
 
-use super::common::setup_japanese_fonts;
+use super::common::{
+    apply_accent_color, apply_font_size, apply_theme, ensure_fonts_for, setup_fonts,
+};
+use crate::cancel::CancelToken;
+use crate::config::{Config, ErrorDisplay, OutputMode, Provider};
+use crate::diff::DiffSpan;
+use crate::gemini::{ContentBlocked, Translation};
+use crate::hotkey::lock_recovering;
+use crate::language::DetectedLanguage;
+use crate::TranslationEvent;
 
 enum ContentState {
+    /// 翻訳前に原文を編集中
+    Editing(String),
     Loading,
     Ready(String),
     Error(String),
+    /// 安全性フィルター・著作権保護によるブロック（カテゴリの一覧を表示する）
+    Blocked(ContentBlocked),
 }
 
 struct ResultApp {
     state: ContentState,
-    receiver: Option<Receiver<Result<String, String>>>,
+    receiver: Option<Receiver<TranslationEvent>>,
+    /// サーバー混雑で待機中のステータスコード・次の再試行時刻・中止フラグ
+    server_busy: Option<(u16, Instant, Arc<AtomicBool>)>,
+    /// 所要時間と試行回数（フッター表示用）
+    stats: Option<(Duration, u32)>,
     markdown_cache: CommonMarkCache,
     is_translating: Option<Arc<AtomicBool>>,
+    /// 折りたたみ表示する行数（0で無効）
+    collapse_after_lines: usize,
+    /// 「続きを表示」で全文を展開済みか
+    expanded: bool,
+    /// 「すべて中止」でウィンドウを閉じるためのトークン
+    cancel: CancelToken,
+    /// エラーの通知方法
+    error_display: ErrorDisplay,
+    /// 原文と設定（別のキーでの再試行用）
+    source_text: Option<String>,
+    config: Config,
+    /// 認証エラー・クォータ超過で、予備のキーがあるか
+    can_failover: bool,
+    /// マウスが乗るまでクリックを透過中か
+    click_through: bool,
+    /// 簡潔な翻訳を小さなツールチップで表示中か（クリックで詳細へ切り替え）
+    preview: bool,
+    /// 信頼度が設定値未満だった言語判定（原文の言語の確認用）
+    low_confidence: Option<DetectedLanguage>,
+    /// 再翻訳時に指定する原文の言語
+    pin_language: String,
+    /// 「コピー」を押した時刻（「コピーしました」の表示用）
+    copied_at: Option<Instant>,
+    /// 表示中の結果の出力モード（別のモードでの再翻訳ボタン用）
+    output_mode: OutputMode,
+    /// 原文と翻訳結果を左右に並べて表示中か
+    side_by_side: bool,
+    /// Markdownを整形せず、一部を選択してコピーできる元のテキストで表示中か
+    show_source: bool,
+    /// 再翻訳する前の結果（差分表示用）
+    previous_result: Option<String>,
+    /// 前の結果との差分を表示中か
+    show_diff: bool,
+    /// 求めた差分（表示の切り替え・結果の更新時に1回だけ求め、前の結果・表示中の結果が変わったら破棄する）
+    diff_spans: Option<Vec<DiffSpan>>,
+    /// 表として翻訳し、クリップボードへコピーした結果の行・列（結果を受け取ったときに1回だけ解析する）
+    copied_table: Option<Vec<Vec<String>>>,
+    /// 翻訳待ちになった時刻（経過時間の表示用）
+    loading_since: Option<Instant>,
+    /// 実行中の翻訳をキャンセルするフラグ（翻訳タスクから受け取る）
+    cancel_request: Option<Arc<AtomicBool>>,
+    /// 開いたままのウィンドウの場合、次の翻訳を受け取るチャネル
+    incoming: Option<Receiver<NextTranslation>>,
+    /// 最前面に表示しているか（ウィンドウ内のボタンで切り替えられる）
+    always_on_top: bool,
+    /// 表示先モニターの拡大率（最初のフレームで文字の拡大率を合わせたら None にする）
+    monitor_scale: Option<f32>,
 }
 
-impl eframe::App for ResultApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // 結果を受信チェック
-        if let Some(ref rx) = self.receiver {
-            match rx.try_recv() {
-                Ok(result) => {
-                    match result {
-                        Ok(content) => {
-                            self.state = ContentState::Ready(content);
-                            // 翻訳完了、フラグをクリア
-                            if let Some(ref flag) = self.is_translating {
-                                flag.store(false, Ordering::SeqCst);
-                            }
-                        }
-                        Err(e) => {
-                            // トースト通知でもエラーを表示
-                            crate::notification::show_error("API エラー", &e);
-                            self.state = ContentState::Error(e);
-                            // エラーでもフラグをクリア
-                            if let Some(ref flag) = self.is_translating {
-                                flag.store(false, Ordering::SeqCst);
-                            }
-                        }
+/// 開いたままのウィンドウに表示する次の翻訳
+struct NextTranslation {
+    receiver: Receiver<TranslationEvent>,
+    config: Config,
+    is_translating: Option<Arc<AtomicBool>>,
+    source_text: String,
+}
+
+/// 開いたままの結果ウィンドウへの送信口（ウィンドウがない場合はNone）
+static PINNED_WINDOW: Mutex<Option<Sender<NextTranslation>>> = Mutex::new(None);
+
+/// 「すべて中止」を検知するための再描画間隔
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// これ以上応答がない場合は、通信が止まっている可能性を知らせる
+const SLOW_RESPONSE_HINT_AFTER: Duration = Duration::from_secs(15);
+
+/// クリック透過中にカーソル位置を確認する間隔
+const HOVER_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// これより長い空白なしの文字列を含む場合は、Markdownではなく折り返し可能なテキストとして表示する
+const MAX_UNBROKEN_RUN: usize = 200;
+
+/// 「コピーしました」を表示する時間
+const COPIED_NOTICE_DURATION: Duration = Duration::from_secs(2);
+
+/// ツールチップ表示（1段目）のウィンドウサイズ
+const PREVIEW_SIZE: [f32; 2] = [340.0, 140.0];
+
+/// 詳細表示のウィンドウサイズ
+const DETAIL_SIZE: [f32; 2] = [500.0, 400.0];
+
+/// 結果を表示する（長い1行がウィンドウを横に広げないよう、幅を固定して折り返す）
+/// plain_text_threshold 文字を超える結果はMarkdownとして解析せず、選択可能なプレーンテキストで表示する（0で無効）
+fn show_content(
+    ui: &mut egui::Ui,
+    cache: &mut CommonMarkCache,
+    content: &str,
+    table: Option<&[Vec<String>]>,
+    plain_text_threshold: usize,
+    render_markdown: bool,
+) {
+    ui.set_max_width(ui.available_width());
+
+    // 表（TSV）として翻訳した結果は行・列を揃えて表示する
+    if let Some(rows) = table {
+        ui.weak("翻訳した表はクリップボードにコピー済みです（表計算ソフトに貼り付けできます）");
+        egui::Grid::new("translated_table")
+            .striped(true)
+            .show(ui, |ui| {
+                for row in rows {
+                    for cell in row {
+                        ui.label(cell);
                     }
-                    self.receiver = None;
+                    ui.end_row();
                 }
-                Err(TryRecvError::Empty) => {
-                    // まだ結果がない、再描画を要求
-                    ctx.request_repaint();
+            });
+        return;
+    }
+
+    // Markdownで表示しない設定の場合は、任意の範囲を選択してコピーできる読み取り専用の欄に表示する
+    if !render_markdown {
+        let mut text = content;
+        ui.add(
+            egui::TextEdit::multiline(&mut text)
+                .desired_width(f32::INFINITY)
+                .frame(false),
+        );
+        return;
+    }
+
+    let too_large = plain_text_threshold > 0 && content.chars().count() > plain_text_threshold;
+
+    if too_large || crate::content_kind::longest_unbroken_run(content) > MAX_UNBROKEN_RUN {
+        // コードブロック等は折り返されないため、プレーンテキストとして任意の位置で折り返す
+        ui.add(egui::Label::new(content).wrap().selectable(true));
+    } else {
+        CommonMarkViewer::new().show(ui, cache, content);
+    }
+}
+
+/// 前の結果との差分を、追加は緑の背景、削除は赤の取り消し線で表示する
+fn show_diff(ui: &mut egui::Ui, spans: &[DiffSpan]) {
+    let font_id = egui::TextStyle::Body.resolve(ui.style());
+    let text_color = ui.visuals().text_color();
+    let added = egui::Color32::from_rgba_unmultiplied(0, 160, 0, 70);
+    let removed = egui::Color32::from_rgb(200, 60, 60);
+
+    let mut job = egui::text::LayoutJob::default();
+    for span in spans {
+        let (text, format) = match span {
+            DiffSpan::Same(text) => (text, egui::TextFormat::simple(font_id.clone(), text_color)),
+            DiffSpan::Added(text) => (
+                text,
+                egui::TextFormat {
+                    background: added,
+                    ..egui::TextFormat::simple(font_id.clone(), text_color)
+                },
+            ),
+            DiffSpan::Removed(text) => (
+                text,
+                egui::TextFormat {
+                    strikethrough: egui::Stroke::new(1.0, removed),
+                    ..egui::TextFormat::simple(font_id.clone(), removed)
+                },
+            ),
+        };
+        job.append(text, 0.0, format);
+    }
+
+    ui.add(egui::Label::new(job).wrap().selectable(true));
+}
+
+/// 先頭の指定行数だけを切り出す（行数が収まる場合はNone）
+fn truncate_lines(content: &str, max_lines: usize) -> Option<String> {
+    if max_lines == 0 || content.lines().count() <= max_lines {
+        return None;
+    }
+
+    Some(content.lines().take(max_lines).collect::<Vec<_>>().join("\n"))
+}
+
+impl ResultApp {
+    /// 翻訳中フラグをクリア
+    fn clear_translating_flag(&self) {
+        if let Some(ref flag) = self.is_translating {
+            flag.store(false, Ordering::SeqCst);
+        }
+    }
+
+    /// 開いたままのウィンドウに次の翻訳が届いていれば、表示中の内容を置き換える
+    fn poll_incoming(&mut self, ctx: &egui::Context) {
+        // 連続して届いた場合は最新のものだけを表示する
+        let Some(next) = self.incoming.as_ref().and_then(|rx| rx.try_iter().last()) else {
+            return;
+        };
+
+        // 置き換えられる翻訳はもう表示しないため中止する
+        if let Some(flag) = self.cancel_request.take() {
+            flag.store(true, Ordering::SeqCst);
+        }
+
+        self.receiver = Some(next.receiver);
+        self.source_text = Some(next.source_text);
+        // 別の原文のため、前の結果との差分は意味がない
+        self.previous_result = None;
+        self.show_diff = false;
+        self.diff_spans = None;
+        self.copied_table = None;
+        self.is_translating = next.is_translating;
+        self.output_mode = next.config.output_mode;
+        self.collapse_after_lines = next.config.collapse_after_lines;
+        self.error_display = next.config.error_display;
+        self.config = next.config;
+        self.state = ContentState::Loading;
+        self.server_busy = None;
+        self.stats = None;
+        self.expanded = false;
+        self.can_failover = false;
+        self.low_confidence = None;
+        self.copied_at = None;
+
+        if self.config.focus_result_window {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        }
+    }
+
+    /// 次の翻訳の受け付けをやめる（閉じる前に呼ぶ）
+    /// 受け付けたまま表示できなかった翻訳があれば、次のホットキーを妨げないようフラグを戻す
+    fn release_pinned(&mut self) {
+        let Some(incoming) = self.incoming.take() else {
+            return;
+        };
+
+        *lock_recovering(&PINNED_WINDOW) = None;
+        for next in incoming.try_iter() {
+            if let Some(flag) = next.is_translating {
+                flag.store(false, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// 実行中の翻訳を中止してウィンドウを閉じる（次のホットキーをすぐ使えるようフラグも戻す）
+    fn cancel_translation(&mut self, ctx: &egui::Context) {
+        if let Some(flag) = self.cancel_request.take() {
+            flag.store(true, Ordering::SeqCst);
+        }
+        self.receiver = None;
+        self.clear_translating_flag();
+        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+    }
+
+    /// 受信済みのメッセージをすべて取り出して状態に反映する
+    /// 1フレームに1件ずつ処理すると描画が受信に追いつかないため、溜まった分をまとめて処理し、
+    /// Markdownの再描画はフレームごとに最新の内容で1回だけ行う
+    fn poll_receiver(&mut self, ctx: &egui::Context) {
+        let Some(rx) = self.receiver.take() else {
+            return;
+        };
+
+        let mut latest: Option<Result<Translation, String>> = None;
+        let mut blocked: Option<ContentBlocked> = None;
+        let mut disconnected = false;
+        // このフレームで届いた途中経過（フォントの確認はフレームごとに1回だけ行う）
+        let mut received = String::new();
+
+        loop {
+            match rx.try_recv() {
+                Ok(TranslationEvent::Finished(result)) => latest = Some(result),
+                Ok(TranslationEvent::Blocked(info)) => blocked = Some(info),
+                Ok(TranslationEvent::Started { cancel }) => self.cancel_request = Some(cancel),
+                Ok(TranslationEvent::ServerBusy {
+                    status,
+                    retry_at,
+                    stop,
+                }) => self.server_busy = Some((status, retry_at, stop)),
+                Ok(TranslationEvent::Partial(chunk)) => {
+                    // 届いた分から表示し、完了時に後処理済みの全文で置き換える
+                    received.push_str(&chunk);
+                    self.server_busy = None;
+                    self.diff_spans = None;
+                    self.copied_table = None;
+                    match self.state {
+                        ContentState::Ready(ref mut content) => content.push_str(&chunk),
+                        _ => self.state = ContentState::Ready(chunk),
+                    }
                 }
+                Err(TryRecvError::Empty) => break,
                 Err(TryRecvError::Disconnected) => {
-                    self.state = ContentState::Error("接続が切断されました".to_string());
-                    self.receiver = None;
-                    // エラーでもフラグをクリア
-                    if let Some(ref flag) = self.is_translating {
-                        flag.store(false, Ordering::SeqCst);
-                    }
+                    disconnected = true;
+                    break;
                 }
             }
         }
 
+        // 追加したフォントは次のフレームから有効になるため、ループ内で確認すると
+        // 同じフォントを何度も読み込んでしまう（最終結果が届いた場合はその全文で確認する）
+        if !received.is_empty() && !matches!(latest, Some(Ok(_))) {
+            ensure_fonts_for(ctx, &received);
+        }
+
+        let finished = latest.is_some() || blocked.is_some();
+        if finished {
+            self.server_busy = None;
+        }
+
+        if let Some(info) = blocked {
+            if self.error_display.shows_toast() {
+                crate::notification::show_error("ブロックされました", &info.to_string());
+            }
+            self.state = ContentState::Blocked(info);
+            self.clear_translating_flag();
+            if !self.error_display.shows_window() {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+        }
+
+        match latest {
+            Some(Ok(translation)) => {
+                ensure_fonts_for(ctx, &translation.content);
+                self.stats = (translation.attempts > 0)
+                    .then_some((translation.elapsed, translation.attempts));
+                self.low_confidence = translation
+                    .detected
+                    .clone()
+                    .filter(|d| {
+                        self.source_text.is_some()
+                            && d.confidence < self.config.min_detection_confidence
+                    });
+                if let Some(ref detected) = self.low_confidence {
+                    self.pin_language = detected.name.clone();
+                }
+                self.copy_automatically(&translation.content);
+                self.send_to_target_app(&translation.content);
+                self.update_title(ctx, &translation);
+                // 前の結果の画像・スクロール状態がウィンドウを使い回すたびに溜まらないよう、
+                // 新しい結果ごとにMarkdownのキャッシュを作り直す
+                self.markdown_cache = CommonMarkCache::default();
+                self.copied_table = translation
+                    .table_copied
+                    .then(|| crate::table::parse_tsv(&translation.content))
+                    .flatten();
+                self.state = ContentState::Ready(translation.content);
+                self.diff_spans = None;
+                self.record_history();
+                self.expanded = false;
+                // 翻訳完了、フラグをクリア
+                self.clear_translating_flag();
+            }
+            Some(Err(e)) => {
+                self.can_failover = self.source_text.is_some()
+                    && crate::gemini::is_key_failover_error(&e)
+                    && !crate::credential::load_backup_api_keys(self.config.provider).is_empty();
+                if self.error_display.shows_toast() {
+                    crate::notification::show_error("API エラー", &e);
+                }
+                self.state = ContentState::Error(e);
+                // エラーでもフラグをクリア
+                self.clear_translating_flag();
+                // トーストのみの場合はウィンドウを閉じる
+                if !self.error_display.shows_window() && !self.can_failover {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+            }
+            None => {}
+        }
+
+        // 最終結果を受け取った後は受信を終える（送信側の終了は切断として扱わない）
+        if finished {
+            return;
+        }
+
+        if disconnected {
+            // 結果を受け取る前に送信側が終了した場合はエラー
+            if matches!(self.state, ContentState::Loading) {
+                self.state = ContentState::Error("接続が切断されました".to_string());
+            }
+            // 途中まで表示した後に終了した場合も、翻訳中のままにせず次のホットキーを受け付ける
+            self.clear_translating_flag();
+        } else {
+            self.receiver = Some(rx);
+            // まだ続きがある可能性があるため再描画を要求
+            ctx.request_repaint();
+        }
+    }
+
+    /// 最初のフレームで、文字の拡大率を表示先モニターの拡大率に合わせる
+    /// ウィンドウが報告する拡大率と一致していれば何もしない
+    fn apply_monitor_scale(&mut self, ctx: &egui::Context) {
+        let Some(scale) = self.monitor_scale.take() else {
+            return;
+        };
+        if ctx
+            .native_pixels_per_point()
+            .is_some_and(|native| (native - scale).abs() > f32::EPSILON)
+        {
+            ctx.set_pixels_per_point(scale);
+        }
+    }
+
+    /// ウィンドウタイトルに原文と翻訳先の言語を表示する（例: "EN→JA 翻訳結果"）
+    /// 言語が判定できない場合は既定のタイトルのまま
+    fn update_title(&self, ctx: &egui::Context, translation: &Translation) {
+        if !self.config.title_shows_languages {
+            return;
+        }
+
+        let source = crate::language::resolve_source_language(
+            translation.detected.as_ref(),
+            &translation.content,
+            self.source_text.as_deref().unwrap_or_default(),
+        );
+        let Some(source) = source else {
+            return;
+        };
+
+        let source = self
+            .config
+            .source_language_hint()
+            .map(str::to_string)
+            .unwrap_or(source);
+        // DeepLは翻訳方向を自動で切り替えず、常に設定した言語コードへ翻訳する
+        let target = if self.config.provider == Provider::DeepL {
+            self.config.deepl_target_lang.to_uppercase()
+        } else {
+            crate::language::language_code(&crate::language::expected_target(
+                &source,
+                &self.config.target_language,
+                self.config.language_pair.as_ref(),
+            ))
+        };
+        ctx.send_viewport_cmd(egui::ViewportCommand::Title(format!(
+            "{}→{} 翻訳結果",
+            crate::language::language_code(&source),
+            target
+        )));
+    }
+
+    /// 表示した翻訳結果を履歴に記録する（原文がわからない結果は記録しない）
+    fn record_history(&self) {
+        let (Some(source), ContentState::Ready(result)) = (&self.source_text, &self.state) else {
+            return;
+        };
+
+        crate::history::append(
+            source,
+            result,
+            self.config.active_model(),
+            self.config.history_limit,
+        );
+    }
+
+    /// 設定に応じて、翻訳結果を自動でクリップボードにコピーする
+    fn copy_automatically(&self, content: &str) {
+        if !self.config.auto_copy {
+            return;
+        }
+
+        if let Err(e) = crate::clipboard::replace_text(content, self.config.clipboard_backend) {
+            eprintln!("Failed to copy translation: {}", e);
+        }
+    }
+
+    /// 送信先アプリが設定されていれば、翻訳結果を貼り付ける（別スレッド）
+    fn send_to_target_app(&self, content: &str) {
+        let Some(target) = self.config.send_to_app.clone() else {
+            return;
+        };
+
+        let content = content.to_string();
+        std::thread::spawn(move || {
+            if let Err(e) = crate::send_to_app::send(&target, &content) {
+                eprintln!("Failed to send result to app: {}", e);
+                crate::notification::show_error("送信エラー", &e.to_string());
+            }
+        });
+    }
+
+    /// カーソルがウィンドウ上に来たらクリック透過を解除する
+    /// 透過中はウィンドウがマウスイベントを受け取らないため、カーソル位置を直接確認する
+    fn update_click_through(&mut self, ctx: &egui::Context) {
+        if !self.click_through {
+            return;
+        }
+
+        let (outer_rect, pixels_per_point) = ctx.input(|i| {
+            (
+                i.viewport().outer_rect,
+                i.viewport().native_pixels_per_point.unwrap_or(1.0),
+            )
+        });
+
+        let (cursor_x, cursor_y) = get_cursor_position();
+        let cursor = egui::pos2(cursor_x / pixels_per_point, cursor_y / pixels_per_point);
+
+        if outer_rect.is_some_and(|rect| rect.contains(cursor)) {
+            self.click_through = false;
+            ctx.send_viewport_cmd(egui::ViewportCommand::MousePassthrough(false));
+        } else {
+            ctx.request_repaint_after(HOVER_CHECK_INTERVAL);
+        }
+    }
+
+    /// ツールチップ表示から詳細表示へ切り替え、詳細な翻訳を取得する
+    fn expand_to_detail(&mut self, ctx: &egui::Context) {
+        let Some(text) = self.source_text.clone() else {
+            return;
+        };
+
+        self.preview = false;
+
+        // 簡潔モードを選んでいる場合も、詳細表示では詳細モードで取得する
+        let mut config = self.config.clone();
+        if config.output_mode == OutputMode::Concise && config.provider.supports_explanation() {
+            config.output_mode = OutputMode::Detailed;
+        }
+
+        self.output_mode = config.output_mode;
+        self.receiver = Some(crate::spawn_translation_task(text, config));
+        self.state = ContentState::Loading;
+        self.stats = None;
+        if let Some(ref flag) = self.is_translating {
+            flag.store(true, Ordering::SeqCst);
+        }
+
+        ctx.send_viewport_cmd(egui::ViewportCommand::Decorations(true));
+        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(DETAIL_SIZE.into()));
+        // 大きくなった分が画面外へはみ出さないよう、位置を作業領域の内側へ寄せる
+        // 作業領域は物理ピクセルなので、このウィンドウの拡大率で換算する
+        if let Some(rect) = ctx.input(|i| i.viewport().outer_rect) {
+            let pixels_per_point = ctx.pixels_per_point();
+            let [x, y] = clamp_to_work_area(
+                [rect.min.x * pixels_per_point, rect.min.y * pixels_per_point],
+                DETAIL_SIZE,
+                true,
+            );
+            let position = egui::pos2(x / pixels_per_point, y / pixels_per_point);
+            ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(position));
+        }
+        ctx.send_viewport_cmd(egui::ViewportCommand::WindowLevel(window_level(
+            self.always_on_top,
+        )));
+    }
+
+    /// 最前面表示を切り替える（ウィンドウを開き直さずに反映する）
+    fn toggle_always_on_top(&mut self, ctx: &egui::Context) {
+        self.always_on_top = !self.always_on_top;
+        ctx.send_viewport_cmd(egui::ViewportCommand::WindowLevel(window_level(
+            self.always_on_top,
+        )));
+    }
+
+    /// 同じ原文を翻訳し直す前に、表示中の結果を差分表示用に残す
+    fn remember_previous_result(&mut self) {
+        if let ContentState::Ready(ref content) = self.state {
+            self.previous_result = Some(content.clone());
+        }
+        self.show_diff = false;
+        self.diff_spans = None;
+    }
+
+    /// 差分の表示中で、まだ求めていなければ前の結果との差分を求める（受信中は求めない）
+    /// 差分の表は大きくなりうるため、再描画のたびには求め直さない
+    fn update_diff_spans(&mut self) {
+        if !self.show_diff || self.diff_spans.is_some() || self.receiver.is_some() {
+            return;
+        }
+        if let (Some(previous), ContentState::Ready(content)) = (&self.previous_result, &self.state)
+        {
+            self.diff_spans = Some(crate::diff::diff_words(previous, content));
+        }
+    }
+
+    /// 原文の言語を指定して翻訳し直す
+    fn retranslate_with_source_language(&mut self) {
+        let Some(text) = self.source_text.clone() else {
+            return;
+        };
+        let language = self.pin_language.trim();
+        if language.is_empty() {
+            return;
+        }
+
+        self.config.pinned_source_language = Some(language.to_string());
+        self.remember_previous_result();
+
+        self.receiver = Some(crate::spawn_translation_task(text, self.config.clone()));
+        self.state = ContentState::Loading;
+        self.low_confidence = None;
+        if let Some(ref flag) = self.is_translating {
+            flag.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// 同じ原文を別の出力モードで翻訳し直す（ウィンドウは開いたまま）
+    fn retranslate_with_mode(&mut self, mode: OutputMode) {
+        let Some(text) = self.source_text.clone() else {
+            return;
+        };
+        self.remember_previous_result();
+
+        let config = Config {
+            output_mode: mode,
+            ..self.config.clone()
+        };
+
+        self.output_mode = mode;
+        self.receiver = Some(crate::spawn_translation_task(text, config));
+        self.state = ContentState::Loading;
+        self.stats = None;
+        if let Some(ref flag) = self.is_translating {
+            flag.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// 編集した原文で翻訳を開始する
+    fn translate_edited(&mut self) {
+        let ContentState::Editing(ref text) = self.state else {
+            return;
+        };
+        if text.trim().is_empty() {
+            return;
+        }
+        let text = text.clone();
+
+        self.receiver = Some(crate::spawn_translation_task(
+            text.clone(),
+            self.config.clone(),
+        ));
+        self.source_text = Some(text);
+        self.state = ContentState::Loading;
+        if let Some(ref flag) = self.is_translating {
+            flag.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// 次の予備のキーに切り替えて同じ原文を翻訳し直す
+    fn retry_with_next_key(&mut self) {
+        let Some(text) = self.source_text.clone() else {
+            return;
+        };
+
+        match crate::credential::rotate_api_key(self.config.provider) {
+            Ok(Some(next_key)) => {
+                self.config.api_key = next_key;
+                self.receiver = Some(crate::spawn_translation_task(text, self.config.clone()));
+                self.state = ContentState::Loading;
+                self.can_failover = false;
+                if let Some(ref flag) = self.is_translating {
+                    flag.store(true, Ordering::SeqCst);
+                }
+            }
+            Ok(None) => {
+                self.can_failover = false;
+                self.state = ContentState::Error("予備のAPIキーがありません".to_string());
+            }
+            Err(e) => {
+                self.can_failover = false;
+                self.state = ContentState::Error(format!("APIキーの切り替えに失敗: {}", e));
+            }
+        }
+    }
+}
+
+impl eframe::App for ResultApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // 「すべて中止」された場合はウィンドウを閉じる
+        if self.cancel.is_cancelled() {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            return;
+        }
+        ctx.request_repaint_after(CANCEL_CHECK_INTERVAL);
+
+        self.update_click_through(ctx);
+        self.apply_monitor_scale(ctx);
+
+        // 開いたままのウィンドウでは、次の翻訳が届いていないか確認する
+        self.poll_incoming(ctx);
+
+        // 結果を受信チェック
+        self.poll_receiver(ctx);
+        self.update_diff_spans();
+
+        // 翻訳待ちに入った時刻を記録し、経過秒数の表示を毎秒更新する
+        if matches!(self.state, ContentState::Loading) {
+            self.loading_since.get_or_insert_with(Instant::now);
+            ctx.request_repaint_after(Duration::from_secs(1));
+        } else {
+            self.loading_since = None;
+        }
+
+        let mut retry_requested = false;
+        let mut detail_requested = false;
+        let mut pin_requested = false;
+        let mut always_on_top_requested = false;
+        let mut translate_requested = false;
+        let mut cancel_requested = false;
+        let mut mode_requested: Option<OutputMode> = None;
+
         egui::CentralPanel::default().show(ctx, |ui| {
+            // 翻訳前：取得した原文を編集して「翻訳」で確定する
+            if let ContentState::Editing(ref mut text) = self.state {
+                ui.label("原文を確認・編集してください（Ctrl+Enterで翻訳）");
+                ui.add_space(5.0);
+                egui::ScrollArea::vertical()
+                    .max_height(ui.available_height() - 40.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(text)
+                                .desired_width(f32::INFINITY)
+                                .desired_rows(12),
+                        );
+                    });
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    if ui.button("翻訳").clicked()
+                        || ui.input(|i| i.modifiers.ctrl && i.key_pressed(egui::Key::Enter))
+                    {
+                        translate_requested = true;
+                    }
+                    if ui.button("閉じる").clicked() {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+
+                    // 送信する文字数（上限を超える場合は警告色）
+                    let count = text.chars().count();
+                    let max_chars = self.config.max_chars;
+                    if max_chars > 0 && count > max_chars {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            format!("{} 文字（上限 {} 文字を超えています）", count, max_chars),
+                        );
+                    } else {
+                        ui.label(format!("{} 文字", count));
+                    }
+                });
+                return;
+            }
+
             match &self.state {
+                // 上で表示済み
+                ContentState::Editing(_) => {}
                 ContentState::Loading => {
                     ui.vertical_centered(|ui| {
-                        ui.add_space(150.0);
+                        ui.add_space(if self.preview { 30.0 } else { 150.0 });
                         ui.spinner();
                         ui.add_space(10.0);
-                        ui.label("翻訳中...");
+                        let elapsed = self.loading_since.map(|t| t.elapsed()).unwrap_or_default();
+                        ui.label(format!("翻訳中... {}秒", elapsed.as_secs()));
+
+                        // 混雑の待機中は下に理由を表示するため、ここでは知らせない
+                        if elapsed >= SLOW_RESPONSE_HINT_AFTER && self.server_busy.is_none() {
+                            ui.weak("まだ応答がありません（通信が遅いか、止まっている可能性があります）");
+                        }
+
+                        if self.cancel_request.is_some() {
+                            ui.add_space(10.0);
+                            if ui.button("キャンセル").clicked() {
+                                cancel_requested = true;
+                            }
+                        }
+
+                        // 混雑中は自動で再試行していることと、次の再試行までの時間を示す
+                        if let Some((status, retry_at, ref stop)) = self.server_busy {
+                            let remaining = retry_at.saturating_duration_since(Instant::now());
+                            ui.add_space(10.0);
+                            ui.colored_label(
+                                egui::Color32::from_rgb(200, 140, 0),
+                                "サーバー混雑中、待機しています",
+                            );
+                            ui.weak(format!(
+                                "（{}）次の再試行まで約{}秒",
+                                status,
+                                remaining.as_secs_f32().ceil() as u64
+                            ));
+                            if ui.button("今すぐ中止").clicked() {
+                                stop.store(true, Ordering::SeqCst);
+                            }
+                        }
                     });
                 }
-                ContentState::Ready(content) => {
+                ContentState::Ready(content) if self.preview => {
+                    // 1段目：簡潔な翻訳のみを表示し、クリックで詳細へ
                     egui::ScrollArea::vertical()
-                        .max_height(550.0)
+                        .max_height(PREVIEW_SIZE[1] - 40.0)
                         .show(ui, |ui| {
-                            CommonMarkViewer::new().show(ui, &mut self.markdown_cache, content);
+                            show_content(
+                                ui,
+                                &mut self.markdown_cache,
+                                content,
+                                self.copied_table.as_deref(),
+                                self.config.plain_text_threshold,
+                                self.config.render_markdown,
+                            );
+                        });
+
+                    if ui.link("クリックで詳細を表示").clicked() {
+                        detail_requested = true;
+                    }
+
+                    let background = ui.interact(
+                        ui.min_rect(),
+                        ui.id().with("preview_click"),
+                        egui::Sense::click(),
+                    );
+                    if background.clicked() {
+                        detail_requested = true;
+                    }
+                }
+                ContentState::Ready(content) => {
+                    let truncated = if self.expanded {
+                        None
+                    } else {
+                        truncate_lines(content, self.collapse_after_lines)
+                    };
+
+                    // 言語判定の信頼度が低い場合は原文の言語を確認する
+                    if let Some(ref detected) = self.low_confidence {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            format!(
+                                "原文の言語の判定に自信がありません（{}・信頼度{}%）",
+                                detected.name, detected.confidence
+                            ),
+                        );
+                        ui.horizontal(|ui| {
+                            ui.label("原文の言語:");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.pin_language)
+                                    .desired_width(120.0),
+                            );
+                            if ui.button("この言語として再翻訳").clicked() {
+                                pin_requested = true;
+                            }
                         });
+                        ui.add_space(5.0);
+                    }
+
+                    let visible = truncated.as_deref().unwrap_or(content);
+                    match self.source_text {
+                        // 再翻訳前の結果との差分（全文で比較する）
+                        _ if self.show_diff && self.diff_spans.is_some() => {
+                            let spans = self.diff_spans.as_deref().unwrap_or_default();
+                            egui::ScrollArea::vertical()
+                                .max_height(550.0)
+                                .show(ui, |ui| show_diff(ui, spans));
+                        }
+                        // 左に原文、右に翻訳結果を並べる（それぞれ独立してスクロール）
+                        Some(ref source) if self.side_by_side => {
+                            ui.columns(2, |columns| {
+                                egui::ScrollArea::vertical()
+                                    .id_salt("source_column")
+                                    .max_height(550.0)
+                                    .show(&mut columns[0], |ui| {
+                                        ui.add(egui::Label::new(source.as_str()).selectable(true));
+                                    });
+                                egui::ScrollArea::vertical()
+                                    .id_salt("result_column")
+                                    .max_height(550.0)
+                                    .show(&mut columns[1], |ui| {
+                                        show_content(
+                                            ui,
+                                            &mut self.markdown_cache,
+                                            visible,
+                                            self.copied_table.as_deref(),
+                                            self.config.plain_text_threshold,
+                                            self.config.render_markdown && !self.show_source,
+                                        );
+                                    });
+                            });
+                        }
+                        _ => {
+                            egui::ScrollArea::vertical()
+                                .max_height(550.0)
+                                .show(ui, |ui| {
+                                    show_content(
+                                        ui,
+                                        &mut self.markdown_cache,
+                                        visible,
+                                        self.copied_table.as_deref(),
+                                        self.config.plain_text_threshold,
+                                        self.config.render_markdown && !self.show_source,
+                                    );
+                                });
+                        }
+                    }
+
+                    if truncated.is_some() && ui.button("続きを表示").clicked() {
+                        self.expanded = true;
+                    }
 
                     ui.add_space(10.0);
 
+                    // 設定を開かずに別の出力モードで翻訳し直す（DeepLは簡潔モードのみ）
+                    if self.source_text.is_some() && self.config.provider.supports_explanation() {
+                        ui.horizontal(|ui| {
+                            for &mode in OutputMode::all() {
+                                if mode != self.output_mode
+                                    && ui
+                                        .button(format!("{}で再翻訳", mode.short_label()))
+                                        .clicked()
+                                {
+                                    mode_requested = Some(mode);
+                                }
+                            }
+                        });
+                    }
+
+                    ui.horizontal(|ui| {
+                        if ui.button("コピー").clicked() {
+                            // 表示上の折りたたみに関わらず全文（Markdownのまま）をコピー
+                            match crate::clipboard::set_text(content) {
+                                Ok(_) => self.copied_at = Some(Instant::now()),
+                                Err(e) => {
+                                    crate::notification::show_error("コピーエラー", &e.to_string())
+                                }
+                            }
+                        }
+
+                        // 自動コピーで上書きしたクリップボードの内容を戻す
+                        if self.config.auto_copy
+                            && crate::clipboard::can_restore()
+                            && ui
+                                .button("元に戻す")
+                                .on_hover_text("自動コピーする前のクリップボードの内容に戻す")
+                                .clicked()
+                        {
+                            if let Err(e) = crate::clipboard::restore_replaced() {
+                                crate::notification::show_error("コピーエラー", &e.to_string());
+                            }
+                        }
+
+                        if ui.button("閉じる").clicked() {
+                            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                        }
+
+                        if self.source_text.is_some() {
+                            let label = if self.side_by_side {
+                                "縦に表示"
+                            } else {
+                                "原文と並べて表示"
+                            };
+                            if ui.button(label).clicked() {
+                                self.side_by_side = !self.side_by_side;
+                            }
+                        }
+
+                        // 整形済みのMarkdownは文の途中から選択できないため、元のテキストに切り替えられるようにする
+                        if self.config.render_markdown {
+                            let label = if self.show_source {
+                                "整形して表示"
+                            } else {
+                                "ソース表示"
+                            };
+                            if ui.button(label).clicked() {
+                                self.show_source = !self.show_source;
+                            }
+                        }
+
+                        let label = if self.always_on_top {
+                            "最前面を解除"
+                        } else {
+                            "最前面に固定"
+                        };
+                        if ui.button(label).clicked() {
+                            always_on_top_requested = true;
+                        }
+
+                        if self.previous_result.is_some() {
+                            let label = if self.show_diff {
+                                "差分を隠す"
+                            } else {
+                                "前の結果との差分"
+                            };
+                            if ui.button(label).clicked() {
+                                self.show_diff = !self.show_diff;
+                            }
+                        }
+
+                        if let Some(copied_at) = self.copied_at {
+                            let elapsed = copied_at.elapsed();
+                            if elapsed < COPIED_NOTICE_DURATION {
+                                ui.label("コピーしました");
+                                ctx.request_repaint_after(COPIED_NOTICE_DURATION - elapsed);
+                            } else {
+                                self.copied_at = None;
+                            }
+                        }
+
+                        // フッター：所要時間と試行回数
+                        if let Some((elapsed, attempts)) = self.stats {
+                            let footer = if attempts > 1 {
+                                format!("{:.1}秒（{}回試行）", elapsed.as_secs_f32(), attempts)
+                            } else {
+                                format!("{:.1}秒", elapsed.as_secs_f32())
+                            };
+                            ui.weak(footer);
+                        }
+                    });
+                }
+                ContentState::Blocked(info) => {
+                    let title = if info.prompt_blocked {
+                        "入力テキストがブロックされました"
+                    } else if info.is_recitation() {
+                        "著作権保護により応答が制限されました"
+                    } else {
+                        "安全性フィルターにより応答がブロックされました"
+                    };
+                    ui.heading(egui::RichText::new(title).color(egui::Color32::RED));
+                    ui.label(format!("理由: {}", info.reason));
+                    ui.add_space(10.0);
+
+                    if info.ratings.is_empty() {
+                        ui.weak("カテゴリごとの評価は返されませんでした");
+                    } else {
+                        egui::Grid::new("safety_ratings")
+                            .striped(true)
+                            .num_columns(3)
+                            .show(ui, |ui| {
+                                ui.strong("カテゴリ");
+                                ui.strong("可能性");
+                                ui.strong("ブロック");
+                                ui.end_row();
+
+                                for rating in &info.ratings {
+                                    ui.label(rating.category_label());
+                                    ui.label(rating.probability_label());
+                                    if rating.blocked {
+                                        ui.colored_label(egui::Color32::RED, "ブロック");
+                                    } else {
+                                        ui.label("-");
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    }
+
+                    ui.add_space(10.0);
                     if ui.button("閉じる").clicked() {
                         ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                     }
@@ -90,18 +1081,80 @@ impl eframe::App for ResultApp {
                     ui.colored_label(egui::Color32::RED, format!("エラー: {}", error));
                     ui.add_space(10.0);
 
-                    if ui.button("閉じる").clicked() {
-                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-                    }
+                    ui.horizontal(|ui| {
+                        if self.can_failover && ui.button("別のキーで再試行").clicked() {
+                            retry_requested = true;
+                        }
+
+                        if crate::gemini::is_invalid_api_key_error(error)
+                            && ui.button("設定を開く").clicked()
+                        {
+                            open_setup();
+                            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                        }
+
+                        if ui.button("閉じる").clicked() {
+                            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                        }
+                    });
                 }
             }
         });
 
+        if translate_requested {
+            self.translate_edited();
+        }
+
+        if cancel_requested {
+            self.cancel_translation(ctx);
+        }
+
+        if let Some(mode) = mode_requested {
+            self.retranslate_with_mode(mode);
+        }
+
+        if retry_requested {
+            self.retry_with_next_key();
+        }
+
+        if detail_requested {
+            self.expand_to_detail(ctx);
+        }
+
+        if pin_requested {
+            self.retranslate_with_source_language();
+        }
+
+        if always_on_top_requested {
+            self.toggle_always_on_top(ctx);
+        }
+
         // Escキーで閉じる
         if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
             ctx.send_viewport_cmd(egui::ViewportCommand::Close);
         }
     }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.release_pinned();
+    }
+}
+
+fn window_level(always_on_top: bool) -> egui::WindowLevel {
+    if always_on_top {
+        egui::WindowLevel::AlwaysOnTop
+    } else {
+        egui::WindowLevel::Normal
+    }
+}
+
+/// 設定画面を別プロセスで開く（結果ウィンドウと同じスレッドでは開けないため）
+fn open_setup() {
+    let result = std::env::current_exe()
+        .and_then(|exe| std::process::Command::new(exe).arg("--setup").spawn());
+    if let Err(e) = result {
+        eprintln!("Failed to open setup window: {}", e);
+    }
 }
 
 fn get_cursor_position() -> (f32, f32) {
@@ -112,37 +1165,308 @@ fn get_cursor_position() -> (f32, f32) {
     }
 }
 
+/// タイトルバー・枠の分として、ウィンドウの内側の大きさに足す余白
+const FRAME_ALLOWANCE: [f32; 2] = [16.0, 40.0];
+
+/// 指定した位置（物理ピクセル）に最も近いモニター
+fn monitor_at(x: f32, y: f32) -> HMONITOR {
+    let point = POINT {
+        x: x as i32,
+        y: y as i32,
+    };
+    unsafe { MonitorFromPoint(point, MONITOR_DEFAULTTONEAREST) }
+}
+
+/// モニターの拡大率（96 DPI を 1.0 とする）。取得できない場合は 1.0
+fn monitor_scale(monitor: HMONITOR) -> f32 {
+    let (mut dpi_x, mut dpi_y) = (0, 0);
+    match unsafe { GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) } {
+        Ok(()) if dpi_x > 0 => dpi_x as f32 / 96.0,
+        _ => 1.0,
+    }
+}
+
+/// メインモニターの拡大率
+/// 作成時のウィンドウ位置・大きさは、メインモニターの拡大率で物理ピクセルに換算される
+fn primary_monitor_scale() -> f32 {
+    let monitor = unsafe { MonitorFromPoint(POINT { x: 0, y: 0 }, MONITOR_DEFAULTTOPRIMARY) };
+    monitor_scale(monitor)
+}
+
+/// モニターの作業領域（タスクバーを除いた領域）
+fn monitor_work_area(monitor: HMONITOR) -> Option<RECT> {
+    unsafe {
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        GetMonitorInfoW(monitor, &mut info)
+            .as_bool()
+            .then_some(info.rcWork)
+    }
+}
+
+/// ウィンドウ全体がモニターの作業領域に収まるよう位置を調整する
+/// 右・下にはみ出す場合は内側へ寄せ、作業領域より大きい場合は左上をそろえる
+/// 位置は物理ピクセル、大きさは論理サイズで受け取り、そのモニターの拡大率で換算する
+fn clamp_to_work_area(position: [f32; 2], size: [f32; 2], decorated: bool) -> [f32; 2] {
+    let monitor = monitor_at(position[0], position[1]);
+    let Some(area) = monitor_work_area(monitor) else {
+        return position;
+    };
+    let scale = monitor_scale(monitor);
+    let (width, height) = if decorated {
+        (size[0] + FRAME_ALLOWANCE[0], size[1] + FRAME_ALLOWANCE[1])
+    } else {
+        (size[0], size[1])
+    };
+    let (width, height) = (width * scale, height * scale);
+
+    [
+        position[0]
+            .min(area.right as f32 - width)
+            .max(area.left as f32),
+        position[1]
+            .min(area.bottom as f32 - height)
+            .max(area.top as f32),
+    ]
+}
+
+/// ウィンドウを作成できなかった場合（GPUのないRDPセッションなど）に、
+/// 翻訳結果をメッセージボックスで表示して結果が失われないようにする
+fn show_fallback(mut app: ResultApp) {
+    app.release_pinned();
+
+    let outcome = match app.state {
+        // 編集できないため、取得したままの原文を翻訳する
+        ContentState::Editing(text) => crate::translate_blocking(text, app.config.clone()),
+        ContentState::Ready(content) => Ok(content),
+        ContentState::Error(e) => Err(e),
+        ContentState::Blocked(info) => Err(info.to_string()),
+        ContentState::Loading => match app.receiver {
+            // 翻訳タスクの完了を待つ
+            Some(ref rx) => rx
+                .iter()
+                .find_map(TranslationEvent::into_outcome)
+                .unwrap_or_else(|| Err("接続が切断されました".to_string()))
+                .map(|t| t.content),
+            None => Err("翻訳結果がありません".to_string()),
+        },
+    };
+
+    if let Some(ref flag) = app.is_translating {
+        flag.store(false, Ordering::SeqCst);
+    }
+
+    // 「すべて中止」された場合は何も表示しない
+    if app.cancel.is_cancelled() {
+        return;
+    }
+
+    let (title, text, icon) = match outcome {
+        Ok(content) => ("翻訳結果", content, MB_ICONINFORMATION),
+        Err(e) => ("翻訳エラー", format!("エラー: {}", e), MB_ICONERROR),
+    };
+
+    unsafe {
+        MessageBoxW(
+            HWND(0),
+            &HSTRING::from(text),
+            &HSTRING::from(title),
+            MB_OK | icon | MB_TOPMOST,
+        );
+    }
+}
+
 pub fn show_result_with_receiver(
-    receiver: Receiver<Result<String, String>>,
+    receiver: Receiver<TranslationEvent>,
+    config: &Config,
     is_translating: Option<Arc<AtomicBool>>,
+    source_text: Option<String>,
+) -> Result<()> {
+    // ツールチップ表示は原文がある場合のみ（詳細の取得に原文が必要なため）
+    let preview = config.preview_first && source_text.is_some();
+
+    run_result_window(
+        ContentState::Loading,
+        Some(receiver),
+        config,
+        is_translating,
+        source_text,
+        preview,
+        None,
+    )
+}
+
+/// 開いたままの結果ウィンドウに翻訳を表示する（ウィンドウがなければ開き、閉じるまでブロックする）
+pub fn show_result_in_pinned_window(
+    receiver: Receiver<TranslationEvent>,
+    config: &Config,
+    is_translating: Option<Arc<AtomicBool>>,
+    source_text: String,
+) -> Result<()> {
+    let next = NextTranslation {
+        receiver,
+        config: config.clone(),
+        is_translating,
+        source_text,
+    };
+
+    let (next, incoming) = {
+        let mut pinned = lock_recovering(&PINNED_WINDOW);
+        // 開いているウィンドウがあればそちらに渡して終わる（閉じた直後で渡せなかった場合は新しく開く）
+        let next = match pinned.as_ref() {
+            Some(sender) => match sender.send(next) {
+                Ok(()) => return Ok(()),
+                Err(mpsc::SendError(next)) => next,
+            },
+            None => next,
+        };
+        let (tx, rx) = mpsc::channel();
+        *pinned = Some(tx);
+        (next, rx)
+    };
+
+    run_result_window(
+        ContentState::Loading,
+        Some(next.receiver),
+        &next.config,
+        next.is_translating,
+        Some(next.source_text),
+        false,
+        Some(incoming),
+    )
+}
+
+/// 原文を編集できる状態でウィンドウを表示し、「翻訳」が押されてから翻訳する
+pub fn show_result_for_editing(
+    text: String,
+    config: &Config,
+    is_translating: Option<Arc<AtomicBool>>,
+) -> Result<()> {
+    // 編集中は次のホットキーを妨げないよう、翻訳を開始するまでフラグを下ろしておく
+    if let Some(ref flag) = is_translating {
+        flag.store(false, Ordering::SeqCst);
+    }
+
+    run_result_window(
+        ContentState::Editing(text),
+        None,
+        config,
+        is_translating,
+        None,
+        false,
+        None,
+    )
+}
+
+fn run_result_window(
+    state: ContentState,
+    receiver: Option<Receiver<TranslationEvent>>,
+    config: &Config,
+    is_translating: Option<Arc<AtomicBool>>,
+    source_text: Option<String>,
+    preview: bool,
+    incoming: Option<Receiver<NextTranslation>>,
 ) -> Result<()> {
     let (cursor_x, cursor_y) = get_cursor_position();
+    let size = if preview { PREVIEW_SIZE } else { DETAIL_SIZE };
+    // カーソル位置は物理ピクセルなので、カーソルのあるモニターの拡大率で余白を換算して配置し、
+    // 作成時の換算に使われるメインモニターの拡大率で論理座標に戻す
+    // 大きさも同じくメインモニターの拡大率で換算されるため、表示先モニターの拡大率との比で補正する
+    let scale = monitor_scale(monitor_at(cursor_x, cursor_y));
+    let [x, y] = clamp_to_work_area(
+        [cursor_x + 20.0 * scale, cursor_y - 10.0 * scale],
+        size,
+        !preview,
+    );
+    let primary_scale = primary_monitor_scale();
+    let position = [x / primary_scale, y / primary_scale];
+    let inner_size = [
+        size[0] * scale / primary_scale,
+        size[1] * scale / primary_scale,
+    ];
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_inner_size([500.0, 400.0])
-            .with_position([cursor_x + 20.0, cursor_y - 10.0])
-            .with_always_on_top()
+            .with_inner_size(inner_size)
+            .with_decorations(!preview)
+            .with_position(position)
+            // ツールチップ表示は他のウィンドウに隠れないよう常に最前面にする
+            .with_window_level(window_level(preview || config.always_on_top))
+            .with_active(config.focus_result_window)
+            .with_mouse_passthrough(config.click_through_until_hover)
             .with_resizable(true),
         ..Default::default()
     };
 
+    let extra_fonts = config.extra_fonts.clone();
+    let accent_color = config.accent_color;
+    let theme = config.theme;
+    let font_size = config.font_size;
+
     let result_app = ResultApp {
-        state: ContentState::Loading,
-        receiver: Some(receiver),
+        state,
+        receiver,
+        server_busy: None,
+        stats: None,
         markdown_cache: CommonMarkCache::default(),
         is_translating,
+        collapse_after_lines: config.collapse_after_lines,
+        expanded: false,
+        cancel: CancelToken::new(),
+        error_display: config.error_display,
+        source_text,
+        config: config.clone(),
+        can_failover: false,
+        click_through: config.click_through_until_hover,
+        preview,
+        low_confidence: None,
+        pin_language: String::new(),
+        copied_at: None,
+        output_mode: if preview {
+            OutputMode::Concise
+        } else {
+            config.output_mode
+        },
+        side_by_side: false,
+        show_source: false,
+        previous_result: None,
+        show_diff: false,
+        diff_spans: None,
+        copied_table: None,
+        loading_since: None,
+        cancel_request: None,
+        incoming,
+        always_on_top: config.always_on_top,
+        monitor_scale: Some(scale),
     };
 
-    eframe::run_native(
+    // ウィンドウ作成に失敗した場合に結果を取り戻せるよう、アプリはクロージャ内で取り出す
+    let mut pending = Some(result_app);
+
+    let run_result = eframe::run_native(
         "Translation Result",
         options,
         Box::new(|cc| {
-            setup_japanese_fonts(&cc.egui_ctx);
-            Ok(Box::new(result_app))
+            setup_fonts(&cc.egui_ctx, &extra_fonts);
+            apply_theme(&cc.egui_ctx, theme);
+            apply_font_size(&cc.egui_ctx, font_size);
+            apply_accent_color(&cc.egui_ctx, accent_color);
+            let app = pending.take().expect("app creator is called only once");
+            Ok(Box::new(app))
         }),
-    )
-    .map_err(|e| anyhow::anyhow!("Failed to run result window: {}", e))?;
+    );
+
+    if let Err(e) = run_result {
+        eprintln!("Failed to run result window: {}", e);
+
+        // アプリ生成前に失敗した場合はメッセージボックスで代替表示
+        let Some(app) = pending else {
+            return Err(anyhow::anyhow!("Failed to run result window: {}", e));
+        };
+        show_fallback(app);
+    }
 
     Ok(())
 }
@@ -150,6 +1474,9 @@ pub fn show_result_with_receiver(
 // 旧API（後方互換のため残す）
 pub fn show_result(content: &str) -> Result<()> {
     let (tx, rx) = mpsc::channel();
-    let _ = tx.send(Ok(content.to_string()));
-    show_result_with_receiver(rx, None)
+    let _ = tx.send(TranslationEvent::Finished(Ok(Translation::plain(
+        content.to_string(),
+    ))));
+    let config = crate::config::load_or_create().unwrap_or_default();
+    show_result_with_receiver(rx, &config, None, None)
 }
@@ -1,11 +1,19 @@
 use anyhow::Result;
 use eframe::egui;
+use std::collections::BTreeMap;
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread;
 
-use super::common::setup_japanese_fonts;
-use crate::config::{self, Config, Hotkey, OutputMode, DEFAULT_MODEL, FALLBACK_MODELS};
+use super::common::{apply_accent_color, apply_font_size, apply_theme, setup_fonts};
+use crate::config::{
+    self, AppRule, ClipboardBackend, Config, DisclaimerRule, ErrorDisplay, Hotkey, LanguagePair, OutputMode, Profile,
+    Provider, ReadingLevel, Theme, SendToApp, SpecialContentAction, StartupMethod, DEEPL_TARGET_LANGUAGES, DEFAULT_CUSTOM_PROMPT, FALLBACK_MODELS,
+};
 use crate::gemini::{fetch_available_models, ModelInfo};
+use crate::provider::{Backend, TranslationProvider};
+
+/// テスト翻訳に使うサンプル文
+const TEST_SAMPLE_TEXT: &str = "The quick brown fox jumps over the lazy dog.";
 
 /// egui::KeyをWindows VKコードに変換
 fn key_to_vk_code(key: egui::Key) -> Option<i32> {
@@ -59,10 +67,59 @@ fn key_to_vk_code(key: egui::Key) -> Option<i32> {
         Key::F10 => Some(0x79),
         Key::F11 => Some(0x7A),
         Key::F12 => Some(0x7B),
+        // 記号キー（VK_OEM_*、US配列での割り当て）
+        Key::Semicolon => Some(0xBA),
+        Key::Equals => Some(0xBB),
+        Key::Comma => Some(0xBC),
+        Key::Minus => Some(0xBD),
+        Key::Period => Some(0xBE),
+        Key::Slash => Some(0xBF),
+        Key::Backtick => Some(0xC0),
+        Key::OpenBracket => Some(0xDB),
+        Key::Backslash => Some(0xDC),
+        Key::CloseBracket => Some(0xDD),
+        Key::Quote => Some(0xDE),
         _ => None,
     }
 }
 
+/// キー入力待ちの対象ホットキー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HotkeyTarget {
+    Translate,
+    Reset,
+    Pause,
+}
+
+/// モデル一覧の1行（お気に入りの切り替えボタンとモデル名）を表示し、
+/// お気に入りの切り替えが押されたかを返す
+fn model_row(
+    ui: &mut egui::Ui,
+    model: &ModelInfo,
+    favorite: bool,
+    selected_model_id: &mut String,
+) -> bool {
+    let label = if model.display_name.is_empty() {
+        model.model_id().to_string()
+    } else {
+        format!("{} ({})", model.display_name, model.model_id())
+    };
+
+    ui.horizontal(|ui| {
+        let toggled = ui
+            .small_button(if favorite { "★" } else { "☆" })
+            .on_hover_text(if favorite {
+                "お気に入りから外す"
+            } else {
+                "お気に入りに追加（一覧の先頭に表示）"
+            })
+            .clicked();
+        ui.selectable_value(selected_model_id, model.model_id().to_string(), label);
+        toggled
+    })
+    .inner
+}
+
 enum ModelLoadState {
     NotLoaded,
     Loading,
@@ -71,38 +128,211 @@ enum ModelLoadState {
 }
 
 struct SetupApp {
+    /// 読み込んだ設定（画面で編集しない項目を保存時に引き継ぐ）
+    base_config: Config,
     api_key: String,
+    /// 認証エラー・クォータ超過時に切り替える予備のキー
+    backup_api_keys: Vec<String>,
+    /// 追加HTTPヘッダー（名前, 値）
+    extra_headers: Vec<(String, String)>,
+    /// 用語集（原語, 訳語）
+    glossary: Vec<(String, String)>,
+    /// アプリごとの上書き（実行ファイル名, ルール）
+    app_rules: Vec<(String, AppRule)>,
     selected_model_id: String,
+    auto_model: bool,
+    favorite_models: Vec<String>,
+    plain_concise: bool,
+    min_detection_confidence: u8,
     output_mode: OutputMode,
+    auto_dictionary: bool,
+    target_language: String,
+    use_source_language: bool,
+    source_language: String,
+    reading_level: ReadingLevel,
+    error_display: ErrorDisplay,
+    special_content: SpecialContentAction,
+    clipboard_backend: ClipboardBackend,
     hotkey: Hotkey,
-    listening_for_hotkey: bool,
+    smart_swap: bool,
+    skip_same_language: bool,
+    use_language_pair: bool,
+    language_pair: LanguagePair,
+    preserve_markdown: bool,
+    include_reading: bool,
+    collapse_after_lines: usize,
+    plain_text_threshold: usize,
+    render_markdown: bool,
+    focus_result_window: bool,
+    always_on_top: bool,
+    title_shows_languages: bool,
+    stream_responses: bool,
+    stream_channel_capacity: usize,
+    edit_before_translate: bool,
+    auto_copy: bool,
+    silent: bool,
+    auto_translate_on_copy: bool,
+    double_press_window_ms: u64,
+    preview_first: bool,
+    reuse_window: bool,
+    use_send_to_app: bool,
+    use_accent_color: bool,
+    accent_color: [u8; 3],
+    theme: Theme,
+    font_size: f32,
+    send_to_app: SendToApp,
+    click_through_until_hover: bool,
+    busy_notice_interval_secs: u64,
+    queue_requests: bool,
+    history_limit: usize,
+    cache_size: usize,
+    timeout_secs: u64,
+    max_retries: u32,
+    retry_delay_ms: u64,
+    chunk_chars: usize,
+    max_chars: usize,
+    provider: Provider,
+    openai_base_url: String,
+    openai_model: String,
+    deepl_target_lang: String,
+    shared_context: String,
+    context_cache_ttl_mins: u64,
+    disclaimer_rules: Vec<DisclaimerRule>,
+    profiles: BTreeMap<String, Profile>,
+    active_profile: Option<String>,
+    new_profile_name: String,
+    custom_prompt: String,
+    use_temperature: bool,
+    temperature: f32,
+    use_max_output_tokens: bool,
+    max_output_tokens: u32,
+    reset_hotkey: Option<Hotkey>,
+    pause_hotkey: Option<Hotkey>,
+    listening_for: Option<HotkeyTarget>,
     models: ModelLoadState,
     model_receiver: Option<Receiver<Result<Vec<ModelInfo>, String>>>,
+    /// 保存済みの一覧を表示したまま、裏で取得し直しているか
+    refreshing_models: bool,
+    /// テスト翻訳の結果（Ok: 訳文, Err: エラー内容）
+    test_result: Option<Result<String, String>>,
+    test_receiver: Option<Receiver<Result<String, String>>>,
     error_message: Option<String>,
     api_key_validated: bool,
     saved: bool,
     startup_enabled: bool,
+    startup_method: StartupMethod,
 }
 
 impl SetupApp {
     fn new() -> Self {
-        let (api_key, selected_model_id, output_mode, hotkey) = match config::load_or_create() {
-            Ok(cfg) => (cfg.api_key, cfg.model, cfg.output_mode, cfg.hotkey),
-            Err(_) => (String::new(), DEFAULT_MODEL.to_string(), OutputMode::default(), Hotkey::default()),
-        };
+        let cfg = config::load_or_create().unwrap_or_default();
 
-        Self {
-            api_key,
-            selected_model_id,
-            output_mode,
-            hotkey,
-            listening_for_hotkey: false,
+        let mut app = Self {
+            base_config: cfg.clone(),
+            api_key: cfg.api_key,
+            backup_api_keys: crate::credential::load_backup_api_keys(cfg.provider),
+            extra_headers: cfg.extra_headers.clone().into_iter().collect(),
+            app_rules: cfg.app_rules.clone().into_iter().collect(),
+            glossary: cfg.glossary.clone().into_iter().collect(),
+            selected_model_id: cfg.model,
+            auto_model: cfg.auto_model,
+            favorite_models: cfg.favorite_models.clone(),
+            plain_concise: cfg.plain_concise,
+            min_detection_confidence: cfg.min_detection_confidence,
+            output_mode: cfg.output_mode,
+            auto_dictionary: cfg.auto_dictionary,
+            target_language: cfg.target_language.clone(),
+            use_source_language: cfg.source_language.is_some(),
+            source_language: cfg.source_language.clone().unwrap_or_default(),
+            reading_level: cfg.reading_level,
+            error_display: cfg.error_display,
+            special_content: cfg.special_content,
+            clipboard_backend: cfg.clipboard_backend,
+            hotkey: cfg.hotkey,
+            smart_swap: cfg.smart_swap,
+            skip_same_language: cfg.skip_same_language,
+            use_language_pair: cfg.language_pair.is_some(),
+            language_pair: cfg.language_pair.clone().unwrap_or_default(),
+            preserve_markdown: cfg.preserve_markdown,
+            include_reading: cfg.include_reading,
+            collapse_after_lines: cfg.collapse_after_lines,
+            plain_text_threshold: cfg.plain_text_threshold,
+            render_markdown: cfg.render_markdown,
+            focus_result_window: cfg.focus_result_window,
+            always_on_top: cfg.always_on_top,
+            title_shows_languages: cfg.title_shows_languages,
+            stream_responses: cfg.stream_responses,
+            stream_channel_capacity: cfg.stream_channel_capacity,
+            edit_before_translate: cfg.edit_before_translate,
+            auto_copy: cfg.auto_copy,
+            silent: cfg.silent,
+            auto_translate_on_copy: cfg.auto_translate_on_copy,
+            double_press_window_ms: cfg.double_press_window_ms,
+            preview_first: cfg.preview_first,
+            reuse_window: cfg.reuse_window,
+            use_send_to_app: cfg.send_to_app.is_some(),
+            use_accent_color: cfg.accent_color.is_some(),
+            accent_color: cfg.accent_color.unwrap_or([0x2e, 0x86, 0xde]),
+            theme: cfg.theme,
+            font_size: cfg.font_size,
+            send_to_app: cfg.send_to_app.clone().unwrap_or_default(),
+            click_through_until_hover: cfg.click_through_until_hover,
+            busy_notice_interval_secs: cfg.busy_notice_interval_secs,
+            queue_requests: cfg.queue_requests,
+            history_limit: cfg.history_limit,
+            cache_size: cfg.cache_size,
+            timeout_secs: cfg.timeout_secs,
+            max_retries: cfg.max_retries,
+            retry_delay_ms: cfg.retry_delay_ms,
+            chunk_chars: cfg.chunk_chars,
+            max_chars: cfg.max_chars,
+            provider: cfg.provider,
+            openai_base_url: cfg.openai_base_url.clone(),
+            openai_model: cfg.openai_model.clone(),
+            deepl_target_lang: cfg.deepl_target_lang.clone(),
+            shared_context: cfg.shared_context,
+            context_cache_ttl_mins: cfg.context_cache_ttl_secs / 60,
+            disclaimer_rules: cfg.disclaimer_rules,
+            profiles: cfg.profiles,
+            active_profile: cfg.active_profile,
+            new_profile_name: String::new(),
+            use_temperature: cfg.temperature.is_some(),
+            temperature: cfg.temperature.unwrap_or(1.0),
+            use_max_output_tokens: cfg.max_output_tokens.is_some(),
+            max_output_tokens: cfg.max_output_tokens.unwrap_or(2048),
+            custom_prompt: cfg
+                .custom_prompt
+                .clone()
+                .unwrap_or_else(|| DEFAULT_CUSTOM_PROMPT.to_string()),
+            reset_hotkey: cfg.reset_hotkey,
+            pause_hotkey: cfg.pause_hotkey,
+            listening_for: None,
             models: ModelLoadState::NotLoaded,
             model_receiver: None,
+            refreshing_models: false,
+            test_result: None,
+            test_receiver: None,
             error_message: None,
             api_key_validated: false,
             saved: false,
             startup_enabled: crate::startup::is_installed(),
+            startup_method: crate::startup::installed_method().unwrap_or(cfg.startup_method),
+        };
+        // モデル一覧を取得し直さなくても設定を確認できるよう、前回の一覧を表示する
+        app.load_cached_models();
+        app
+    }
+
+    /// 前回取得したモデル一覧を表示し、古くなっていれば裏で取得し直す
+    fn load_cached_models(&mut self) {
+        let Some((models, stale)) = crate::model_cache::load() else {
+            return;
+        };
+        self.models = ModelLoadState::Loaded(models);
+
+        if stale && self.provider == Provider::Gemini && !self.api_key.trim().is_empty() {
+            self.refreshing_models = true;
+            self.spawn_model_fetch();
         }
     }
 
@@ -113,9 +343,13 @@ impl SetupApp {
         }
 
         self.models = ModelLoadState::Loading;
+        self.refreshing_models = false;
         self.error_message = None;
         self.api_key_validated = false;
+        self.spawn_model_fetch();
+    }
 
+    fn spawn_model_fetch(&mut self) {
         let (tx, rx): (
             Sender<Result<Vec<ModelInfo>, String>>,
             Receiver<Result<Vec<ModelInfo>, String>>,
@@ -123,6 +357,7 @@ impl SetupApp {
         self.model_receiver = Some(rx);
 
         let api_key = self.api_key.clone();
+        let extra_headers = self.header_map();
 
         thread::spawn(move || {
             let rt = match tokio::runtime::Runtime::new() {
@@ -132,12 +367,82 @@ impl SetupApp {
                     return;
                 }
             };
-            let result = rt.block_on(fetch_available_models(&api_key));
+            let result = rt.block_on(fetch_available_models(&api_key, &extra_headers));
 
             let _ = tx.send(result.map_err(|e| e.to_string()));
         });
     }
 
+    /// 入力中の設定でサンプル文を実際に翻訳する（generateContent の権限・クォータも確認できる）
+    fn start_test_translation(&mut self) {
+        if self.api_key.trim().is_empty() {
+            self.error_message = Some("APIキーを入力してください".to_string());
+            return;
+        }
+
+        self.test_result = None;
+        self.error_message = None;
+
+        let (tx, rx) = mpsc::channel();
+        self.test_receiver = Some(rx);
+
+        // 翻訳時と同じ組み立て方でクライアントを作るため、入力中の値だけを設定に詰める
+        let config = Config {
+            provider: self.provider,
+            api_key: self.api_key.trim().to_string(),
+            model: self.selected_model_id.clone(),
+            openai_base_url: self.openai_base_url.trim().to_string(),
+            openai_model: self.openai_model.trim().to_string(),
+            deepl_target_lang: self.deepl_target_lang.clone(),
+            output_mode: if self.provider.supports_explanation() {
+                self.output_mode
+            } else {
+                OutputMode::Concise
+            },
+            target_language: self.target_language.clone(),
+            custom_prompt: Some(self.custom_prompt.clone()).filter(|t| t.contains("{text}")),
+            temperature: self.use_temperature.then_some(self.temperature),
+            max_output_tokens: self.use_max_output_tokens.then_some(self.max_output_tokens),
+            extra_headers: self.header_map(),
+            timeout_secs: self.timeout_secs,
+            max_retries: self.max_retries,
+            retry_delay_ms: self.retry_delay_ms,
+            ..Config::default()
+        };
+        let backend = Backend::from_config(&config, None);
+
+        thread::spawn(move || {
+            let rt = match tokio::runtime::Runtime::new() {
+                Ok(rt) => rt,
+                Err(e) => {
+                    let _ = tx.send(Err(format!("ランタイム作成失敗: {}", e)));
+                    return;
+                }
+            };
+            let result = rt.block_on(backend.translate(TEST_SAMPLE_TEXT));
+
+            let _ = tx.send(result.map(|t| t.content).map_err(|e| e.to_string()));
+        });
+    }
+
+    fn check_test_translation(&mut self) {
+        if let Some(ref rx) = self.test_receiver {
+            if let Ok(result) = rx.try_recv() {
+                self.test_result = Some(result);
+                self.test_receiver = None;
+            }
+        }
+    }
+
+    /// 空行を除いた追加HTTPヘッダー
+    fn header_map(&self) -> BTreeMap<String, String> {
+        self.extra_headers
+            .iter()
+            .filter(|(name, _)| !name.trim().is_empty())
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .collect()
+    }
+
     fn check_model_fetch(&mut self) {
         if let Some(ref rx) = self.model_receiver {
             if let Ok(result) = rx.try_recv() {
@@ -150,6 +455,14 @@ impl SetupApp {
                         } else {
                             // APIキーが有効であることが確認された
                             self.api_key_validated = true;
+                            // 自動選択の場合は一覧を取得するたびに選び直す
+                            let auto_pick = self
+                                .auto_model
+                                .then(|| crate::gemini::pick_fast_model(&models))
+                                .flatten();
+                            if let Some(model) = auto_pick {
+                                self.selected_model_id = model.model_id().to_string();
+                            }
                             // 現在選択されているモデルが一覧にあるか確認
                             let exists = models
                                 .iter()
@@ -157,14 +470,181 @@ impl SetupApp {
                             if !exists && !models.is_empty() {
                                 self.selected_model_id = models[0].model_id().to_string();
                             }
+                            if let Err(e) = crate::model_cache::save(&models) {
+                                eprintln!("Failed to save model list: {}", e);
+                            }
                             self.models = ModelLoadState::Loaded(models);
                         }
                     }
+                    // 裏での取得に失敗した場合は、保存済みの一覧を表示したままにする
+                    Err(e) if self.refreshing_models => {
+                        eprintln!("Failed to refresh model list: {}", e);
+                    }
                     Err(e) => {
                         self.models = ModelLoadState::Error(e);
                     }
                 }
                 self.model_receiver = None;
+                self.refreshing_models = false;
+            }
+        }
+    }
+
+    /// 入力内容を検証して設定・APIキーを保存し、スタートアップ登録を反映する
+    /// （「保存」ボタンと Ctrl+S で共通）
+    fn save_config(&mut self) {
+        // カスタムモードを使う場合、プロンプトに原文の位置がないと翻訳できない
+        let uses_custom = self.output_mode == OutputMode::Custom
+            || self
+                .profiles
+                .values()
+                .any(|p| p.output_mode == OutputMode::Custom)
+            || self.app_rules.iter().any(|(_, rule)| {
+                rule.output_mode == OutputMode::Custom
+                    && !rule
+                        .custom_prompt
+                        .as_deref()
+                        .is_some_and(|t| t.contains("{text}"))
+            });
+
+        // すべて中止・一時停止はシングルプレスのみのため、重なる場合は別のキーが必要
+        let conflicting_single = [self.reset_hotkey, self.pause_hotkey]
+            .into_iter()
+            .flatten()
+            .find_map(|hk| hk.conflicts_with_system());
+
+        if self.api_key.trim().is_empty() {
+            self.error_message = Some("APIキーを入力してください".to_string());
+        } else if let Some(shortcut) = self.hotkey.conflicts_with_system() {
+            self.error_message = Some(format!(
+                "ホットキーが{}と重なるため、ダブルプレスを有効にしてください",
+                shortcut
+            ));
+        } else if let Some(shortcut) = conflicting_single {
+            self.error_message = Some(format!(
+                "{}と重なるホットキーは使えません。別のキーを選んでください",
+                shortcut
+            ));
+        } else if uses_custom && !self.custom_prompt.contains("{text}") {
+            self.error_message = Some("カスタムプロンプトに {text} を含めてください".to_string());
+        } else {
+            let config = Config {
+                api_key: self.api_key.clone(),
+                model: self.selected_model_id.clone(),
+                auto_model: self.auto_model,
+                favorite_models: self.favorite_models.clone(),
+                plain_concise: self.plain_concise,
+                min_detection_confidence: self.min_detection_confidence,
+                output_mode: self.output_mode,
+                auto_dictionary: self.auto_dictionary,
+                target_language: self.target_language.trim().to_string(),
+                source_language: Some(self.source_language.trim().to_string())
+                    .filter(|l| self.use_source_language && !l.is_empty()),
+                temperature: self.use_temperature.then_some(self.temperature),
+                max_output_tokens: self.use_max_output_tokens.then_some(self.max_output_tokens),
+                custom_prompt: Some(self.custom_prompt.clone()).filter(|t| t.contains("{text}")),
+                profiles: self.profiles.clone(),
+                active_profile: self.active_profile.clone(),
+                hotkey: self.hotkey,
+                reset_hotkey: self.reset_hotkey,
+                pause_hotkey: self.pause_hotkey,
+                smart_swap: self.smart_swap,
+                skip_same_language: self.skip_same_language,
+                language_pair: self.use_language_pair.then(|| self.language_pair.clone()),
+                preserve_markdown: self.preserve_markdown,
+                include_reading: self.include_reading,
+                collapse_after_lines: self.collapse_after_lines,
+                plain_text_threshold: self.plain_text_threshold,
+                render_markdown: self.render_markdown,
+                focus_result_window: self.focus_result_window,
+                always_on_top: self.always_on_top,
+                title_shows_languages: self.title_shows_languages,
+                stream_responses: self.stream_responses,
+                stream_channel_capacity: self.stream_channel_capacity,
+                edit_before_translate: self.edit_before_translate,
+                auto_copy: self.auto_copy,
+                silent: self.silent,
+                auto_translate_on_copy: self.auto_translate_on_copy,
+                double_press_window_ms: self.double_press_window_ms,
+                preview_first: self.preview_first,
+                reuse_window: self.reuse_window,
+                accent_color: self.use_accent_color.then_some(self.accent_color),
+                theme: self.theme,
+                startup_method: self.startup_method,
+                font_size: self.font_size,
+                send_to_app: self.use_send_to_app.then(|| self.send_to_app.clone()),
+                click_through_until_hover: self.click_through_until_hover,
+                busy_notice_interval_secs: self.busy_notice_interval_secs,
+                queue_requests: self.queue_requests,
+                history_limit: self.history_limit,
+                cache_size: self.cache_size,
+                timeout_secs: self.timeout_secs,
+                max_retries: self.max_retries,
+                retry_delay_ms: self.retry_delay_ms,
+                chunk_chars: self.chunk_chars,
+                max_chars: self.max_chars,
+                provider: self.provider,
+                openai_base_url: self.openai_base_url.trim().to_string(),
+                openai_model: self.openai_model.trim().to_string(),
+                deepl_target_lang: self.deepl_target_lang.clone(),
+                reading_level: self.reading_level,
+                error_display: self.error_display,
+                special_content: self.special_content,
+                clipboard_backend: self.clipboard_backend,
+                shared_context: self.shared_context.clone(),
+                context_cache_ttl_secs: self.context_cache_ttl_mins * 60,
+                disclaimer_rules: self.disclaimer_rules.clone(),
+                extra_headers: self.header_map(),
+                glossary: self
+                    .glossary
+                    .iter()
+                    .filter(|(term, translation)| {
+                        !term.trim().is_empty() && !translation.trim().is_empty()
+                    })
+                    .map(|(term, translation)| {
+                        (term.trim().to_string(), translation.trim().to_string())
+                    })
+                    .collect(),
+                app_rules: self
+                    .app_rules
+                    .iter()
+                    .filter(|(name, _)| !name.trim().is_empty())
+                    .map(|(name, rule)| {
+                        let rule = AppRule {
+                            output_mode: rule.output_mode,
+                            custom_prompt: rule.custom_prompt.clone().filter(|t| {
+                                rule.output_mode == OutputMode::Custom && t.contains("{text}")
+                            }),
+                        };
+                        (name.trim().to_lowercase(), rule)
+                    })
+                    .collect(),
+                ..self.base_config.clone()
+            };
+
+            let save_result = crate::gemini::build_header_map(&config.extra_headers)
+                .and_then(|_| config::save(&config))
+                .and_then(|_| crate::credential::save_backup_api_keys(self.provider, &self.backup_api_keys));
+
+            match save_result {
+                Ok(_) => {
+                    // スタートアップ設定を適用
+                    let startup_result = if self.startup_enabled {
+                        crate::startup::install(self.startup_method)
+                    } else {
+                        crate::startup::uninstall_startup()
+                    };
+
+                    if let Err(e) = startup_result {
+                        self.error_message = Some(format!("スタートアップ設定エラー: {}", e));
+                    } else {
+                        self.saved = true;
+                        self.error_message = None;
+                    }
+                }
+                Err(e) => {
+                    self.error_message = Some(format!("保存エラー: {}", e));
+                }
             }
         }
     }
@@ -177,247 +657,1236 @@ impl SetupApp {
 impl eframe::App for SetupApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // ホットキー入力待ちの場合、キーを記録
-        if self.listening_for_hotkey {
+        if let Some(target) = self.listening_for {
             ctx.input(|i| {
                 for event in &i.events {
-                    if let egui::Event::Key {
-                        key,
-                        pressed: true,
-                        modifiers,
-                        ..
-                    } = event
-                    {
-                        // egui::Keyをi32のVKコードに変換
-                        if let Some(vk_code) = key_to_vk_code(*key) {
-                            self.hotkey = Hotkey {
+                    let captured = match event {
+                        egui::Event::Key {
+                            key,
+                            pressed: true,
+                            modifiers,
+                            ..
+                        } => {
+                            // egui::Keyをi32のVKコードに変換
+                            key_to_vk_code(*key).map(|vk_code| Hotkey {
                                 ctrl: modifiers.ctrl,
                                 alt: modifiers.alt,
                                 shift: modifiers.shift,
                                 key_code: vk_code,
                                 is_double_press: false, // 手動設定時はシングルプレス
+                                mouse_button: None,
+                            })
+                        }
+                        // マウスのサイドボタン（戻る・進む）
+                        egui::Event::PointerButton {
+                            button,
+                            pressed: true,
+                            modifiers,
+                            ..
+                        } => {
+                            let mouse_button = match button {
+                                egui::PointerButton::Extra1 => Some(1),
+                                egui::PointerButton::Extra2 => Some(2),
+                                _ => None,
                             };
-                            self.listening_for_hotkey = false;
+                            mouse_button.map(|mouse_button| Hotkey {
+                                ctrl: modifiers.ctrl,
+                                alt: modifiers.alt,
+                                shift: modifiers.shift,
+                                key_code: 0,
+                                is_double_press: false,
+                                mouse_button: Some(mouse_button),
+                            })
+                        }
+                        _ => None,
+                    };
+
+                    if let Some(captured) = captured {
+                        match target {
+                            HotkeyTarget::Translate => self.hotkey = captured,
+                            HotkeyTarget::Reset => self.reset_hotkey = Some(captured),
+                            HotkeyTarget::Pause => self.pause_hotkey = Some(captured),
                         }
+                        self.listening_for = None;
                     }
                 }
             });
         }
 
-        // モデル取得の完了をチェック
+        // モデル取得・テスト翻訳の完了をチェック
         self.check_model_fetch();
+        self.check_test_translation();
+
+        // Ctrl+S でどこからでも保存（ホットキーの入力待ち中はキーの記録を優先）
+        let save_shortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::S);
+        if !self.saved
+            && self.listening_for.is_none()
+            && ctx.input_mut(|i| i.consume_shortcut(&save_shortcut))
+        {
+            self.save_config();
+        }
 
         egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("Clipboard Translator - 設定");
-            ui.add_space(20.0);
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.heading("Clipboard Translator - 設定");
+                ui.add_space(20.0);
+
+                let previous_provider = self.provider;
+                ui.horizontal(|ui| {
+                    ui.label("プロバイダー:");
+                    egui::ComboBox::from_id_salt("provider_selector")
+                        .selected_text(self.provider.label())
+                        .show_ui(ui, |ui| {
+                            for provider in Provider::all() {
+                                ui.selectable_value(&mut self.provider, *provider, provider.label());
+                            }
+                        });
+                });
+                // APIキーと予備のキーはプロバイダーごとに保存しているため、切り替えたら入力欄も入れ替える
+                if self.provider != previous_provider {
+                    self.api_key =
+                        crate::credential::load_api_key(self.provider).unwrap_or_default();
+                    self.backup_api_keys = crate::credential::load_backup_api_keys(self.provider);
+                }
+                ui.add_space(5.0);
 
-            ui.label("Google AI Studio で取得した Gemini API キーを入力してください:");
-            ui.add_space(10.0);
+                ui.label(match self.provider {
+                    Provider::Gemini => "Google AI Studio で取得した Gemini API キーを入力してください:",
+                    Provider::OpenAi => "OpenAI（または互換サービス）の API キーを入力してください:",
+                    Provider::DeepL => "DeepL API Free の認証キーを入力してください:",
+                });
+                ui.add_space(10.0);
 
-            ui.horizontal(|ui| {
-                ui.label("APIキー:");
-                let response =
-                    ui.add(egui::TextEdit::singleline(&mut self.api_key).desired_width(300.0));
+                ui.horizontal(|ui| {
+                    ui.label("APIキー:");
+                    let response =
+                        ui.add(egui::TextEdit::singleline(&mut self.api_key).desired_width(300.0));
 
-                if ui.button("モデル取得").clicked() {
-                    self.start_model_fetch();
-                }
+                    if self.provider == Provider::Gemini && ui.button("モデル取得").clicked() {
+                        self.start_model_fetch();
+                    }
 
-                // フォーカスを外したときも取得開始
-                if response.lost_focus()
-                    && ui.input(|i| i.key_pressed(egui::Key::Enter))
-                    && matches!(self.models, ModelLoadState::NotLoaded)
-                {
-                    self.start_model_fetch();
-                }
-            });
+                    // APIキー欄で Enter を押したときも取得開始（取得済みの場合は取得し直す）
+                    if self.provider == Provider::Gemini
+                        && response.lost_focus()
+                        && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                        && !matches!(self.models, ModelLoadState::Loading)
+                    {
+                        self.start_model_fetch();
+                    }
+                });
+
+                // 予備のAPIキー
+                ui.collapsing("予備のAPIキー", |ui| {
+                    ui.label("認証エラー・クォータ超過時に「別のキーで再試行」で切り替えます:");
+                    let mut remove_index = None;
+                    for (i, key) in self.backup_api_keys.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(key)
+                                    .password(true)
+                                    .desired_width(300.0),
+                            );
+                            if ui.small_button("削除").clicked() {
+                                remove_index = Some(i);
+                            }
+                        });
+                    }
+                    if let Some(i) = remove_index {
+                        self.backup_api_keys.remove(i);
+                    }
+                    if ui.button("追加").clicked() {
+                        self.backup_api_keys.push(String::new());
+                    }
+                });
 
-            ui.add_space(10.0);
+                ui.add_space(10.0);
 
-            // モデル選択
-            ui.horizontal(|ui| {
-                ui.label("モデル:");
+                if self.provider == Provider::Gemini {
+                    // モデル選択
+                    ui.horizontal(|ui| {
+                        ui.label("モデル:");
 
-                match &self.models {
-                    ModelLoadState::NotLoaded => {
-                        ui.label("(APIキー入力後「モデル取得」を押してください)");
+                        match &self.models {
+                            ModelLoadState::NotLoaded => {
+                                ui.label("(APIキー入力後「モデル取得」を押してください)");
+                            }
+                            ModelLoadState::Loading => {
+                                ui.spinner();
+                                ui.label("モデル一覧を取得中...");
+                            }
+                            ModelLoadState::Loaded(models) => {
+                                let selected_display = models
+                                    .iter()
+                                    .find(|m| m.model_id() == self.selected_model_id)
+                                    .map(|m| m.display_name.clone())
+                                    .unwrap_or_else(|| self.selected_model_id.clone());
+
+                                let before = self.selected_model_id.clone();
+                                let mut toggled_favorite = None;
+                                egui::ComboBox::from_id_salt("model_selector")
+                                    .selected_text(&selected_display)
+                                    .width(300.0)
+                                    .show_ui(ui, |ui| {
+                                        // お気に入りを先頭に、区切り線を挟んで残りのモデルを表示する
+                                        let (favorites, others): (Vec<_>, Vec<_>) =
+                                            models.iter().partition(|m| {
+                                                self.favorite_models
+                                                    .iter()
+                                                    .any(|f| f == m.model_id())
+                                            });
+                                        for model in &favorites {
+                                            if model_row(
+                                                ui,
+                                                model,
+                                                true,
+                                                &mut self.selected_model_id,
+                                            ) {
+                                                toggled_favorite =
+                                                    Some(model.model_id().to_string());
+                                            }
+                                        }
+                                        if !favorites.is_empty() && !others.is_empty() {
+                                            ui.separator();
+                                        }
+                                        for model in &others {
+                                            if model_row(
+                                                ui,
+                                                model,
+                                                false,
+                                                &mut self.selected_model_id,
+                                            ) {
+                                                toggled_favorite =
+                                                    Some(model.model_id().to_string());
+                                            }
+                                        }
+                                    });
+
+                                if let Some(model_id) = toggled_favorite {
+                                    if let Some(i) =
+                                        self.favorite_models.iter().position(|f| *f == model_id)
+                                    {
+                                        self.favorite_models.remove(i);
+                                    } else {
+                                        self.favorite_models.push(model_id);
+                                    }
+                                }
+
+                                // 手動で選んだ場合は自動選択を解除
+                                if self.selected_model_id != before {
+                                    self.auto_model = false;
+                                }
+
+                                if ui.checkbox(&mut self.auto_model, "自動").changed()
+                                    && self.auto_model
+                                {
+                                    if let Some(model) = crate::gemini::pick_fast_model(models) {
+                                        self.selected_model_id = model.model_id().to_string();
+                                    }
+                                }
+                            }
+                            ModelLoadState::Error(err) => {
+                                ui.colored_label(egui::Color32::YELLOW, format!("取得失敗: {}", err));
+
+                                // フォールバックモデルを表示
+                                let fallback = self.get_fallback_models();
+                                egui::ComboBox::from_id_salt("model_selector_fallback")
+                                    .selected_text(&self.selected_model_id)
+                                    .show_ui(ui, |ui| {
+                                        for model in &fallback {
+                                            ui.selectable_value(
+                                                &mut self.selected_model_id,
+                                                model.clone(),
+                                                model,
+                                            );
+                                        }
+                                    });
+                            }
+                        }
+                    });
+
+                    // APIキー検証成功メッセージ
+                    if self.api_key_validated {
+                        ui.add_space(5.0);
+                        ui.colored_label(egui::Color32::GREEN, "APIキーは有効です");
+                    }
+                } else if self.provider == Provider::DeepL {
+                    // DeepL：モデルの代わりに翻訳先言語を選ぶ
+                    ui.horizontal(|ui| {
+                        ui.label("翻訳先言語:");
+                        let selected = DEEPL_TARGET_LANGUAGES
+                            .iter()
+                            .find(|(code, _)| *code == self.deepl_target_lang)
+                            .map(|(_, name)| *name)
+                            .unwrap_or(self.deepl_target_lang.as_str());
+                        egui::ComboBox::from_id_salt("deepl_target_selector")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                for (code, name) in DEEPL_TARGET_LANGUAGES {
+                                    ui.selectable_value(
+                                        &mut self.deepl_target_lang,
+                                        code.to_string(),
+                                        format!("{} ({})", name, code),
+                                    );
+                                }
+                            });
+                    });
+                } else {
+                    // OpenAI互換API：エンドポイントとモデル名を直接入力する
+                    ui.horizontal(|ui| {
+                        ui.label("ベースURL:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.openai_base_url)
+                                .hint_text("https://api.openai.com/v1")
+                                .desired_width(300.0),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("モデル:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.openai_model)
+                                .hint_text("gpt-4o-mini")
+                                .desired_width(200.0),
+                        );
+                    });
+                }
+
+                // 保存前に実際の翻訳が通るか確認する
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    let running = self.test_receiver.is_some();
+                    if ui
+                        .add_enabled(!running, egui::Button::new("テスト翻訳"))
+                        .clicked()
+                    {
+                        self.start_test_translation();
                     }
-                    ModelLoadState::Loading => {
+                    if running {
                         ui.spinner();
-                        ui.label("モデル一覧を取得中...");
+                        ui.label("翻訳中...");
                     }
-                    ModelLoadState::Loaded(models) => {
-                        let selected_display = models
-                            .iter()
-                            .find(|m| m.model_id() == self.selected_model_id)
-                            .map(|m| m.display_name.clone())
-                            .unwrap_or_else(|| self.selected_model_id.clone());
+                });
+                match &self.test_result {
+                    Some(Ok(content)) => {
+                        ui.label(format!("原文: {}", TEST_SAMPLE_TEXT));
+                        ui.colored_label(egui::Color32::GREEN, content);
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(egui::Color32::RED, format!("テスト翻訳に失敗: {}", e));
+                    }
+                    None => {}
+                }
+
+                ui.add_space(15.0);
 
-                        egui::ComboBox::from_id_salt("model_selector")
-                            .selected_text(&selected_display)
+                // 出力モード選択（DeepLは解説を出せないため簡潔モードに固定）
+                if !self.provider.supports_explanation() {
+                    self.output_mode = OutputMode::Concise;
+                }
+                ui.horizontal(|ui| {
+                    ui.label("出力モード:");
+                    ui.add_enabled_ui(self.provider.supports_explanation(), |ui| {
+                        egui::ComboBox::from_id_salt("output_mode_selector")
+                            .selected_text(self.output_mode.label())
                             .width(300.0)
                             .show_ui(ui, |ui| {
-                                for model in models {
-                                    let label = if model.display_name.is_empty() {
-                                        model.model_id().to_string()
-                                    } else {
-                                        format!(
-                                            "{} ({})",
-                                            model.display_name,
-                                            model.model_id()
-                                        )
-                                    };
-                                    let model_id = model.model_id().to_string();
-                                    ui.selectable_value(
-                                        &mut self.selected_model_id,
-                                        model_id,
-                                        label,
-                                    );
+                                for mode in OutputMode::all() {
+                                    ui.selectable_value(&mut self.output_mode, *mode, mode.label());
                                 }
                             });
+                    });
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("翻訳先の言語:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.target_language)
+                            .hint_text("日本語")
+                            .desired_width(120.0),
+                    );
+                    ui.label("（原文がこの言語の場合は英語へ）");
+                });
+
+                // 読み方は翻訳先が日本語の場合のみ選べる
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.add_enabled(
+                        crate::language::is_japanese_target(&self.target_language),
+                        egui::Checkbox::new(
+                            &mut self.include_reading,
+                            "詳細モードで訳文の読み方（ひらがな・ローマ字）を表示",
+                        ),
+                    );
+                });
+
+                // 短い語句などで自動検出を誤る場合は原文の言語を固定する
+                ui.horizontal(|ui| {
+                    ui.label("原文の言語:");
+                    ui.checkbox(&mut self.use_source_language, "指定する");
+                    ui.add_enabled(
+                        self.use_source_language,
+                        egui::TextEdit::singleline(&mut self.source_language)
+                            .hint_text("英語")
+                            .desired_width(120.0),
+                    );
+                    if !self.use_source_language {
+                        ui.label("（自動検出）");
                     }
-                    ModelLoadState::Error(err) => {
-                        ui.colored_label(egui::Color32::YELLOW, format!("取得失敗: {}", err));
+                });
+
+                if self.output_mode == OutputMode::Concise {
+                    ui.horizontal(|ui| {
+                        ui.add_space(80.0);
+                        ui.checkbox(
+                            &mut self.plain_concise,
+                            "引用符・Markdownの装飾を取り除いてプレーンテキストにする",
+                        );
+                    });
+                }
 
-                        // フォールバックモデルを表示
-                        let fallback = self.get_fallback_models();
-                        egui::ComboBox::from_id_salt("model_selector_fallback")
-                            .selected_text(&self.selected_model_id)
+                if self.output_mode == OutputMode::Simple {
+                    ui.horizontal(|ui| {
+                        ui.label("解説の難易度:");
+                        egui::ComboBox::from_id_salt("reading_level_selector")
+                            .selected_text(self.reading_level.label())
                             .show_ui(ui, |ui| {
-                                for model in &fallback {
+                                for level in ReadingLevel::all() {
                                     ui.selectable_value(
-                                        &mut self.selected_model_id,
-                                        model.clone(),
-                                        model,
+                                        &mut self.reading_level,
+                                        *level,
+                                        level.label(),
                                     );
                                 }
                             });
-                    }
+                    });
                 }
-            });
 
-            // APIキー検証成功メッセージ
-            if self.api_key_validated {
-                ui.add_space(5.0);
-                ui.colored_label(egui::Color32::GREEN, "APIキーは有効です");
-            }
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(
+                        &mut self.auto_dictionary,
+                        "1語だけコピーした場合は辞書モードで表示（意味・品詞・例文）",
+                    );
+                });
+
+                // カスタムモードのプロンプト（{text} に原文が入る）
+                ui.label("カスタムプロンプト（出力モードが「カスタム」の場合に使用、{text} が原文に置き換わります）:");
+                ui.add_enabled(
+                    self.output_mode == OutputMode::Custom,
+                    egui::TextEdit::multiline(&mut self.custom_prompt)
+                        .desired_width(f32::INFINITY)
+                        .desired_rows(4),
+                );
+
+                // 生成パラメータ（チェックしない項目はサーバー側の既定値）
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.use_temperature, "温度:");
+                    ui.add_enabled(
+                        self.use_temperature,
+                        egui::Slider::new(&mut self.temperature, 0.0..=2.0).step_by(0.1),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.use_max_output_tokens, "最大出力トークン数:");
+                    ui.add_enabled(
+                        self.use_max_output_tokens,
+                        egui::DragValue::new(&mut self.max_output_tokens)
+                            .range(1..=65536)
+                            .speed(16),
+                    );
+                });
+
+                ui.add_space(10.0);
+
+                // プロファイル（用途ごとにモデル・出力モード・翻訳先を切り替える）
+                ui.horizontal(|ui| {
+                    ui.label("プロファイル:");
+                    egui::ComboBox::from_id_salt("profile_selector")
+                        .selected_text(self.active_profile.as_deref().unwrap_or("なし（上の設定を使用）"))
+                        .width(200.0)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.active_profile, None, "なし（上の設定を使用）");
+                            for name in self.profiles.keys() {
+                                ui.selectable_value(
+                                    &mut self.active_profile,
+                                    Some(name.clone()),
+                                    name,
+                                );
+                            }
+                        });
+                });
 
-            ui.add_space(15.0);
-
-            // 出力モード選択
-            ui.horizontal(|ui| {
-                ui.label("出力モード:");
-                egui::ComboBox::from_id_salt("output_mode_selector")
-                    .selected_text(self.output_mode.label())
-                    .width(300.0)
-                    .show_ui(ui, |ui| {
-                        for mode in OutputMode::all() {
-                            ui.selectable_value(&mut self.output_mode, *mode, mode.label());
+                ui.collapsing("プロファイルの管理", |ui| {
+                    ui.label("上のモデル・出力モード・翻訳先の言語を名前を付けて保存します:");
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.new_profile_name)
+                                .hint_text("仕事用")
+                                .desired_width(150.0),
+                        );
+                        let name = self.new_profile_name.trim().to_string();
+                        if ui
+                            .add_enabled(!name.is_empty(), egui::Button::new("現在の設定を保存"))
+                            .clicked()
+                        {
+                            self.profiles.insert(
+                                name,
+                                Profile {
+                                    model: self.selected_model_id.clone(),
+                                    output_mode: self.output_mode,
+                                    target_language: self.target_language.trim().to_string(),
+                                },
+                            );
+                            self.new_profile_name.clear();
                         }
                     });
-            });
 
-            ui.add_space(15.0);
+                    let mut remove_name = None;
+                    for (name, profile) in &self.profiles {
+                        ui.horizontal(|ui| {
+                            ui.label(format!(
+                                "{}: {} / {} / {}",
+                                name,
+                                profile.model,
+                                profile.output_mode.short_label(),
+                                profile.target_language
+                            ));
+                            if ui.small_button("削除").clicked() {
+                                remove_name = Some(name.clone());
+                            }
+                        });
+                    }
+                    if let Some(name) = remove_name {
+                        self.profiles.remove(&name);
+                        if self.active_profile.as_ref() == Some(&name) {
+                            self.active_profile = None;
+                        }
+                    }
+                });
 
-            // ホットキー設定
-            ui.horizontal(|ui| {
-                ui.label("ホットキー:");
-                let hotkey_text = if self.listening_for_hotkey {
-                    "キーを押してください...".to_string()
-                } else {
-                    self.hotkey.to_string()
-                };
+                ui.horizontal(|ui| {
+                    ui.label("クリップボード:");
+                    egui::ComboBox::from_id_salt("clipboard_backend_selector")
+                        .selected_text(self.clipboard_backend.label())
+                        .show_ui(ui, |ui| {
+                            for backend in ClipboardBackend::all() {
+                                ui.selectable_value(
+                                    &mut self.clipboard_backend,
+                                    *backend,
+                                    backend.label(),
+                                );
+                            }
+                        });
+                });
 
-                if ui.button(&hotkey_text).clicked() {
-                    self.listening_for_hotkey = true;
-                }
+                // URL・パス・コードは誤判定を避けるため、確実に判定できる場合のみ対象にする
+                ui.horizontal(|ui| {
+                    ui.label("URL・コード:");
+                    egui::ComboBox::from_id_salt("special_content_selector")
+                        .selected_text(self.special_content.label())
+                        .show_ui(ui, |ui| {
+                            for action in SpecialContentAction::all() {
+                                ui.selectable_value(
+                                    &mut self.special_content,
+                                    *action,
+                                    action.label(),
+                                );
+                            }
+                        });
+                });
 
-                if ui.button("リセット").clicked() {
-                    self.hotkey = Hotkey::default();
-                    self.listening_for_hotkey = false;
-                }
-            });
+                ui.horizontal(|ui| {
+                    ui.label("エラー通知:");
+                    egui::ComboBox::from_id_salt("error_display_selector")
+                        .selected_text(self.error_display.label())
+                        .show_ui(ui, |ui| {
+                            for display in ErrorDisplay::all() {
+                                ui.selectable_value(
+                                    &mut self.error_display,
+                                    *display,
+                                    display.label(),
+                                );
+                            }
+                        });
+                });
 
-            ui.horizontal(|ui| {
-                ui.add_space(80.0);
-                ui.checkbox(&mut self.hotkey.is_double_press, "ダブルプレス（例: Ctrl+C+C）");
-            });
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(
+                        &mut self.smart_swap,
+                        "スマートスワップ（日本語の入力は直前の原文の言語へ翻訳）",
+                    );
+                });
 
-            ui.add_space(15.0);
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(
+                        &mut self.skip_same_language,
+                        "原文がすでに翻訳先の言語なら翻訳しない（APIを呼ばずにそのまま表示）",
+                    );
+                });
 
-            // 自動スタートアップ設定
-            ui.horizontal(|ui| {
-                ui.label("自動起動:");
-                ui.checkbox(&mut self.startup_enabled, "Windows起動時に自動で起動する");
-            });
+                ui.horizontal(|ui| {
+                    ui.label("言語判定の確認:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.min_detection_confidence)
+                            .range(0..=100)
+                            .suffix(" %未満"),
+                    );
+                    ui.label("（0で確認しない）");
+                });
 
-            ui.add_space(10.0);
-            ui.hyperlink_to(
-                "Google AI Studio でAPIキーを取得",
-                "https://aistudio.google.com/app/apikey",
-            );
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(&mut self.use_language_pair, "双方向翻訳:");
+                    ui.add_enabled(
+                        self.use_language_pair,
+                        egui::TextEdit::singleline(&mut self.language_pair.a).desired_width(80.0),
+                    );
+                    ui.label("⇔");
+                    ui.add_enabled(
+                        self.use_language_pair,
+                        egui::TextEdit::singleline(&mut self.language_pair.b).desired_width(80.0),
+                    );
+                });
 
-            ui.add_space(20.0);
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(
+                        &mut self.preserve_markdown,
+                        "Markdownの構造（見出し・リスト・コード）を保って翻訳",
+                    );
+                });
 
-            if let Some(error) = &self.error_message {
-                ui.colored_label(egui::Color32::RED, error);
-                ui.add_space(10.0);
-            }
+                ui.horizontal(|ui| {
+                    ui.label("折りたたみ:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.collapse_after_lines)
+                            .range(0..=200)
+                            .suffix(" 行"),
+                    );
+                    ui.label("（0で全文表示）");
+                });
 
-            if self.saved {
-                ui.colored_label(
-                    egui::Color32::GREEN,
-                    "設定を保存しました。アプリケーションを再起動してください。",
-                );
-                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    ui.label("プレーン表示:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.plain_text_threshold)
+                            .range(0..=1_000_000)
+                            .speed(100)
+                            .suffix(" 文字超"),
+                    );
+                    ui.label("（長い結果はMarkdownを解析しない。0で無効）");
+                });
 
-                if ui.button("閉じる").clicked() {
-                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-                }
-            } else {
                 ui.horizontal(|ui| {
-                    if ui.button("保存").clicked() {
-                        if self.api_key.trim().is_empty() {
-                            self.error_message = Some("APIキーを入力してください".to_string());
+                    ui.add_space(80.0);
+                    ui.checkbox(
+                        &mut self.render_markdown,
+                        "結果をMarkdownとして表示（オフで常にプレーンテキスト）",
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(
+                        &mut self.focus_result_window,
+                        "結果ウィンドウにフォーカスを移す",
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(&mut self.always_on_top, "結果ウィンドウを常に最前面に表示");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(
+                        &mut self.title_shows_languages,
+                        "ウィンドウタイトルに翻訳方向を表示（例: EN→JA）",
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(
+                        &mut self.stream_responses,
+                        "生成途中の翻訳を逐次表示する",
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(
+                        &mut self.edit_before_translate,
+                        "翻訳する前に原文を確認・編集する",
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(&mut self.auto_copy, "翻訳結果を自動でクリップボードにコピー");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(
+                        &mut self.silent,
+                        "ウィンドウを表示せず通知で知らせる（サイレント）",
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(
+                        &mut self.auto_translate_on_copy,
+                        "テキストをコピーするたびに自動で翻訳する（ホットキー不要）",
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(
+                        &mut self.preview_first,
+                        "まず簡潔な翻訳をツールチップで表示（クリックで詳細）",
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(
+                        &mut self.reuse_window,
+                        "結果ウィンドウを開いたままにし、次の翻訳も同じウィンドウに表示",
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(
+                        &mut self.click_through_until_hover,
+                        "マウスが乗るまでクリックを透過する",
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("テーマ:");
+                    let before = self.theme;
+                    egui::ComboBox::from_id_salt("theme_selector")
+                        .selected_text(self.theme.label())
+                        .show_ui(ui, |ui| {
+                            for theme in Theme::all() {
+                                ui.selectable_value(&mut self.theme, *theme, theme.label());
+                            }
+                        });
+                    // 設定画面にもその場で反映する
+                    if self.theme != before {
+                        apply_theme(ctx, self.theme);
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("文字サイズ:");
+                    if ui
+                        .add(egui::Slider::new(&mut self.font_size, 12.0..=28.0).step_by(0.5))
+                        .changed()
+                    {
+                        apply_font_size(ctx, self.font_size);
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("アクセントカラー:");
+                    let toggled = ui.checkbox(&mut self.use_accent_color, "").changed();
+                    let picked = ui
+                        .add_enabled_ui(self.use_accent_color, |ui| {
+                            ui.color_edit_button_srgb(&mut self.accent_color).changed()
+                        })
+                        .inner;
+                    // 設定画面にもその場で反映する
+                    if toggled || picked {
+                        if self.use_accent_color {
+                            apply_accent_color(ctx, Some(self.accent_color));
                         } else {
-                            let config = Config {
-                                api_key: self.api_key.clone(),
-                                model: self.selected_model_id.clone(),
-                                output_mode: self.output_mode,
-                                hotkey: self.hotkey,
-                            };
+                            ctx.all_styles_mut(|style| {
+                                style.visuals = if style.visuals.dark_mode {
+                                    egui::Visuals::dark()
+                                } else {
+                                    egui::Visuals::light()
+                                };
+                            });
+                        }
+                    }
+                });
 
-                            match config::save(&config) {
-                                Ok(_) => {
-                                    // スタートアップ設定を適用
-                                    let startup_result = if self.startup_enabled {
-                                        crate::startup::install_startup()
-                                    } else {
-                                        crate::startup::uninstall_startup()
-                                    };
+                ui.horizontal(|ui| {
+                    ui.label("実行中の通知:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.busy_notice_interval_secs)
+                            .range(0..=600)
+                            .suffix(" 秒ごと"),
+                    );
+                    ui.label("（0で翻訳1回につき1度だけ）");
+                });
 
-                                    if let Err(e) = startup_result {
-                                        self.error_message = Some(format!("スタートアップ設定エラー: {}", e));
-                                    } else {
-                                        self.saved = true;
-                                        self.error_message = None;
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(
+                        &mut self.queue_requests,
+                        "翻訳中に押したホットキーは順番待ちにして、前の翻訳の後で翻訳する",
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("翻訳履歴:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.history_limit)
+                            .range(0..=10000)
+                            .suffix(" 件まで保存"),
+                    );
+                    ui.label("（0で記録しない、--history で表示）");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("結果のキャッシュ:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.cache_size)
+                            .range(0..=10000)
+                            .suffix(" 件まで保存"),
+                    );
+                    ui.label("（0でキャッシュしない、--clear-cache で削除）");
+                });
+
+                // 通信が遅い環境向けのタイムアウト・リトライ設定
+                ui.collapsing("詳細設定", |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("タイムアウト:");
+                        ui.add(
+                            egui::DragValue::new(&mut self.timeout_secs)
+                                .range(5..=600)
+                                .suffix(" 秒"),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("最大試行回数:");
+                        ui.add(
+                            egui::DragValue::new(&mut self.max_retries)
+                                .range(1..=10)
+                                .suffix(" 回"),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("リトライ間隔:");
+                        ui.add(
+                            egui::DragValue::new(&mut self.retry_delay_ms)
+                                .range(0..=60000)
+                                .speed(100)
+                                .suffix(" ms"),
+                        );
+                        ui.label("（再試行のたびに間隔を伸ばします）");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("長文の分割:");
+                        ui.add(
+                            egui::DragValue::new(&mut self.chunk_chars)
+                                .range(0..=100000)
+                                .speed(100)
+                                .suffix(" 文字ごと"),
+                        );
+                        ui.label("（0で分割しない）");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("逐次表示のバッファ:");
+                        ui.add(
+                            egui::DragValue::new(&mut self.stream_channel_capacity)
+                                .range(1..=4096)
+                                .suffix(" 件"),
+                        );
+                        ui.label("（満杯の間は届いた差分をまとめて表示します）");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("送信前の確認:");
+                        ui.add(
+                            egui::DragValue::new(&mut self.max_chars)
+                                .range(0..=1000000)
+                                .speed(100)
+                                .suffix(" 文字を超える場合"),
+                        );
+                        ui.label("（0で確認しない）");
+                    });
+                });
+
+                ui.add_space(15.0);
+
+                // ホットキー設定
+                ui.horizontal(|ui| {
+                    ui.label("ホットキー:");
+                    let hotkey_text = if self.listening_for == Some(HotkeyTarget::Translate) {
+                        "キーまたはマウスのサイドボタンを押してください...".to_string()
+                    } else {
+                        self.hotkey.to_string()
+                    };
+
+                    if ui.button(&hotkey_text).clicked() {
+                        self.listening_for = Some(HotkeyTarget::Translate);
+                    }
+
+                    if ui.button("リセット").clicked() {
+                        self.hotkey = Hotkey::default();
+                        self.listening_for = None;
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.checkbox(&mut self.hotkey.is_double_press, "ダブルプレス（例: Ctrl+C+C）");
+                });
+
+                if self.hotkey.is_double_press {
+                    ui.horizontal(|ui| {
+                        ui.add_space(80.0);
+                        ui.label("押す間隔:");
+                        ui.add(
+                            egui::Slider::new(&mut self.double_press_window_ms, 200..=1000)
+                                .step_by(50.0)
+                                .suffix(" ms以内"),
+                        );
+                    });
+                }
+
+                // すべて中止（リセット）用ホットキー
+                ui.horizontal(|ui| {
+                    ui.label("すべて中止:");
+                    let reset_text = if self.listening_for == Some(HotkeyTarget::Reset) {
+                        "キーまたはマウスのサイドボタンを押してください...".to_string()
+                    } else {
+                        self.reset_hotkey
+                            .map(|hk| hk.to_string())
+                            .unwrap_or_else(|| "未設定".to_string())
+                    };
+
+                    if ui.button(&reset_text).clicked() {
+                        self.listening_for = Some(HotkeyTarget::Reset);
+                    }
+
+                    if self.reset_hotkey.is_some() && ui.button("解除").clicked() {
+                        self.reset_hotkey = None;
+                        self.listening_for = None;
+                    }
+                });
+
+                // 一時停止・再開用ホットキー
+                ui.horizontal(|ui| {
+                    ui.label("一時停止/再開:");
+                    let pause_text = if self.listening_for == Some(HotkeyTarget::Pause) {
+                        "キーまたはマウスのサイドボタンを押してください...".to_string()
+                    } else {
+                        self.pause_hotkey
+                            .map(|hk| hk.to_string())
+                            .unwrap_or_else(|| "未設定".to_string())
+                    };
+
+                    if ui.button(&pause_text).clicked() {
+                        self.listening_for = Some(HotkeyTarget::Pause);
+                    }
+
+                    if self.pause_hotkey.is_some() && ui.button("解除").clicked() {
+                        self.pause_hotkey = None;
+                        self.listening_for = None;
+                    }
+                });
+
+                // 通常のショートカットを潰してしまう組み合わせを警告する
+                let conflicts = [
+                    ("ホットキー", Some(self.hotkey)),
+                    ("すべて中止", self.reset_hotkey),
+                    ("一時停止/再開", self.pause_hotkey),
+                ];
+                for (name, hotkey) in conflicts {
+                    if let Some(shortcut) = hotkey.and_then(|hk| hk.conflicts_with_system()) {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            format!("⚠ {}が{}と重なっています", name, shortcut),
+                        );
+                    }
+                }
+
+                ui.add_space(15.0);
+
+                // 共有コンテキスト（用語集・例文など）
+                ui.collapsing("共有コンテキスト", |ui| {
+                    ui.label("毎回の翻訳に付ける用語集・例文・指示など:");
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.shared_context)
+                            .desired_rows(4)
+                            .desired_width(f32::INFINITY),
+                    );
+                    ui.horizontal(|ui| {
+                        ui.label("キャッシュ保持時間:");
+                        ui.add(
+                            egui::DragValue::new(&mut self.context_cache_ttl_mins)
+                                .range(0..=1440)
+                                .suffix(" 分"),
+                        );
+                        ui.label("（0でキャッシュしない）");
+                    });
+                });
+
+                ui.add_space(15.0);
+
+                // 翻訳結果の送信先アプリ
+                ui.collapsing("送信先アプリ", |ui| {
+                    ui.checkbox(
+                        &mut self.use_send_to_app,
+                        "翻訳結果を指定したアプリに貼り付ける",
+                    );
+                    ui.add_enabled_ui(self.use_send_to_app, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("ウィンドウタイトル:");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.send_to_app.window_title)
+                                    .hint_text("例: メモ帳")
+                                    .desired_width(220.0),
+                            );
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("プロセス名:");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.send_to_app.process_name)
+                                    .hint_text("例: notepad.exe")
+                                    .desired_width(220.0),
+                            );
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("未起動時に実行:");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.send_to_app.launch_command)
+                                    .hint_text("空欄の場合は通知のみ")
+                                    .desired_width(220.0),
+                            );
+                        });
+                    });
+                });
+
+                ui.add_space(15.0);
+
+                // 用語集（人名・製品名などの固定の訳語）
+                ui.collapsing("用語集", |ui| {
+                    ui.label("常にこの訳語で翻訳する用語:");
+                    let mut remove_index = None;
+                    for (i, (term, translation)) in self.glossary.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(term)
+                                    .hint_text("原語")
+                                    .desired_width(160.0),
+                            );
+                            ui.label("→");
+                            ui.add(
+                                egui::TextEdit::singleline(translation)
+                                    .hint_text("訳語")
+                                    .desired_width(160.0),
+                            );
+                            if ui.small_button("削除").clicked() {
+                                remove_index = Some(i);
+                            }
+                        });
+                    }
+                    if let Some(i) = remove_index {
+                        self.glossary.remove(i);
+                    }
+                    if ui.button("追加").clicked() {
+                        self.glossary.push((String::new(), String::new()));
+                    }
+                });
+
+                ui.add_space(15.0);
+
+                // コピー元のアプリごとの出力モード（エディタでは直訳、チャットでは解説付きなど）
+                ui.collapsing("アプリごとの設定", |ui| {
+                    ui.label("コピー元のアプリ（実行ファイル名）ごとに出力モードを変えます:");
+                    let mut remove_index = None;
+                    for (i, (name, rule)) in self.app_rules.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(name)
+                                    .hint_text("code.exe")
+                                    .desired_width(140.0),
+                            );
+                            egui::ComboBox::from_id_salt(("app_rule_mode", i))
+                                .selected_text(rule.output_mode.short_label())
+                                .show_ui(ui, |ui| {
+                                    for mode in OutputMode::all() {
+                                        ui.selectable_value(
+                                            &mut rule.output_mode,
+                                            *mode,
+                                            mode.label(),
+                                        );
                                     }
-                                }
-                                Err(e) => {
-                                    self.error_message = Some(format!("保存エラー: {}", e));
-                                }
+                                });
+                            if ui.small_button("削除").clicked() {
+                                remove_index = Some(i);
                             }
+                        });
+                        if rule.output_mode == OutputMode::Custom {
+                            let prompt = rule.custom_prompt.get_or_insert_with(String::new);
+                            ui.add(
+                                egui::TextEdit::multiline(prompt)
+                                    .hint_text("空欄の場合は基本設定のカスタムプロンプト（{text} に原文が入ります）")
+                                    .desired_rows(3)
+                                    .desired_width(f32::INFINITY),
+                            );
                         }
                     }
+                    if let Some(i) = remove_index {
+                        self.app_rules.remove(i);
+                    }
+                    if ui.button("追加").clicked() {
+                        self.app_rules.push((
+                            String::new(),
+                            AppRule {
+                                output_mode: OutputMode::Concise,
+                                custom_prompt: None,
+                            },
+                        ));
+                    }
+                });
 
-                    if ui.button("キャンセル").clicked() {
-                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                ui.add_space(15.0);
+
+                // 追加HTTPヘッダー（社内ゲートウェイ経由の場合など）
+                ui.collapsing("追加HTTPヘッダー", |ui| {
+                    ui.label("すべてのAPIリクエストに付けるヘッダー:");
+                    let mut remove_index = None;
+                    for (i, (name, value)) in self.extra_headers.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(name)
+                                    .hint_text("名前")
+                                    .desired_width(140.0),
+                            );
+                            ui.add(
+                                egui::TextEdit::singleline(value)
+                                    .hint_text("値")
+                                    .desired_width(220.0),
+                            );
+                            if ui.small_button("削除").clicked() {
+                                remove_index = Some(i);
+                            }
+                        });
+                    }
+                    if let Some(i) = remove_index {
+                        self.extra_headers.remove(i);
+                    }
+                    if ui.button("追加").clicked() {
+                        self.extra_headers.push((String::new(), String::new()));
                     }
                 });
-            }
+
+                ui.add_space(15.0);
+
+                // 先頭・末尾の定型文の除去ルール
+                ui.collapsing("先頭・末尾の定型文を除去", |ui| {
+                    ui.label("結果の最初・最後の行がこの文と一致する場合、その行を取り除きます:");
+                    let mut remove_index = None;
+                    for (i, rule) in self.disclaimer_rules.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut rule.enabled, "");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut rule.pattern).desired_width(300.0),
+                            );
+                            if ui.small_button("削除").clicked() {
+                                remove_index = Some(i);
+                            }
+                        });
+                    }
+                    if let Some(i) = remove_index {
+                        self.disclaimer_rules.remove(i);
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("追加").clicked() {
+                            self.disclaimer_rules.push(DisclaimerRule::new(""));
+                        }
+                        if ui.button("既定に戻す").clicked() {
+                            self.disclaimer_rules = config::default_disclaimer_rules();
+                        }
+                    });
+                });
+
+                ui.add_space(15.0);
+
+                // 自動スタートアップ設定
+                ui.horizontal(|ui| {
+                    ui.label("自動起動:");
+                    ui.checkbox(&mut self.startup_enabled, "Windows起動時に自動で起動する");
+                });
+
+                // 管理者権限のウィンドウでもホットキーを検知するにはタスクスケジューラで登録する
+                ui.horizontal(|ui| {
+                    ui.add_space(80.0);
+                    ui.add_enabled_ui(self.startup_enabled, |ui| {
+                        egui::ComboBox::from_id_salt("startup_method")
+                            .selected_text(self.startup_method.label())
+                            .show_ui(ui, |ui| {
+                                for method in StartupMethod::all() {
+                                    ui.selectable_value(&mut self.startup_method, *method, method.label());
+                                }
+                            });
+                    });
+                });
+                if self.startup_enabled && self.startup_method == StartupMethod::TaskScheduler {
+                    ui.horizontal(|ui| {
+                        ui.add_space(80.0);
+                        ui.weak("登録・解除には管理者として設定画面を開く必要があります");
+                    });
+                }
+
+                ui.add_space(10.0);
+                ui.hyperlink_to(
+                    "Google AI Studio でAPIキーを取得",
+                    "https://aistudio.google.com/app/apikey",
+                );
+
+                // 画面にない詳細設定は config.json を直接編集する
+                if ui.button("設定フォルダを開く").clicked() {
+                    if let Err(e) = config::open_config_dir() {
+                        self.error_message = Some(format!("設定フォルダを開けませんでした: {}", e));
+                    }
+                }
+
+                ui.add_space(20.0);
+
+                if let Some(error) = &self.error_message {
+                    ui.colored_label(egui::Color32::RED, error);
+                    ui.add_space(10.0);
+                }
+
+                if self.saved {
+                    ui.colored_label(
+                        egui::Color32::GREEN,
+                        "設定を保存しました。アプリケーションを再起動してください。",
+                    );
+                    ui.add_space(10.0);
+
+                    if ui.button("閉じる").clicked() {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                } else {
+                    ui.horizontal(|ui| {
+                        if ui.button("保存").on_hover_text("Ctrl+S").clicked() {
+                            self.save_config();
+                        }
+
+                        if ui.button("キャンセル").clicked() {
+                            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                        }
+                    });
+                }
+            });
         });
 
         // ローディング中は定期的に再描画
-        if matches!(self.models, ModelLoadState::Loading) {
+        if self.model_receiver.is_some() || self.test_receiver.is_some() {
             ctx.request_repaint();
         }
     }
@@ -435,8 +1904,12 @@ pub fn show_setup_window() -> Result<()> {
         "Clipboard Translator Setup",
         options,
         Box::new(|cc| {
-            setup_japanese_fonts(&cc.egui_ctx);
-            Ok(Box::new(SetupApp::new()))
+            let app = SetupApp::new();
+            setup_fonts(&cc.egui_ctx, &app.base_config.extra_fonts);
+            apply_theme(&cc.egui_ctx, app.base_config.theme);
+            apply_font_size(&cc.egui_ctx, app.base_config.font_size);
+            apply_accent_color(&cc.egui_ctx, app.base_config.accent_color);
+            Ok(Box::new(app))
         }),
     )
     .map_err(|e| anyhow::anyhow!("Failed to run setup window: {}", e))?;